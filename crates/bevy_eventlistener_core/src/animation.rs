@@ -0,0 +1,167 @@
+//! Bubbling events sent when an [`AnimationPlayer`] finishes or loops its animation.
+//!
+//! Add the [`AnimationEventsPlugin`] to send an [`AnimationFinished`] event when an animation that
+//! does not repeat reaches its end, and an [`AnimationLooped`] event every time a repeating
+//! animation starts over. These events target the entity with the [`AnimationPlayer`], and bubble
+//! up the hierarchy like any other [`EntityEvent`], so a parent game object can chain behavior
+//! when an animation ends, instead of polling the state of the player every frame.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     animation::{AnimationEventsPlugin, AnimationFinished},
+//! #     callbacks::Listener,
+//! #     event_listener::On,
+//! # };
+//! # use bevy_animation::AnimationPlayer;
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! fn spawn_door(mut commands: Commands) {
+//!     commands.spawn((
+//!         AnimationPlayer::default(),
+//!         On::<AnimationFinished>::run(|finished: Listener<AnimationFinished>| {
+//!             println!("the door of {:?} is open", finished.target);
+//!         }),
+//!     ));
+//! }
+//!
+//! App::new()
+//!     .add_plugins(AnimationEventsPlugin)
+//!     .add_systems(Startup, spawn_door);
+//! ```
+
+use bevy_animation::{AnimationClip, AnimationPlayer};
+use bevy_app::prelude::*;
+use bevy_asset::{Assets, Handle, HandleId};
+use bevy_ecs::prelude::*;
+use bevy_reflect::GetPath;
+use bevy_utils::HashMap;
+
+use crate::{event_listener::EntityEvent, EventListenerPlugin, EventListenerSet};
+
+macro_rules! animation_event {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, PartialEq, Eq, Debug, Event)]
+        pub struct $name {
+            /// The entity with the [`AnimationPlayer`].
+            pub target: Entity,
+            /// The animation that was playing.
+            pub clip: Handle<AnimationClip>,
+        }
+
+        impl EntityEvent for $name {
+            fn target(&self) -> Entity {
+                self.target
+            }
+        }
+    };
+}
+
+animation_event!(
+    /// Sent when an animation that does not repeat reaches its end, see the [module docs](self).
+    AnimationFinished
+);
+animation_event!(
+    /// Sent when a repeating animation reaches its end and starts over, see the
+    /// [module docs](self).
+    AnimationLooped
+);
+
+/// Adds an [`EventListenerPlugin`] for [`AnimationFinished`] and [`AnimationLooped`], and sends
+/// them when an [`AnimationPlayer`] finishes or loops, see the [module docs](self).
+///
+/// The events are sent in the [`PreUpdate`] schedule, before the [`EventListenerSet`]. Animations
+/// are advanced in the [`PostUpdate`] schedule, so the events are dispatched at the start of the
+/// next frame.
+pub struct AnimationEventsPlugin;
+
+impl Plugin for AnimationEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            EventListenerPlugin::<AnimationFinished>::default(),
+            EventListenerPlugin::<AnimationLooped>::default(),
+        ))
+        .add_systems(PreUpdate, send_animation_events.before(EventListenerSet));
+    }
+}
+
+/// The progress of the animation of a player, when [`send_animation_events`] last ran.
+struct Progress {
+    clip: HandleId,
+    finished: bool,
+    cycle: i64,
+}
+
+/// Send the events for every [`AnimationPlayer`] that finished or looped since the last time this
+/// system ran.
+///
+/// `bevy_animation` does not expose which clip a player is playing, or whether it repeats, so they
+/// are read through reflection. While an animation plays in reverse, it finishes once it is back
+/// at its start.
+fn send_animation_events(
+    players: Query<(Entity, &AnimationPlayer), Changed<AnimationPlayer>>,
+    mut removed: RemovedComponents<AnimationPlayer>,
+    clips: Option<Res<Assets<AnimationClip>>>,
+    mut previous: Local<HashMap<Entity, Progress>>,
+    mut finished_events: EventWriter<AnimationFinished>,
+    mut looped_events: EventWriter<AnimationLooped>,
+) {
+    for entity in removed.iter() {
+        previous.remove(&entity);
+    }
+    let Some(clips) = clips else {
+        return;
+    };
+    for (target, player) in &players {
+        let (Ok(clip), Ok(&repeat)) = (
+            player.path::<Handle<AnimationClip>>("animation.animation_clip"),
+            player.path::<bool>("animation.repeat"),
+        ) else {
+            continue;
+        };
+        let Some(duration) = clips.get(clip).map(AnimationClip::duration) else {
+            continue;
+        };
+        let elapsed = player.elapsed();
+        let forward = player.speed() >= 0.0;
+        let finished = !repeat
+            && if forward {
+                elapsed >= duration
+            } else {
+                elapsed <= 0.0
+            };
+        let cycle = if duration > 0.0 {
+            (elapsed / duration).floor() as i64
+        } else {
+            0
+        };
+        let progress = Progress {
+            clip: clip.id(),
+            finished,
+            cycle,
+        };
+        let old = previous
+            .insert(target, progress)
+            .filter(|old| old.clip == clip.id());
+        if finished && !old.as_ref().is_some_and(|old| old.finished) {
+            finished_events.send(AnimationFinished {
+                target,
+                clip: clip.clone(),
+            });
+        }
+        // A restarted animation goes back to its first cycle, which is not a loop.
+        let looped = old.is_some_and(|old| {
+            if forward {
+                cycle > old.cycle
+            } else {
+                cycle < old.cycle
+            }
+        });
+        if repeat && looped {
+            looped_events.send(AnimationLooped {
+                target,
+                clip: clip.clone(),
+            });
+        }
+    }
+}
@@ -0,0 +1,181 @@
+//! Listeners defined in asset files, which are rebuilt when the asset is reloaded.
+//!
+//! A [`ListenerAsset`] is a list of callbacks registered in a [`CallbackRegistry`], along with the
+//! [`ListenerParams`] used to construct each of them. It is loaded from `.listeners.ron` files like
+//! this one:
+//!
+//! ```ron
+//! (
+//!     listeners: [
+//!         (callback: "open_door"),
+//!         (callback: "play_sound", params: { "sound": "creak.ogg", "volume": "0.5" }),
+//!     ],
+//! )
+//! ```
+//!
+//! Adding an [`AssetListeners<E>`] component to an entity adds each of the listeners in the asset
+//! to the entity's [`Listeners<E>`](crate::event_listener::Listeners). When the asset changes, for
+//! example because it was edited while the app is running with asset hot reloading enabled, the
+//! listeners are removed and constructed again from the new definitions. This allows designers to
+//! change how entities respond to events without recompiling.
+
+use bevy_app::prelude::*;
+use bevy_asset::{AddAsset, AssetEvent, AssetLoader, Assets, Handle, LoadContext, LoadedAsset};
+use bevy_ecs::prelude::*;
+use bevy_reflect::{TypePath, TypeUuid};
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::error;
+use bevy_utils::{BoxedFuture, HashSet};
+use serde::Deserialize;
+
+use crate::{
+    callback_registry::{CallbackRegistry, ListenerParams},
+    event_listener::{AddListener, EntityEvent, ListenerId, RemoveListener},
+    EventListenerSet,
+};
+
+/// Adds the [`ListenerAsset`] asset type and loader, and rebuilds the listeners of every
+/// [`AssetListeners<E>`] when its asset is loaded or changed. This requires the `AssetPlugin`.
+///
+/// The listeners are rebuilt in the [`PreUpdate`] schedule, before the [`EventListenerSet`].
+pub struct AssetListenerPlugin<E> {
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E> Default for AssetListenerPlugin<E> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent> Plugin for AssetListenerPlugin<E> {
+    fn build(&self, app: &mut App) {
+        // The asset type is shared by every event type.
+        if !app.world.contains_resource::<Assets<ListenerAsset>>() {
+            app.add_asset::<ListenerAsset>()
+                .init_asset_loader::<ListenerAssetLoader>();
+        }
+        app.init_resource::<CallbackRegistry<E>>().add_systems(
+            PreUpdate,
+            (AssetListeners::<E>::rebuild, apply_deferred)
+                .chain()
+                .before(EventListenerSet),
+        );
+    }
+}
+
+/// A list of listener definitions, loaded from a `.listeners.ron` file.
+#[derive(Clone, Debug, Deserialize, TypeUuid, TypePath)]
+#[uuid = "c2f4d2b6-6b8e-4f39-9a53-2f1b8f6a7d41"]
+pub struct ListenerAsset {
+    /// The listeners to add to each entity using this asset.
+    pub listeners: Vec<ListenerDefinition>,
+}
+
+/// A listener defined in a [`ListenerAsset`].
+#[derive(Clone, Debug, Deserialize)]
+pub struct ListenerDefinition {
+    /// The name the callback was registered under in the [`CallbackRegistry`].
+    pub callback: String,
+    /// The parameters used to construct the listener.
+    #[serde(default)]
+    pub params: ListenerParams,
+}
+
+/// Loads a [`ListenerAsset`] from a RON file with the `.listeners.ron` extension.
+#[derive(Default)]
+pub struct ListenerAssetLoader;
+
+impl AssetLoader for ListenerAssetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy_asset::Error>> {
+        Box::pin(async move {
+            let asset = ron::de::from_bytes::<ListenerAsset>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(asset));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["listeners.ron"]
+    }
+}
+
+/// A component that adds the listeners defined in a [`ListenerAsset`] to its entity, for events
+/// of type `E`. The listeners are rebuilt when the asset changes, or when the handle is replaced.
+///
+/// Listeners are added to the entity's [`Listeners<E>`](crate::event_listener::Listeners), so they
+/// do not replace the entity's other listeners.
+#[derive(Component)]
+pub struct AssetListeners<E: EntityEvent> {
+    /// The asset containing the listener definitions.
+    pub handle: Handle<ListenerAsset>,
+    /// The listeners currently added to the entity from the asset.
+    ids: Vec<ListenerId>,
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E: EntityEvent> AssetListeners<E> {
+    /// Create a component that adds the listeners defined in the asset with the given `handle`.
+    pub fn new(handle: Handle<ListenerAsset>) -> Self {
+        Self {
+            handle,
+            ids: Vec::new(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Rebuild the listeners of every [`AssetListeners<E>`] that was added or changed, or whose
+    /// asset was loaded or modified.
+    pub fn rebuild(
+        mut commands: Commands,
+        registry: Res<CallbackRegistry<E>>,
+        assets: Res<Assets<ListenerAsset>>,
+        mut asset_events: EventReader<AssetEvent<ListenerAsset>>,
+        mut modified: Local<HashSet<Handle<ListenerAsset>>>,
+        mut listeners: Query<(Entity, &mut AssetListeners<E>)>,
+    ) {
+        modified.clear();
+        for event in asset_events.iter() {
+            if let AssetEvent::Created { handle } | AssetEvent::Modified { handle } = event {
+                modified.insert(handle.clone_weak());
+            }
+        }
+        for (entity, mut asset_listeners) in &mut listeners {
+            if !asset_listeners.is_changed() && !modified.contains(&asset_listeners.handle) {
+                continue;
+            }
+            let Some(asset) = assets.get(&asset_listeners.handle) else {
+                // The listeners will be built once the asset has loaded.
+                continue;
+            };
+            // Updating the ids must not count as a change, or the listeners would be rebuilt again.
+            let asset_listeners = asset_listeners.bypass_change_detection();
+            for id in asset_listeners.ids.drain(..) {
+                commands.add(RemoveListener::<E>::new(entity, id));
+            }
+            for definition in &asset.listeners {
+                let Some(listener) =
+                    registry.get_with_params(&definition.callback, &definition.params)
+                else {
+                    #[cfg(feature = "trace")]
+                    error!(
+                        "No callback named {:?} is registered for event {:?}, on entity {:?}",
+                        definition.callback,
+                        std::any::type_name::<E>(),
+                        entity,
+                    );
+                    continue;
+                };
+                let add = AddListener::new(entity, listener);
+                asset_listeners.ids.push(add.id());
+                commands.add(add);
+            }
+        }
+    }
+}
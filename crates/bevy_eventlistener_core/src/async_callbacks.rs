@@ -0,0 +1,57 @@
+//! Support for callbacks that run asynchronously on the [`AsyncComputeTaskPool`].
+
+use bevy_ecs::{prelude::*, system::CommandQueue};
+use bevy_tasks::{AsyncComputeTaskPool, Task, TaskPool};
+use futures_lite::future;
+
+/// The tasks spawned by [`On::run_async`](crate::event_listener::On::run_async) callbacks that have
+/// not finished yet.
+///
+/// Each task produces a [`CommandQueue`] that is applied to the world once the task is done.
+#[derive(Resource, Default)]
+pub struct AsyncCallbackTasks {
+    tasks: Vec<Task<CommandQueue>>,
+}
+
+impl AsyncCallbackTasks {
+    /// Spawn a task on the [`AsyncComputeTaskPool`], that will have its resulting commands applied
+    /// to the world when it finishes.
+    pub fn spawn(
+        &mut self,
+        task: impl std::future::Future<Output = CommandQueue> + Send + 'static,
+    ) {
+        let pool = AsyncComputeTaskPool::init(TaskPool::default);
+        self.tasks.push(pool.spawn(task));
+    }
+
+    /// The number of tasks that have not finished yet.
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    /// Returns `true` if there are no unfinished tasks.
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Apply the commands of any tasks that have finished. Tasks that are still running are polled
+    /// again the next time this system runs.
+    pub fn apply_finished(world: &mut World) {
+        world.resource_scope(|world, mut callbacks: Mut<AsyncCallbackTasks>| {
+            // Keep the resource untouched when there is nothing to do, to avoid change detection.
+            if !callbacks.tasks.iter().any(|task| task.is_finished()) {
+                return;
+            }
+            let mut i = 0;
+            while i < callbacks.tasks.len() {
+                if callbacks.tasks[i].is_finished() {
+                    let task = callbacks.tasks.swap_remove(i);
+                    let mut commands = future::block_on(task);
+                    commands.apply(world);
+                } else {
+                    i += 1;
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,337 @@
+//! Handling the errors returned by fallible callbacks, and the panics of callbacks.
+//!
+//! Callbacks created with [`On::run_fallible`](crate::event_listener::On::run_fallible) return a
+//! `Result`. When a callback returns an error, it is passed to the [`CallbackErrorHandler<E>`] of
+//! the event type as a [`CallbackError<E>`], once the callback has finished running. The handler is
+//! chosen with [`EventListenerPlugin::on_callback_error`](crate::EventListenerPlugin::on_callback_error),
+//! and logs the error by default.
+//!
+//! A callback that panics normally aborts the dispatch of its event, and takes down the app. When
+//! [`PanicIsolation<E>`] is enabled with
+//! [`EventListenerPlugin::isolate_panics`](crate::EventListenerPlugin::isolate_panics), panics are
+//! caught instead, and passed to the [`CallbackErrorHandler<E>`] like an error, see
+//! [`PanicIsolation`].
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callback_errors::CallbackErrorHandler,
+//! #     event_listener::{EntityEvent, On},
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Clone, Event)]
+//! # struct Save {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Save {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! #[derive(Clone, Event)]
+//! struct SaveFailed {
+//!     target: Entity,
+//!     reason: String,
+//! }
+//!
+//! let mut app = App::new();
+//! app.add_plugins(EventListenerPlugin::<Save>::default().on_callback_error(
+//!     CallbackErrorHandler::Custom(|world, error| {
+//!         world.send_event(SaveFailed {
+//!             target: error.listener,
+//!             reason: error.error.to_string(),
+//!         });
+//!     }),
+//! ))
+//! .add_event::<SaveFailed>();
+//!
+//! let document = app
+//!     .world
+//!     .spawn(On::<Save>::run_fallible(|| -> Result<(), String> {
+//!         Err("disk full".into())
+//!     }))
+//!     .id();
+//! app.world.send_event(Save { target: document });
+//! app.update();
+//!
+//! let failures = app.world.resource::<Events<SaveFailed>>();
+//! let failure = failures.iter_current_update_events().next().unwrap();
+//! assert_eq!(failure.target, document);
+//! assert_eq!(failure.reason, "disk full");
+//! ```
+
+use std::{
+    any::Any,
+    error::Error,
+    panic::{catch_unwind, AssertUnwindSafe},
+};
+
+use bevy_ecs::prelude::*;
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::error;
+
+use crate::{
+    callbacks::{ListenerInput, ListenerInputs},
+    event_listener::EntityEvent,
+};
+
+/// An error returned by a fallible callback, see the [module docs](self).
+#[derive(Debug)]
+pub struct CallbackError<E> {
+    /// The error returned by the callback.
+    pub error: Box<dyn Error + Send + Sync>,
+    /// The event that triggered the callback, as it was when the callback ran.
+    pub event: E,
+    /// The entity the event was propagating from.
+    pub target: Entity,
+    /// The entity the listener of the callback is on.
+    pub listener: Entity,
+    /// Did the callback panic, instead of returning an error? See [`PanicIsolation`].
+    pub panicked: bool,
+}
+
+/// What to do with the errors returned by fallible callbacks of events of type `E`. This resource
+/// is added by the [`EventListenerPlugin`](crate::EventListenerPlugin), see
+/// [`EventListenerPlugin::on_callback_error`](crate::EventListenerPlugin::on_callback_error).
+#[derive(Resource)]
+pub enum CallbackErrorHandler<E> {
+    /// Log the error. This is the default.
+    Log,
+    /// Panic with the error.
+    Panic,
+    /// Panic with the error in debug builds, and log it in release builds.
+    DebugPanic,
+    /// Call a function with the error, for example to send an event about the failure.
+    Custom(fn(&mut World, CallbackError<E>)),
+}
+
+impl<E> Clone for CallbackErrorHandler<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for CallbackErrorHandler<E> {}
+
+impl<E> CallbackError<E> {
+    /// Describes how the callback failed, for messages.
+    fn outcome(&self) -> &'static str {
+        if self.panicked {
+            "panicked"
+        } else {
+            "failed"
+        }
+    }
+}
+
+impl<E: EntityEvent> CallbackErrorHandler<E> {
+    /// Handle the `error` with the [`CallbackErrorHandler<E>`] of the `world`, or log it if there
+    /// is none.
+    pub fn handle(world: &mut World, error: CallbackError<E>) {
+        let handler = world
+            .get_resource::<CallbackErrorHandler<E>>()
+            .copied()
+            .unwrap_or(Self::Log);
+        match handler {
+            Self::Log => log_error(&error),
+            Self::DebugPanic if !cfg!(debug_assertions) => log_error(&error),
+            Self::Panic | Self::DebugPanic => panic!(
+                "Callback for event {:?} on {:?} {}: {}",
+                std::any::type_name::<E>(),
+                error.listener,
+                error.outcome(),
+                error.error,
+            ),
+            Self::Custom(handler) => handler(world, error),
+        }
+    }
+}
+
+/// Log the `error` returned by a callback.
+#[cfg_attr(not(feature = "trace"), allow(unused_variables))]
+fn log_error<E>(error: &CallbackError<E>) {
+    #[cfg(feature = "trace")]
+    error!(
+        "Callback for event {:?} on {:?} {}: {}",
+        std::any::type_name::<E>(),
+        error.listener,
+        error.outcome(),
+        error.error,
+    );
+}
+
+/// A resource that makes the panics of the callbacks of events of type `E` be caught, instead of
+/// aborting the dispatch of their event. This is added by
+/// [`EventListenerPlugin::isolate_panics`](crate::EventListenerPlugin::isolate_panics).
+///
+/// A caught panic is passed to the [`CallbackErrorHandler<E>`] as a [`CallbackError<E>`], with the
+/// event as it was when the callback panicked, and the event continues propagating. Depending on
+/// the [`OnPanic`] policy, the listener of the callback is then disabled, so it can be enabled
+/// again with [`On::set_enabled`](crate::event_listener::On::set_enabled) once the problem has
+/// been dealt with.
+///
+/// Each event is cloned before running a callback, so it can be recovered if the callback panics.
+/// The default panic hook still prints the panic message.
+///
+/// ```
+/// # use bevy_eventlistener_core::{
+/// #     callback_errors::OnPanic,
+/// #     event_listener::{EntityEvent, On},
+/// #     EventListenerPlugin,
+/// # };
+/// # use bevy_app::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_hierarchy::BuildWorldChildren;
+/// # #[derive(Clone, Event)]
+/// # struct Click {
+/// #     target: Entity,
+/// # }
+/// # impl EntityEvent for Click {
+/// #     fn target(&self) -> Entity {
+/// #         self.target
+/// #     }
+/// # }
+/// # #[derive(Resource, Default)]
+/// # struct Clicks(usize);
+/// let mut app = App::new();
+/// app.add_plugins(EventListenerPlugin::<Click>::default().isolate_panics(OnPanic::DisableListener))
+///     .init_resource::<Clicks>();
+///
+/// let parent = app
+///     .world
+///     .spawn(On::<Click>::run(|mut clicks: ResMut<Clicks>| clicks.0 += 1))
+///     .id();
+/// let button = app
+///     .world
+///     .spawn(On::<Click>::run(|| panic!("bad handler")))
+///     .set_parent(parent)
+///     .id();
+///
+/// app.world.send_event(Click { target: button });
+/// app.update();
+/// // The event kept bubbling, and the listener that panicked was disabled.
+/// assert_eq!(app.world.resource::<Clicks>().0, 1);
+/// assert!(!app.world.get::<On<Click>>(button).unwrap().is_enabled());
+/// ```
+#[derive(Resource)]
+pub struct PanicIsolation<E> {
+    /// What happens to the listener of a callback that panicked.
+    pub on_panic: OnPanic,
+    /// Did the last callback that ran panic? This is taken once the callback is returned to its
+    /// listener.
+    panicked: bool,
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+/// What happens to the listener of a callback that panicked, see [`PanicIsolation`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum OnPanic {
+    /// The listener keeps running its callback.
+    #[default]
+    Continue,
+    /// The listener is disabled.
+    DisableListener,
+}
+
+impl<E> PanicIsolation<E> {
+    /// Catch the panics of callbacks, handling their listener with the `on_panic` policy.
+    pub fn new(on_panic: OnPanic) -> Self {
+        Self {
+            on_panic,
+            panicked: false,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent> PanicIsolation<E> {
+    /// Run `callback` with the `input`, catching its panic if panic isolation is enabled for
+    /// events of type `E`. The input is recovered from the world if the callback panicked while it
+    /// was inserted as a resource, or from a clone made before running the callback otherwise.
+    pub(crate) fn run(
+        world: &mut World,
+        input: ListenerInput<E>,
+        callback: impl FnOnce(&mut World, ListenerInput<E>) -> Option<ListenerInput<E>>,
+    ) -> Option<ListenerInput<E>> {
+        if !world.contains_resource::<PanicIsolation<E>>() {
+            return callback(world, input);
+        }
+        let backup = input.clone();
+        let payload = match catch_unwind(AssertUnwindSafe(|| callback(world, input))) {
+            Ok(output) => return output,
+            Err(payload) => payload,
+        };
+        let input = world
+            .remove_resource::<ListenerInput<E>>()
+            .unwrap_or(backup);
+        let error = CallbackError {
+            error: panic_message(payload).into(),
+            event: input.event_data.clone(),
+            target: input.target,
+            listener: input.listener,
+            panicked: true,
+        };
+        Self::report(world, error);
+        Some(input)
+    }
+
+    /// Run a batched `callback` with the `inputs`, catching its panic if panic isolation is
+    /// enabled for events of type `E`. The panic is reported with the first event of the batch.
+    pub(crate) fn run_batch(
+        world: &mut World,
+        inputs: ListenerInputs<E>,
+        callback: impl FnOnce(&mut World, ListenerInputs<E>),
+    ) {
+        if !world.contains_resource::<PanicIsolation<E>>() {
+            return callback(world, inputs);
+        }
+        let listener = inputs.listener;
+        let first = inputs.events.first().cloned();
+        let Err(payload) = catch_unwind(AssertUnwindSafe(|| callback(world, inputs))) else {
+            return;
+        };
+        world.remove_resource::<ListenerInputs<E>>();
+        let Some(first) = first else {
+            return;
+        };
+        let error = CallbackError {
+            error: panic_message(payload).into(),
+            event: first.event,
+            target: first.target,
+            listener,
+            panicked: true,
+        };
+        Self::report(world, error);
+    }
+
+    /// Record that a callback panicked, and pass the `error` to the [`CallbackErrorHandler<E>`].
+    fn report(world: &mut World, error: CallbackError<E>) {
+        if let Some(mut isolation) = world.get_resource_mut::<PanicIsolation<E>>() {
+            isolation.panicked = true;
+        }
+        CallbackErrorHandler::handle(world, error);
+    }
+
+    /// Returns `true` if the last callback that ran panicked, and its listener should be disabled,
+    /// resetting the record of the panic.
+    pub(crate) fn take_disable(world: &mut World) -> bool {
+        world
+            .get_resource_mut::<PanicIsolation<E>>()
+            .is_some_and(|mut isolation| {
+                std::mem::take(&mut isolation.panicked)
+                    && isolation.on_panic == OnPanic::DisableListener
+            })
+    }
+}
+
+/// The message of a caught panic.
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "the callback panicked".to_string(),
+        },
+    }
+}
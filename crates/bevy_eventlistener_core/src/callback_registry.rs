@@ -0,0 +1,213 @@
+//! Support for listeners that refer to their callbacks by name, so they can be reflected and stored
+//! in scenes.
+
+use bevy_ecs::prelude::*;
+use bevy_reflect::{Reflect, TypePath};
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::error;
+use bevy_utils::HashMap;
+
+use crate::event_listener::{EntityEvent, On};
+
+/// A resource storing event listeners for events of type `E` under string names.
+///
+/// Callback systems cannot be reflected, so an [`On<E>`] cannot be part of a scene. Instead, a
+/// [`NamedListener<E>`] can be used to store the name of a callback in this registry. When the
+/// named listener is added to an entity, the registry is used to construct a new [`On<E>`] on that
+/// entity.
+///
+/// Register callbacks with
+/// [`register_callback`](crate::EventListenerAppExt::register_callback).
+#[derive(Resource)]
+pub struct CallbackRegistry<E: EntityEvent> {
+    callbacks: HashMap<String, Box<ListenerConstructor<E>>>,
+}
+
+/// Constructs a listener registered in a [`CallbackRegistry`] from its parameters.
+type ListenerConstructor<E> = dyn Fn(&ListenerParams) -> On<E> + Send + Sync;
+
+/// Parameters used to construct a listener registered in a [`CallbackRegistry`], see
+/// [`CallbackRegistry::register_with_params`]. Parameters are stored as strings, so they can be
+/// written by hand in data files, and parsed by the listener that uses them.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+#[cfg_attr(feature = "asset", derive(serde::Deserialize), serde(transparent))]
+pub struct ListenerParams(HashMap<String, String>);
+
+impl ListenerParams {
+    /// Set the parameter `name` to `value`.
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) -> &mut Self {
+        self.0.insert(name.into(), value.into());
+        self
+    }
+
+    /// The value of the parameter `name`, if it is set.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.0.get(name).map(String::as_str)
+    }
+
+    /// Parse the value of the parameter `name`. Returns `None` if it is not set, or cannot be
+    /// parsed.
+    pub fn parse<T: std::str::FromStr>(&self, name: &str) -> Option<T> {
+        self.get(name)?.parse().ok()
+    }
+}
+
+impl<E: EntityEvent> Default for CallbackRegistry<E> {
+    fn default() -> Self {
+        Self {
+            callbacks: HashMap::new(),
+        }
+    }
+}
+
+impl<E: EntityEvent> CallbackRegistry<E> {
+    /// Register a function that constructs an event listener under `name`, replacing any listener
+    /// that was previously registered with the same name. The function is called once for every
+    /// [`NamedListener<E>`] that refers to it.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        listener: impl Fn() -> On<E> + Send + Sync + 'static,
+    ) {
+        self.register_with_params(name, move |_| listener());
+    }
+
+    /// Register a function that constructs an event listener from a set of [`ListenerParams`]
+    /// under `name`, replacing any listener that was previously registered with the same name.
+    /// This allows data files to configure the listeners they refer to.
+    pub fn register_with_params(
+        &mut self,
+        name: impl Into<String>,
+        listener: impl Fn(&ListenerParams) -> On<E> + Send + Sync + 'static,
+    ) {
+        self.callbacks.insert(name.into(), Box::new(listener));
+    }
+
+    /// Remove the listener registered under `name`. Returns `true` if it was registered.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.callbacks.remove(name).is_some()
+    }
+
+    /// Returns `true` if a listener is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.callbacks.contains_key(name)
+    }
+
+    /// Construct a new instance of the listener registered under `name`, with no parameters.
+    pub fn get(&self, name: &str) -> Option<On<E>> {
+        self.get_with_params(name, &ListenerParams::default())
+    }
+
+    /// Construct a new instance of the listener registered under `name`, with the given `params`.
+    pub fn get_with_params(&self, name: &str, params: &ListenerParams) -> Option<On<E>> {
+        let mut listener = self.callbacks.get(name)?(params);
+        listener.registered_name = Some(name.to_owned());
+        Some(listener)
+    }
+
+    /// The names of all registered listeners.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.callbacks.keys().map(String::as_str)
+    }
+
+    /// Replace every added or changed [`NamedListener<E>`] with the [`On<E>`] registered under
+    /// its name.
+    pub fn resolve(
+        mut commands: Commands,
+        registry: Res<CallbackRegistry<E>>,
+        named: Query<(Entity, &NamedListener<E>), Changed<NamedListener<E>>>,
+    ) where
+        E: TypePath,
+    {
+        for (entity, named) in &named {
+            let Some(listener) = registry.get(&named.name) else {
+                #[cfg(feature = "trace")]
+                error!(
+                    "No callback named {:?} is registered for event {:?}, on entity {:?}",
+                    named.name,
+                    std::any::type_name::<E>(),
+                    entity,
+                );
+                continue;
+            };
+            commands.entity(entity).insert(listener);
+        }
+    }
+}
+
+/// A reflectable event listener, that refers to a callback registered in the
+/// [`CallbackRegistry<E>`] by name.
+///
+/// When this component is added to an entity, or its name is changed, the [`On<E>`] component of
+/// the entity is replaced with the listener registered under that name. This component is kept on
+/// the entity, so it can be saved along with the rest of the scene.
+///
+/// ```
+/// # use bevy_eventlistener_core::{
+/// #     callback_registry::NamedListener, callbacks::Listener, event_listener::{EntityEvent, On},
+/// #     EventListenerAppExt, EventListenerPlugin,
+/// # };
+/// # use bevy_app::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_reflect::TypePath;
+/// #[derive(Clone, Event, TypePath)]
+/// struct Click {
+///     target: Entity,
+/// }
+/// # impl EntityEvent for Click {
+/// #     fn target(&self) -> Entity {
+/// #         self.target
+/// #     }
+/// # }
+///
+/// fn open_door(event: Listener<Click>) {
+///     // ...
+/// }
+///
+/// let mut app = App::new();
+/// app.add_plugins(EventListenerPlugin::<Click>::default())
+///     .register_callback("open_door", || On::<Click>::run(open_door));
+///
+/// // This entity could also have been spawned from a scene.
+/// let door = app.world.spawn(NamedListener::<Click>::new("open_door")).id();
+/// app.update();
+/// assert!(app.world.get::<On<Click>>(door).is_some());
+/// ```
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct NamedListener<E: EntityEvent + TypePath> {
+    /// The name the callback was registered under in the [`CallbackRegistry<E>`].
+    pub name: String,
+    #[reflect(ignore)]
+    phantom: std::marker::PhantomData<E>,
+}
+
+impl<E: EntityEvent + TypePath> NamedListener<E> {
+    /// Create a listener that runs the callback registered under `name`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent + TypePath> Default for NamedListener<E> {
+    fn default() -> Self {
+        Self::new(String::new())
+    }
+}
+
+impl<E: EntityEvent + TypePath> Clone for NamedListener<E> {
+    fn clone(&self) -> Self {
+        Self::new(self.name.clone())
+    }
+}
+
+impl<E: EntityEvent + TypePath> std::fmt::Debug for NamedListener<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NamedListener")
+            .field("name", &self.name)
+            .finish()
+    }
+}
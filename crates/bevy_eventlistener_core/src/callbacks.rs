@@ -1,28 +1,500 @@
-use bevy_ecs::{prelude::*, system::BoxedSystem};
+use std::{any::Any, sync::Arc, time::Duration};
 
-use crate::EntityEvent;
+use bevy_ecs::{prelude::*, query::ReadOnlyWorldQuery, system::BoxedSystem};
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::error;
 
-#[derive(Default, Debug)]
-pub enum CallbackSystem {
+use crate::{
+    callback_errors::PanicIsolation, history::TriggeredListener, source::EventSource, EntityEvent,
+};
+
+/// The callback system of an event listener, along with its initialization state.
+///
+/// The callback is owned by its listener, and is not shared. While it is running, it is moved out
+/// of the listener, leaving [`CallbackSystem::Empty`] in its place, so dispatching does not need
+/// any locking.
+#[derive(Default)]
+pub enum CallbackSystem<E: EntityEvent> {
+    /// The callback has been taken out of the listener, or the listener has no callback.
     #[default]
     Empty,
-    New(BoxedSystem),
-    Initialized(BoxedSystem),
+    /// The callback has not been run yet, and needs to be initialized.
+    New(Callback<E>),
+    /// The callback has been initialized, and is ready to run.
+    Initialized(Callback<E>),
+    /// The callback is stored in the [`SharedCallbacks`] resource, and may be used by many
+    /// listeners.
+    Shared(SharedCallback<E>),
+}
+
+/// The ways a callback system can receive the [`ListenerInput`] of the event that triggered it.
+pub enum Callback<E: EntityEvent> {
+    /// The callback accesses the input as a resource, using [`Listener`] or [`ListenerMut`].
+    Resource(BoxedSystem),
+    /// The callback receives the input with [`In`], and returns it when it is done.
+    Input(BoxedSystem<ListenerInput<E>, ListenerInput<E>>),
+    /// The callback is run once per frame with every event that reached it, which it accesses as
+    /// a resource using [`ListenerBatch`].
+    Batched(BoxedSystem),
+    /// A chain of callbacks that access the input as a resource, and are run in order until one
+    /// of them calls [`ListenerInput::stop_immediate_propagation`].
+    Chain(Vec<BoxedSystem>),
+}
+
+/// Conversion into the callback of an event listener, see [`On::run`](crate::event_listener::On::run).
+///
+/// This is implemented for every system that can be used as a callback, and for tuples of up to
+/// eight of them, which are run in order as a single callback.
+pub trait IntoCallback<Marker> {
+    /// Convert into a callback.
+    fn into_callback<E: EntityEvent>(self) -> Callback<E>;
+}
+
+impl<S: IntoSystem<(), (), Marker>, Marker> IntoCallback<(IsCallbackSystem, Marker)> for S {
+    fn into_callback<E: EntityEvent>(self) -> Callback<E> {
+        Callback::Resource(Box::new(IntoSystem::into_system(self)))
+    }
+}
+
+/// The [`IntoCallback`] marker for systems, which keeps it apart from [`IsCallbackChain`].
+#[doc(hidden)]
+pub struct IsCallbackSystem;
+
+/// The [`IntoCallback`] marker for tuples of systems, which keeps it apart from
+/// [`IsCallbackSystem`].
+#[doc(hidden)]
+pub struct IsCallbackChain;
+
+macro_rules! impl_into_callback_chain {
+    ($(($system:ident, $marker:ident)),*) => {
+        impl<$($system: IntoSystem<(), (), $marker>, $marker),*>
+            IntoCallback<(IsCallbackChain, ($($marker,)*))> for ($($system,)*)
+        {
+            #[allow(non_snake_case)]
+            fn into_callback<E: EntityEvent>(self) -> Callback<E> {
+                let ($($system,)*) = self;
+                Callback::Chain(vec![$(Box::new(IntoSystem::into_system($system))),*])
+            }
+        }
+    };
+}
+
+impl_into_callback_chain!((S0, M0), (S1, M1));
+impl_into_callback_chain!((S0, M0), (S1, M1), (S2, M2));
+impl_into_callback_chain!((S0, M0), (S1, M1), (S2, M2), (S3, M3));
+impl_into_callback_chain!((S0, M0), (S1, M1), (S2, M2), (S3, M3), (S4, M4));
+impl_into_callback_chain!((S0, M0), (S1, M1), (S2, M2), (S3, M3), (S4, M4), (S5, M5));
+impl_into_callback_chain!(
+    (S0, M0),
+    (S1, M1),
+    (S2, M2),
+    (S3, M3),
+    (S4, M4),
+    (S5, M5),
+    (S6, M6)
+);
+impl_into_callback_chain!(
+    (S0, M0),
+    (S1, M1),
+    (S2, M2),
+    (S3, M3),
+    (S4, M4),
+    (S5, M5),
+    (S6, M6),
+    (S7, M7)
+);
+
+impl<E: EntityEvent> std::fmt::Debug for Callback<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Callback::Resource(system) => f.debug_tuple("Resource").field(&system.name()).finish(),
+            Callback::Input(system) => f.debug_tuple("Input").field(&system.name()).finish(),
+            Callback::Batched(system) => f.debug_tuple("Batched").field(&system.name()).finish(),
+            Callback::Chain(systems) => f
+                .debug_tuple("Chain")
+                .field(&systems.iter().map(|s| s.name()).collect::<Vec<_>>())
+                .finish(),
+        }
+    }
+}
+
+impl<E: EntityEvent> Callback<E> {
+    #[cfg(feature = "trace")]
+    fn name(&self) -> std::borrow::Cow<'static, str> {
+        match self {
+            Callback::Resource(system) | Callback::Batched(system) => system.name(),
+            Callback::Input(system) => system.name(),
+            Callback::Chain(systems) => {
+                let names: Vec<_> = systems.iter().map(|system| system.name()).collect();
+                format!("({})", names.join(", ")).into()
+            }
+        }
+    }
+
+    fn initialize(&mut self, world: &mut World) {
+        match self {
+            Callback::Resource(system) | Callback::Batched(system) => system.initialize(world),
+            Callback::Input(system) => system.initialize(world),
+            Callback::Chain(systems) => {
+                for system in systems {
+                    system.initialize(world);
+                }
+            }
+        }
+    }
+
+    fn run(&mut self, world: &mut World, input: ListenerInput<E>) -> Option<ListenerInput<E>> {
+        match self {
+            Callback::Resource(system) => {
+                world.insert_resource(input);
+                system.run((), world);
+                system.apply_deferred(world);
+                world.remove_resource::<ListenerInput<E>>()
+            }
+            Callback::Input(system) => {
+                let input = system.run(input, world);
+                system.apply_deferred(world);
+                Some(input)
+            }
+            Callback::Chain(systems) => {
+                world.insert_resource(input);
+                for system in systems {
+                    system.run((), world);
+                    system.apply_deferred(world);
+                    // The chain also ends if the input was removed.
+                    let skip_remaining = world
+                        .get_resource::<ListenerInput<E>>()
+                        .map(|input| input.skip_remaining);
+                    if skip_remaining != Some(false) {
+                        break;
+                    }
+                }
+                world.remove_resource::<ListenerInput<E>>()
+            }
+            // Batched callbacks are only run by `run_batch`, once every event has propagated.
+            Callback::Batched(_) => Some(input),
+        }
+    }
 }
 
-impl CallbackSystem {
-    pub(crate) fn run(&mut self, world: &mut World) {
-        let mut system = match std::mem::take(self) {
-            CallbackSystem::Empty => return,
-            CallbackSystem::New(mut system) => {
-                system.initialize(world);
-                system
+impl<E: EntityEvent> std::fmt::Debug for CallbackSystem<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CallbackSystem::Empty => write!(f, "Empty"),
+            CallbackSystem::New(callback) => f.debug_tuple("New").field(callback).finish(),
+            CallbackSystem::Initialized(callback) => {
+                f.debug_tuple("Initialized").field(callback).finish()
+            }
+            CallbackSystem::Shared(callback) => f.debug_tuple("Shared").field(callback).finish(),
+        }
+    }
+}
+
+impl<E: EntityEvent> CallbackSystem<E> {
+    /// The name of the callback system, used for diagnostics.
+    #[cfg(feature = "trace")]
+    pub(crate) fn name(&self) -> std::borrow::Cow<'static, str> {
+        match self {
+            CallbackSystem::Empty => "empty".into(),
+            CallbackSystem::New(callback) | CallbackSystem::Initialized(callback) => {
+                callback.name()
+            }
+            CallbackSystem::Shared(shared) => format!("{shared:?}").into(),
+        }
+    }
+
+    /// Run the callback, initializing it first if needed. Returns the input once the callback is
+    /// done with it, or `None` if a resource-based callback removed the [`ListenerInput`]
+    /// resource.
+    pub(crate) fn run(
+        &mut self,
+        world: &mut World,
+        input: ListenerInput<E>,
+    ) -> Option<ListenerInput<E>> {
+        let mut callback = match std::mem::take(self) {
+            CallbackSystem::Empty => return Some(input),
+            CallbackSystem::Shared(shared) => {
+                *self = CallbackSystem::Shared(shared);
+                return shared.run(world, input);
+            }
+            CallbackSystem::New(mut callback) => {
+                callback.initialize(world);
+                callback
+            }
+            CallbackSystem::Initialized(callback) => callback,
+        };
+        let input = PanicIsolation::run(world, input, |world, input| callback.run(world, input));
+        *self = CallbackSystem::Initialized(callback);
+        input
+    }
+
+    /// Returns `true` if this is a batched callback, see
+    /// [`On::run_batched`](crate::event_listener::On::run_batched).
+    pub(crate) fn is_batched(&self) -> bool {
+        matches!(
+            self,
+            CallbackSystem::New(Callback::Batched(_))
+                | CallbackSystem::Initialized(Callback::Batched(_))
+        )
+    }
+
+    /// Run a batched callback with the events that reached it, initializing it first if needed.
+    /// Does nothing for other callbacks.
+    pub(crate) fn run_batch(&mut self, world: &mut World, inputs: ListenerInputs<E>) {
+        let mut callback = match std::mem::take(self) {
+            CallbackSystem::New(mut callback) => {
+                callback.initialize(world);
+                callback
+            }
+            CallbackSystem::Initialized(callback) => callback,
+            other => {
+                *self = other;
+                return;
             }
-            CallbackSystem::Initialized(system) => system,
         };
-        system.run((), world);
-        system.apply_deferred(world);
-        *self = CallbackSystem::Initialized(system);
+        if let Callback::Batched(system) = &mut callback {
+            PanicIsolation::run_batch(world, inputs, |world, inputs| {
+                world.insert_resource(inputs);
+                system.run((), world);
+                system.apply_deferred(world);
+                world.remove_resource::<ListenerInputs<E>>();
+            });
+        }
+        *self = CallbackSystem::Initialized(callback);
+    }
+}
+
+/// A run condition that must be met for a listener to be triggered, see
+/// [`On::run_if`](crate::event_listener::On::run_if).
+pub(crate) struct ListenerCondition {
+    system: BoxedSystem<(), bool>,
+    initialized: bool,
+}
+
+impl ListenerCondition {
+    pub(crate) fn new<Marker>(condition: impl Condition<Marker>) -> Self {
+        Self {
+            system: Box::new(IntoSystem::into_system(condition)),
+            initialized: false,
+        }
+    }
+
+    /// Evaluate the condition, initializing it first if needed.
+    pub(crate) fn run(&mut self, world: &mut World) -> bool {
+        if !self.initialized {
+            self.system.initialize(world);
+            self.initialized = true;
+        }
+        let result = self.system.run((), world);
+        self.system.apply_deferred(world);
+        result
+    }
+}
+
+/// Checks whether an entity matches a query filter.
+type MatchesFilter = Box<dyn FnMut(&mut World, Entity) -> bool + Send + Sync>;
+
+/// A query filter that the target of an event must match for a listener to be triggered, see
+/// [`On::delegate`](crate::event_listener::On::delegate).
+pub(crate) struct TargetFilter {
+    matches: MatchesFilter,
+}
+
+impl TargetFilter {
+    pub(crate) fn new<F: ReadOnlyWorldQuery + 'static>() -> Self {
+        let mut state: Option<QueryState<(), F>> = None;
+        Self {
+            matches: Box::new(move |world, entity| {
+                let state = state.get_or_insert_with(|| QueryState::new(world));
+                state.get(world, entity).is_ok()
+            }),
+        }
+    }
+
+    /// Does the `target` match the filter? The query is created the first time this is called.
+    pub(crate) fn matches(&mut self, world: &mut World, target: Entity) -> bool {
+        (self.matches)(world, target)
+    }
+}
+
+/// Limits how often a listener can be triggered, see
+/// [`On::throttle`](crate::event_listener::On::throttle),
+/// [`On::with_deferred_cooldown`](crate::event_listener::On::with_deferred_cooldown), and
+/// [`On::debounce`](crate::event_listener::On::debounce).
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum RateLimit {
+    /// The listener is triggered at most once per `period`. If `defer` is set, the last event that
+    /// was suppressed triggers the listener once the period is over.
+    Throttle {
+        period: Duration,
+        last_run: Option<Duration>,
+        defer: bool,
+    },
+    /// The listener is only triggered once no events have reached it for `period`.
+    Debounce {
+        period: Duration,
+        last_event: Option<Duration>,
+    },
+}
+
+impl RateLimit {
+    /// Returns `true` if an event reaching the listener at time `now` should trigger it, and
+    /// records the event.
+    pub(crate) fn allow(&mut self, now: Duration) -> bool {
+        match self {
+            RateLimit::Throttle {
+                period, last_run, ..
+            } => {
+                if last_run.is_some_and(|last_run| now.saturating_sub(last_run) < *period) {
+                    return false;
+                }
+                *last_run = Some(now);
+                true
+            }
+            RateLimit::Debounce { period, last_event } => {
+                let quiet = !last_event.is_some_and(|last| now.saturating_sub(last) < *period);
+                *last_event = Some(now);
+                quiet
+            }
+        }
+    }
+
+    /// Should the events suppressed by this limit trigger the listener later?
+    pub(crate) fn defers(&self) -> bool {
+        matches!(self, RateLimit::Throttle { defer: true, .. })
+    }
+
+    /// Returns `true` if a deferred event should trigger the listener at time `now`, and records
+    /// the run.
+    pub(crate) fn allow_deferred(&mut self, now: Duration) -> bool {
+        match self {
+            RateLimit::Throttle { defer: true, .. } => self.allow(now),
+            // The listener stopped deferring events since this one was suppressed.
+            _ => false,
+        }
+    }
+}
+
+/// A resource storing callback systems that are shared by many listeners for events of type `E`.
+///
+/// Each listener created with [`On::run`](crate::event_listener::On::run) stores and initializes
+/// its own copy of its callback system. When many entities run the same callback, the callback can
+/// instead be added here once, and referenced by each listener with
+/// [`On::run_shared`](crate::event_listener::On::run_shared). The shared system is only
+/// initialized once, and its [`Local`] state is shared by all listeners that use it.
+///
+/// Add shared callbacks with
+/// [`add_shared_callback`](crate::EventListenerAppExt::add_shared_callback).
+#[derive(Resource)]
+pub struct SharedCallbacks<E: EntityEvent> {
+    callbacks: Vec<CallbackSystem<E>>,
+}
+
+impl<E: EntityEvent> Default for SharedCallbacks<E> {
+    fn default() -> Self {
+        Self {
+            callbacks: Vec::new(),
+        }
+    }
+}
+
+impl<E: EntityEvent> SharedCallbacks<E> {
+    /// Add a callback system that can be shared by many listeners. See [`On::run`] for more
+    /// details.
+    ///
+    /// [`On::run`]: crate::event_listener::On::run
+    pub fn add<Marker>(&mut self, callback: impl IntoSystem<(), (), Marker>) -> SharedCallback<E> {
+        self.push(Callback::Resource(Box::new(IntoSystem::into_system(
+            callback,
+        ))))
+    }
+
+    /// Add a callback system that receives the [`ListenerInput`] as a system input, and can be
+    /// shared by many listeners. See [`On::run_with_input`] for more details.
+    ///
+    /// [`On::run_with_input`]: crate::event_listener::On::run_with_input
+    pub fn add_with_input<Marker>(
+        &mut self,
+        callback: impl IntoSystem<ListenerInput<E>, ListenerInput<E>, Marker>,
+    ) -> SharedCallback<E> {
+        self.push(Callback::Input(Box::new(IntoSystem::into_system(callback))))
+    }
+
+    /// The number of shared callbacks.
+    pub fn len(&self) -> usize {
+        self.callbacks.len()
+    }
+
+    /// Returns `true` if there are no shared callbacks.
+    pub fn is_empty(&self) -> bool {
+        self.callbacks.is_empty()
+    }
+
+    fn push(&mut self, callback: Callback<E>) -> SharedCallback<E> {
+        self.callbacks.push(CallbackSystem::New(callback));
+        SharedCallback {
+            index: self.callbacks.len() - 1,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// A handle to a callback system in the [`SharedCallbacks`] resource.
+pub struct SharedCallback<E: EntityEvent> {
+    index: usize,
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E: EntityEvent> SharedCallback<E> {
+    /// Run the shared callback, taking it out of the [`SharedCallbacks`] resource while it runs.
+    fn run(self, world: &mut World, input: ListenerInput<E>) -> Option<ListenerInput<E>> {
+        let Some(mut callback) = world
+            .get_resource_mut::<SharedCallbacks<E>>()
+            .and_then(|mut shared| shared.callbacks.get_mut(self.index).map(std::mem::take))
+        else {
+            #[cfg(feature = "trace")]
+            error!(
+                "The shared callback {:?} for event {:?} does not exist",
+                self,
+                std::any::type_name::<E>(),
+            );
+            return Some(input);
+        };
+        let input = callback.run(world, input);
+        if let Some(mut shared) = world.get_resource_mut::<SharedCallbacks<E>>() {
+            if let Some(slot) = shared.callbacks.get_mut(self.index) {
+                *slot = callback;
+            }
+        }
+        input
+    }
+}
+
+impl<E: EntityEvent> Clone for SharedCallback<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E: EntityEvent> Copy for SharedCallback<E> {}
+
+impl<E: EntityEvent> PartialEq for SharedCallback<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index == other.index
+    }
+}
+
+impl<E: EntityEvent> Eq for SharedCallback<E> {}
+
+impl<E: EntityEvent> std::hash::Hash for SharedCallback<E> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+impl<E: EntityEvent> std::fmt::Debug for SharedCallback<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("SharedCallback").field(&self.index).finish()
     }
 }
 
@@ -38,11 +510,17 @@ pub type Listener<'w, E> = Res<'w, ListenerInput<E>>;
 /// Use this in callback systems to access event data for the event that triggered the callback.
 pub type ListenerMut<'w, E> = ResMut<'w, ListenerInput<E>>;
 
+/// A [`SystemParam`](bevy_ecs::system::SystemParam) used to access the [`ListenerInputs`] of a
+/// batched callback, see [`On::run_batched`](crate::event_listener::On::run_batched).
+pub type ListenerBatch<'w, E> = Res<'w, ListenerInputs<E>>;
+
 /// Data from an event that triggered an [`On<Event>`](crate::event_listener::On) listener, and is
 /// currently bubbling through the entity hierarchy.
 ///
 /// This is accessed as a bevy resource in the callback system. This resource is only available to
-/// callback systems.
+/// callback systems, and is only inserted into the world while a callback that uses it is running.
+/// Callbacks created with [`On::run_with_input`](crate::event_listener::On::run_with_input)
+/// receive the input as a system input instead, and never see this resource.
 ///
 /// ```
 /// # use bevy_eventlistener_core::{callbacks::ListenerMut, event_listener::EntityEvent};
@@ -62,18 +540,73 @@ pub type ListenerMut<'w, E> = ResMut<'w, ListenerInput<E>>;
 ///     event.target(); // The entity that was originally targeted
 ///     event.listener(); // The entity that was listening for this event
 ///     event.stop_propagation(); // Stop the event from bubbling further
+///     event.stop_immediate_propagation(); // Also skip other listeners on this entity
+///     event.prevent_default(); // Don't run the default action for this event
 /// }
 /// ```
 #[derive(Clone, PartialEq, Debug, Resource)]
 pub struct ListenerInput<E: EntityEvent> {
     /// The entity that was listening for this event.
     pub(crate) listener: Entity,
+    /// The entity the event is propagating from, one of [`EntityEvent::targets`].
+    pub(crate) target: Entity,
     /// Event-specific information.
     pub(crate) event_data: E,
     pub(crate) propagate: bool,
+    /// Should the remaining listeners on the current entity be skipped?
+    pub(crate) skip_remaining: bool,
+    /// Should the default action for this event be skipped?
+    pub(crate) default_prevented: bool,
+    /// The phase of propagation the event is currently in.
+    pub(crate) phase: EventPhase,
+    /// The entities the event is propagating through. Only the first `path_len` entities have
+    /// been traversed to reach the current listener.
+    pub(crate) path: Vec<Entity>,
+    pub(crate) path_len: usize,
+    /// The number of listeners that have been triggered by this event.
+    pub(crate) triggered: usize,
+    /// If debug logging or the [`EventHistory`](crate::history::EventHistory) is enabled, records
+    /// each listener that was triggered.
+    pub(crate) triggered_log: Option<Vec<TriggeredListener>>,
+    /// The entity the event should continue propagating from, once the listeners on the current
+    /// entity have run.
+    pub(crate) retarget: Option<Entity>,
+    /// The values returned by the listeners this event triggered, see
+    /// [`On::run_with_output`](crate::event_listener::On::run_with_output).
+    pub(crate) responses: ListenerResponses,
+    /// The data attached to the current listener.
+    pub(crate) data: ListenerData,
+    /// Where the event was sent from, if it was recorded.
+    pub(crate) source: Option<EventSource>,
+    /// Should a warning be logged when the event reaches an entity that has been despawned?
+    #[cfg(feature = "trace")]
+    pub(crate) warn_despawned: bool,
 }
 
 impl<E: EntityEvent> ListenerInput<E> {
+    /// Create the input for an event that has not started propagating from `target` yet.
+    pub(crate) fn new(event_data: E, target: Entity) -> Self {
+        Self {
+            listener: target,
+            target,
+            event_data,
+            propagate: true,
+            skip_remaining: false,
+            default_prevented: false,
+            phase: EventPhase::Capture,
+            path: Vec::new(),
+            path_len: 0,
+            triggered: 0,
+            triggered_log: None,
+            retarget: None,
+            responses: ListenerResponses::default(),
+            data: ListenerData::default(),
+            source: None,
+            #[cfg(feature = "trace")]
+            warn_despawned: false,
+        }
+    }
+
     /// The entity that was listening for this event. Call `target()` to get the entity that this
     /// event originally targeted before it started bubbling through the hierarchy. Note that the
     /// target and listener can be the same entity.
@@ -81,10 +614,222 @@ impl<E: EntityEvent> ListenerInput<E> {
         self.listener
     }
 
-    /// When called, the event will stop bubbling up the hierarchy to its parent.
+    /// The entity this event is propagating from. For events with multiple
+    /// [`targets`](EntityEvent::targets), this is the target currently being propagated from,
+    /// which is not necessarily the same as [`EntityEvent::target`].
+    pub fn target(&self) -> Entity {
+        self.target
+    }
+
+    /// When called, the event will stop propagating through the hierarchy. If the event is in the
+    /// capture phase, no other listeners will be triggered, including the bubbling listeners. If
+    /// the event is being broadcast, it will not be sent to the descendants of the current entity.
+    ///
+    /// Any other listeners on the current entity will still be triggered. Use
+    /// [`ListenerInput::stop_immediate_propagation`] to skip them as well.
     pub fn stop_propagation(&mut self) {
         self.propagate = false;
     }
+
+    /// When called, the event will stop propagating through the hierarchy, and any remaining
+    /// listeners on the current entity will be skipped.
+    pub fn stop_immediate_propagation(&mut self) {
+        self.propagate = false;
+        self.skip_remaining = true;
+    }
+
+    /// When called, the event will stop propagating through the current entity's ancestors, and
+    /// will instead propagate from `target`, as if it had been sent to it: capture listeners on
+    /// the ancestors of `target` are triggered, then the listeners on `target`, then its ancestors
+    /// as the event bubbles. [`GlobalListeners`](crate::event_listener::GlobalListeners) are not
+    /// triggered again. This can be used by a proxy to forward events to the entity it represents.
+    ///
+    /// Any other listeners on the current entity are still triggered, and
+    /// [`ListenerInput::target`] returns the new target once they have run. The event data is not
+    /// changed, so [`EntityEvent::target`] still returns the original target. Stopping propagation
+    /// cancels the retarget, and retargeting has no effect on broadcast events. An event can't be
+    /// retargeted to an entity it has already propagated from.
+    pub fn retarget(&mut self, target: Entity) {
+        self.retarget = Some(target);
+    }
+
+    /// When called, the [`DefaultAction`](crate::event_listener::DefaultAction) for this event
+    /// will not be run once the event has finished propagating. This does not stop the event from
+    /// propagating, use [`ListenerInput::stop_propagation`] for that.
+    pub fn prevent_default(&mut self) {
+        self.default_prevented = true;
+    }
+
+    /// Returns `true` if a listener has called [`ListenerInput::prevent_default`].
+    pub fn default_prevented(&self) -> bool {
+        self.default_prevented
+    }
+
+    /// Record a response to this event from the current listener, which can be read by the
+    /// listeners that are triggered after it, and by the sender of the event, see
+    /// [`EventDispatcher::request`](crate::event_dispatcher::EventDispatcher::request).
+    pub fn respond<T: Send + Sync + 'static>(&mut self, response: T) {
+        self.responses.push(self.listener, response);
+    }
+
+    /// The responses recorded by the listeners that have been triggered so far.
+    pub fn responses(&self) -> &ListenerResponses {
+        &self.responses
+    }
+
+    /// The data attached to the current listener with
+    /// [`On::with_data`](crate::event_listener::On::with_data), if it is of type `T`.
+    pub fn data<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.data.get()
+    }
+
+    /// The phase of propagation that triggered the current callback.
+    pub fn phase(&self) -> EventPhase {
+        self.phase
+    }
+
+    /// Where the event was sent from, if it was sent with a source, see the
+    /// [`source`](crate::source) module.
+    pub fn source(&self) -> Option<&EventSource> {
+        self.source.as_ref()
+    }
+
+    /// The chain of entities traversed between the target and the current listener, starting with
+    /// the target and ending with the listener.
+    ///
+    /// When an event bubbles, entities without listeners for this event are skipped, so the path
+    /// only contains entities with listeners. [`GlobalListeners`](crate::event_listener::GlobalListeners)
+    /// see the whole path. When an event is broadcast, the path contains every entity between the
+    /// target and the listener, and global listeners only see the target.
+    pub fn path(&self) -> &[Entity] {
+        &self.path[..self.path_len]
+    }
+
+    /// The position of the current listener in the [`ListenerInput::path`], where the target has a
+    /// depth of `0`.
+    pub fn depth(&self) -> usize {
+        self.path_len.saturating_sub(1)
+    }
+}
+
+/// The phase of propagation an event is in when it triggers a listener.
+///
+/// Events fully propagate in two passes. First, the event is sent down the hierarchy from the root
+/// to the target, triggering any [`On::capture`](crate::event_listener::On::capture) listeners.
+/// The event then bubbles back up the hierarchy, from the target to the root, triggering all other
+/// listeners.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum EventPhase {
+    /// The event is traveling down the hierarchy towards the target, and has triggered a capture
+    /// listener on one of the target's ancestors.
+    Capture,
+    /// The event has triggered a listener on the target entity itself.
+    AtTarget,
+    /// The event is bubbling up the hierarchy from the target, and has triggered a listener on one
+    /// of the target's ancestors.
+    Bubble,
+    /// The event is being broadcast down the hierarchy from the target, and has triggered a
+    /// listener on one of the target's descendants. See
+    /// [`Propagation::Broadcast`](crate::event_listener::Propagation::Broadcast) and
+    /// [`Propagation::FindHandler`](crate::event_listener::Propagation::FindHandler).
+    Broadcast,
+}
+
+/// The responses recorded by the listeners triggered by an event, either with
+/// [`ListenerInput::respond`], or by returning them from a callback run with
+/// [`On::run_with_output`](crate::event_listener::On::run_with_output).
+///
+/// Responses can be of any type. Each response is stored along with the entity of the listener
+/// that recorded it, in the order they were recorded.
+#[derive(Clone, Default)]
+pub struct ListenerResponses {
+    responses: Vec<(Entity, Arc<dyn Any + Send + Sync>)>,
+}
+
+impl ListenerResponses {
+    pub(crate) fn push<T: Send + Sync + 'static>(&mut self, listener: Entity, response: T) {
+        self.responses.push((listener, Arc::new(response)));
+    }
+
+    /// The responses of type `T`, along with the listener that recorded each of them, in the
+    /// order they were recorded.
+    pub fn iter<T: Send + Sync + 'static>(&self) -> impl Iterator<Item = (Entity, &T)> + '_ {
+        self.responses.iter().filter_map(|(listener, response)| {
+            let response = response.downcast_ref::<T>()?;
+            Some((*listener, response))
+        })
+    }
+
+    /// The first response of type `T` that was recorded. When an event bubbles, this is the
+    /// response of the listener closest to the target.
+    pub fn first<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.iter().next().map(|(_, response)| response)
+    }
+
+    /// The last response of type `T` that was recorded.
+    pub fn last<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.iter().last().map(|(_, response)| response)
+    }
+
+    /// The number of responses recorded, of any type.
+    pub fn len(&self) -> usize {
+        self.responses.len()
+    }
+
+    /// Returns `true` if no responses were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.responses.is_empty()
+    }
+}
+
+impl PartialEq for ListenerResponses {
+    fn eq(&self, other: &Self) -> bool {
+        self.responses.len() == other.responses.len()
+            && self
+                .responses
+                .iter()
+                .zip(&other.responses)
+                .all(|((a, x), (b, y))| a == b && Arc::ptr_eq(x, y))
+    }
+}
+
+impl std::fmt::Debug for ListenerResponses {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.responses.iter().map(|(listener, _)| listener))
+            .finish()
+    }
+}
+
+/// The data attached to a listener, see [`On::with_data`](crate::event_listener::On::with_data).
+#[derive(Clone, Default)]
+pub(crate) struct ListenerData(Option<Arc<dyn Any + Send + Sync>>);
+
+impl ListenerData {
+    pub(crate) fn new<T: Send + Sync + 'static>(data: T) -> Self {
+        Self(Some(Arc::new(data)))
+    }
+
+    pub(crate) fn get<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.0.as_ref()?.downcast_ref()
+    }
+}
+
+impl PartialEq for ListenerData {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+}
+
+impl std::fmt::Debug for ListenerData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ListenerData")
+            .field(&self.0.is_some())
+            .finish()
+    }
 }
 
 impl<E: EntityEvent> std::ops::Deref for ListenerInput<E> {
@@ -100,3 +845,61 @@ impl<E: EntityEvent> std::ops::DerefMut for ListenerInput<E> {
         &mut self.event_data
     }
 }
+
+/// An event that reached a batched listener, see [`ListenerInputs`].
+#[derive(Clone, PartialEq, Debug)]
+pub struct BatchedEvent<E> {
+    /// The event data, as it was when the event reached the listener.
+    pub event: E,
+    /// The entity the event was propagating from.
+    pub target: Entity,
+    /// The phase of propagation the event reached the listener in.
+    pub phase: EventPhase,
+    /// Where the event was sent from, if it was sent with a source.
+    pub source: Option<EventSource>,
+}
+
+/// Every event that reached a batched listener in the current frame, in the order they reached it.
+///
+/// This is accessed as a bevy resource in batched callback systems, using [`ListenerBatch`], see
+/// [`On::run_batched`](crate::event_listener::On::run_batched).
+#[derive(Clone, PartialEq, Debug, Resource)]
+pub struct ListenerInputs<E: EntityEvent> {
+    pub(crate) listener: Entity,
+    pub(crate) events: Vec<BatchedEvent<E>>,
+    pub(crate) data: ListenerData,
+}
+
+impl<E: EntityEvent> ListenerInputs<E> {
+    /// The entity that was listening for these events, or [`Entity::PLACEHOLDER`] for global
+    /// listeners.
+    pub fn listener(&self) -> Entity {
+        self.listener
+    }
+
+    /// The data attached to the listener with
+    /// [`On::with_data`](crate::event_listener::On::with_data), if it is of type `T`.
+    pub fn data<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.data.get()
+    }
+
+    /// The events that reached the listener.
+    pub fn events(&self) -> &[BatchedEvent<E>] {
+        &self.events
+    }
+
+    /// Iterate over the data of the events that reached the listener.
+    pub fn iter(&self) -> impl Iterator<Item = &E> + '_ {
+        self.events.iter().map(|batched| &batched.event)
+    }
+
+    /// The number of events that reached the listener.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if no events reached the listener.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
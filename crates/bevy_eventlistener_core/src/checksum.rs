@@ -0,0 +1,117 @@
+//! Checksums of the events dispatched in deterministic mode, for lockstep multiplayer.
+//!
+//! With [`EventListenerPlugin::deterministic`](crate::EventListenerPlugin::deterministic), the
+//! [`EventDispatcher<E>`](crate::event_dispatcher::EventDispatcher) hashes every event it
+//! dispatches with a [`StableHasher`], along with its target, propagation path, and the number of
+//! listeners it triggered. Peers running the same simulation can compare the
+//! [`frame_checksum`](crate::event_dispatcher::EventDispatcher::frame_checksum) of each frame to
+//! detect a desync as soon as it happens.
+
+use std::hash::{Hash, Hasher};
+
+use bevy_ecs::entity::Entity;
+
+/// A [`Hasher`] that produces the same hash on every platform, and in every build.
+///
+/// This is the 64-bit FNV-1a hash. Integers are hashed as little-endian bytes, and `usize` and
+/// `isize` are hashed as 64-bit integers, so the hash does not depend on the endianness or pointer
+/// width of the machine. Unlike the hashers of the standard library and `bevy_utils`, it is not
+/// randomly seeded, and its algorithm does not change between versions of Rust.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StableHasher {
+    hash: u64,
+}
+
+impl StableHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self {
+            hash: Self::OFFSET_BASIS,
+        }
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= u64::from(*byte);
+            self.hash = self.hash.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i16(&mut self, i: i16) {
+        self.write_u16(i as u16);
+    }
+
+    fn write_i32(&mut self, i: i32) {
+        self.write_u32(i as u32);
+    }
+
+    fn write_i64(&mut self, i: i64) {
+        self.write_u64(i as u64);
+    }
+
+    fn write_i128(&mut self, i: i128) {
+        self.write_u128(i as u128);
+    }
+
+    fn write_isize(&mut self, i: isize) {
+        self.write_u64(i as u64);
+    }
+}
+
+/// Hash an event, for [`EventDispatcher::with_checksum`](crate::event_dispatcher::EventDispatcher::with_checksum).
+pub(crate) fn hash_event<E: Hash>(event: &E, hasher: &mut StableHasher) {
+    event.hash(hasher);
+}
+
+/// The checksum of a single dispatched event, along with what happened while it propagated.
+pub(crate) fn event_checksum<E>(
+    hash_event: fn(&E, &mut StableHasher),
+    event: Option<&E>,
+    target: Entity,
+    path: &[Entity],
+    triggered: usize,
+) -> u64 {
+    let mut hasher = StableHasher::default();
+    target.hash(&mut hasher);
+    match event {
+        Some(event) => {
+            hasher.write_u8(1);
+            hash_event(event, &mut hasher);
+            path.hash(&mut hasher);
+            hasher.write_usize(triggered);
+        }
+        // The event was lost during propagation.
+        None => hasher.write_u8(0),
+    }
+    hasher.finish()
+}
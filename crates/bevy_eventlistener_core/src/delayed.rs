@@ -0,0 +1,139 @@
+//! Events that are sent after a delay.
+//!
+//! The [`DelayedEvents<E>`] resource holds events that should be dispatched later, either after
+//! some time has passed, or after a number of frames. Once an event is due, it is sent right before
+//! the events of type `E` are dispatched, and propagates like any other event. This can be used for
+//! timed tooltips, cooldowns, or buffered inputs, without a timer system for each event type.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     delayed::DelayedEvents,
+//! #     event_listener::{EntityEvent, On},
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use std::time::Duration;
+//! # #[derive(Clone, Event)]
+//! # struct ShowTooltip {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for ShowTooltip {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! fn start_hover(mut delayed: ResMut<DelayedEvents<ShowTooltip>>) {
+//!     # let button = Entity::PLACEHOLDER;
+//!     delayed.send_in(ShowTooltip { target: button }, Duration::from_millis(500));
+//! }
+//! ```
+
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use bevy_time::Time;
+
+use crate::event_listener::EntityEvent;
+
+/// When a [`DelayedEvents`] entry is due.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "snapshot", derive(serde::Serialize, serde::Deserialize))]
+pub enum Delay {
+    /// The time left before the event is sent.
+    Time(Duration),
+    /// The number of frames left before the event is sent.
+    Frames(u32),
+}
+
+/// A resource storing the events of type `E` that will be sent later, see the
+/// [module docs](self). This is added by the [`EventListenerPlugin`](crate::EventListenerPlugin).
+///
+/// Time based delays are measured with the [`Time`] resource, so they need the `TimePlugin`.
+/// Events that are due in the same frame are sent in the order they were added.
+#[derive(Resource)]
+pub struct DelayedEvents<E: EntityEvent> {
+    events: Vec<(E, Delay)>,
+}
+
+impl<E: EntityEvent> Default for DelayedEvents<E> {
+    fn default() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+impl<E: EntityEvent> DelayedEvents<E> {
+    /// Send the `event` once `delay` has passed.
+    pub fn send_in(&mut self, event: E, delay: Duration) {
+        self.events.push((event, Delay::Time(delay)));
+    }
+
+    /// Send the `event` after `frames` frames have passed. With `0`, the event is sent the next
+    /// time events of type `E` are dispatched.
+    pub fn send_after_frames(&mut self, event: E, frames: u32) {
+        self.events.push((event, Delay::Frames(frames)));
+    }
+
+    /// The number of events waiting to be sent.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns `true` if no events are waiting to be sent.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Cancel every event waiting to be sent.
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+
+    /// Cancel the events waiting to be sent for which `cancel` returns `true`.
+    pub fn cancel(&mut self, mut cancel: impl FnMut(&E) -> bool) {
+        self.events.retain(|(event, _)| !cancel(event));
+    }
+
+    /// Remove and return the events waiting to be sent, along with the delay left for each of them.
+    #[cfg(feature = "snapshot")]
+    pub(crate) fn drain(&mut self) -> impl Iterator<Item = (E, Delay)> + '_ {
+        self.events.drain(..)
+    }
+
+    /// Send the `event` once `delay` is over.
+    #[cfg(feature = "snapshot")]
+    pub(crate) fn push(&mut self, event: E, delay: Delay) {
+        self.events.push((event, delay));
+    }
+
+    /// A run condition that returns `true` if there are events waiting to be sent.
+    pub fn has_pending(delayed: Res<DelayedEvents<E>>) -> bool {
+        !delayed.is_empty()
+    }
+
+    /// Send the events that are due, and count down the delays of the others.
+    pub fn send_due(
+        mut delayed: ResMut<DelayedEvents<E>>,
+        time: Option<Res<Time>>,
+        mut writer: EventWriter<E>,
+    ) {
+        let delta = time.map_or(Duration::ZERO, |time| time.delta());
+        delayed.events.retain_mut(|(event, delay)| {
+            let due = match delay {
+                Delay::Time(left) => {
+                    *left = left.saturating_sub(delta);
+                    left.is_zero()
+                }
+                Delay::Frames(0) => true,
+                Delay::Frames(left) => {
+                    *left -= 1;
+                    false
+                }
+            };
+            if due {
+                writer.send(event.clone());
+            }
+            !due
+        });
+    }
+}
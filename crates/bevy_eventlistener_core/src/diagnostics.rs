@@ -0,0 +1,107 @@
+//! Publishes statistics about event dispatch with [`bevy_diagnostic`].
+
+use std::hash::{Hash, Hasher};
+
+use bevy_app::prelude::*;
+use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics, RegisterDiagnostic};
+use bevy_ecs::prelude::*;
+use bevy_utils::{get_short_name, Uuid};
+
+use crate::{event_dispatcher::EventDispatcher, event_listener::EntityEvent};
+
+/// Adds diagnostics for the dispatch of events of type `E`, measured every frame:
+///
+/// - `events`: the number of events propagated.
+/// - `listeners_triggered`: the number of listeners triggered.
+/// - `average_depth`: the average length of each event's propagation path.
+/// - `dispatch_time`: the total time spent propagating events, including running callbacks.
+///
+/// The [`EventListenerPlugin<E>`](crate::EventListenerPlugin) must also be added.
+pub struct EventListenerDiagnosticsPlugin<E>(std::marker::PhantomData<fn() -> E>);
+
+impl<E> Default for EventListenerDiagnosticsPlugin<E> {
+    fn default() -> Self {
+        Self(std::marker::PhantomData)
+    }
+}
+
+impl<E: EntityEvent> Plugin for EventListenerDiagnosticsPlugin<E> {
+    fn build(&self, app: &mut App) {
+        let name = |metric: &str| {
+            format!(
+                "event_listener/{}/{metric}",
+                get_short_name(std::any::type_name::<E>())
+            )
+        };
+        app.register_diagnostic(Diagnostic::new(Self::events(), name("events"), 20))
+            .register_diagnostic(Diagnostic::new(
+                Self::listeners_triggered(),
+                name("listeners_triggered"),
+                20,
+            ))
+            .register_diagnostic(Diagnostic::new(
+                Self::average_depth(),
+                name("average_depth"),
+                20,
+            ))
+            .register_diagnostic(
+                Diagnostic::new(Self::dispatch_time(), name("dispatch_time"), 20).with_suffix("ms"),
+            )
+            .add_systems(Last, Self::diagnostic_system);
+    }
+
+    fn finish(&self, app: &mut App) {
+        match app.world.get_resource_mut::<EventDispatcher<E>>() {
+            Some(mut dispatcher) => dispatcher.measure_time(true),
+            None => panic!(
+                "EventListenerDiagnosticsPlugin<{0}> requires EventListenerPlugin<{0}>",
+                std::any::type_name::<E>()
+            ),
+        }
+    }
+}
+
+impl<E: EntityEvent> EventListenerDiagnosticsPlugin<E> {
+    /// The number of events propagated.
+    pub fn events() -> DiagnosticId {
+        Self::id(0)
+    }
+
+    /// The number of listeners triggered.
+    pub fn listeners_triggered() -> DiagnosticId {
+        Self::id(1)
+    }
+
+    /// The average length of each event's propagation path.
+    pub fn average_depth() -> DiagnosticId {
+        Self::id(2)
+    }
+
+    /// The total time spent propagating events, in milliseconds.
+    pub fn dispatch_time() -> DiagnosticId {
+        Self::id(3)
+    }
+
+    /// Each event type has its own diagnostics, with ids derived from the name of the type.
+    fn id(metric: u64) -> DiagnosticId {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        std::any::type_name::<E>().hash(&mut hasher);
+        DiagnosticId(Uuid::from_u64_pair(hasher.finish(), metric))
+    }
+
+    /// Publish the statistics for the events dispatched this frame, and reset them.
+    pub fn diagnostic_system(
+        mut diagnostics: Diagnostics,
+        mut dispatcher: ResMut<EventDispatcher<E>>,
+    ) {
+        let stats = dispatcher.reset_stats();
+        diagnostics.add_measurement(Self::events(), || stats.events as f64);
+        diagnostics.add_measurement(Self::listeners_triggered(), || {
+            stats.listeners_triggered as f64
+        });
+        diagnostics.add_measurement(Self::average_depth(), || stats.average_depth());
+        diagnostics.add_measurement(Self::dispatch_time(), || {
+            stats.dispatch_time.as_secs_f64() * 1000.0
+        });
+    }
+}
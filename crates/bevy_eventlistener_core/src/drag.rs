@@ -0,0 +1,396 @@
+//! Drag and drop events, driven by pointer input supplied by the app.
+//!
+//! The [`DragPlugin`] reads [`PointerInput`] events, usually sent by a picking backend, and sends
+//! bubbling events for each step of a drag and drop interaction:
+//! - [`DragStart`], [`Drag`] and [`DragEnd`] are sent to the entity being dragged, which is the
+//!   entity that was under the pointer when it was pressed.
+//! - [`DragEnter`], [`DragOver`], [`DragLeave`] and [`Drop`] are sent to the entity under the
+//!   pointer while something is being dragged over it.
+//!
+//! A drag starts the first time the pointer moves while it is pressed, so pressing and releasing a
+//! pointer without moving it does not send any drag events. Dragged entities can attach a payload
+//! to the drag in the [`Drags`] resource, usually in their [`DragStart`] listener, which can be
+//! read by the listeners of the entities it is dropped on.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::Listener,
+//! #     drag::{DragPlugin, DragStart, Drags, Drop, PointerAction, PointerInput},
+//! #     event_listener::On,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_math::Vec2;
+//! #[derive(Clone, Copy, PartialEq, Debug)]
+//! struct Card(u32);
+//!
+//! let mut app = App::new();
+//! app.add_plugins(DragPlugin);
+//!
+//! let card = app
+//!     .world
+//!     .spawn(On::<DragStart>::run(
+//!         |event: Listener<DragStart>, mut drags: ResMut<Drags>| {
+//!             drags.set_payload(event.pointer, Card(7));
+//!         },
+//!     ))
+//!     .id();
+//! let pile = app
+//!     .world
+//!     .spawn(On::<Drop>::run(|event: Listener<Drop>, drags: Res<Drags>| {
+//!         assert_eq!(drags.payload::<Card>(event.pointer), Some(&Card(7)));
+//!     }))
+//!     .id();
+//!
+//! for (action, entity) in [
+//!     (PointerAction::Press, card),
+//!     (PointerAction::Move, pile),
+//!     (PointerAction::Release, pile),
+//! ] {
+//!     app.world.send_event(PointerInput {
+//!         pointer: 0,
+//!         action,
+//!         position: Vec2::ZERO,
+//!         entity: Some(entity),
+//!     });
+//!     app.update();
+//! }
+//! ```
+
+use std::any::Any;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
+use bevy_utils::HashMap;
+
+use crate::{event_listener::EntityEvent, EventDispatchSet, EventListenerPlugin, EventListenerSet};
+
+/// Adds the [`Drags`] resource, and an [`EventListenerPlugin`] for each of the drag and drop
+/// events, which are sent from the [`PointerInput`] events sent by the app.
+///
+/// [`PointerInput`] events should be sent in the [`PreUpdate`] schedule, before the
+/// [`EventListenerSet`]. The drag and drop events are dispatched in the same frame, in the order
+/// [`DragStart`], [`Drag`], [`DragLeave`], [`DragEnter`], [`DragOver`], [`Drop`], [`DragEnd`].
+pub struct DragPlugin;
+
+impl Plugin for DragPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PointerInput>()
+            .init_resource::<Drags>()
+            .add_plugins((
+                EventListenerPlugin::<DragStart>::default(),
+                EventListenerPlugin::<Drag>::default(),
+                EventListenerPlugin::<DragLeave>::default(),
+                EventListenerPlugin::<DragEnter>::default(),
+                EventListenerPlugin::<DragOver>::default(),
+                EventListenerPlugin::<Drop>::default(),
+                EventListenerPlugin::<DragEnd>::default(),
+            ))
+            .add_systems(PreUpdate, send_drag_events.before(EventListenerSet))
+            .configure_sets(
+                PreUpdate,
+                (
+                    EventDispatchSet::<DragStart>::default(),
+                    EventDispatchSet::<Drag>::default(),
+                    EventDispatchSet::<DragLeave>::default(),
+                    EventDispatchSet::<DragEnter>::default(),
+                    EventDispatchSet::<DragOver>::default(),
+                    EventDispatchSet::<Drop>::default(),
+                    EventDispatchSet::<DragEnd>::default(),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// A change to the state of a pointer, usually sent by a picking backend.
+#[derive(Clone, Copy, PartialEq, Debug, Event)]
+pub struct PointerInput {
+    /// Identifies the pointer, so multiple pointers can drag independently.
+    pub pointer: u64,
+    /// What the pointer did.
+    pub action: PointerAction,
+    /// The position of the pointer.
+    pub position: Vec2,
+    /// The topmost entity under the pointer, if any.
+    pub entity: Option<Entity>,
+}
+
+/// The kind of [`PointerInput`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PointerAction {
+    /// The pointer was pressed.
+    Press,
+    /// The pointer moved.
+    Move,
+    /// The pointer was released.
+    Release,
+}
+
+/// The state of a drag in progress, stored in [`Drags`].
+#[derive(Debug)]
+pub struct DragState {
+    /// The entity being dragged, if the pointer was pressed over an entity.
+    pub dragged: Option<Entity>,
+    /// The position the pointer was pressed at.
+    pub start: Vec2,
+    /// The last position of the pointer.
+    pub position: Vec2,
+    /// The entity the pointer is currently over, which will receive the [`Drop`].
+    pub over: Option<Entity>,
+    /// Has the pointer moved since it was pressed?
+    pub dragging: bool,
+    payload: Option<Box<dyn Any + Send + Sync>>,
+}
+
+/// A resource storing the pressed pointers, and the state of their drags.
+///
+/// The state of a drag is kept until the frame after it ends, so the listeners of its [`Drop`] and
+/// [`DragEnd`] can still read its payload.
+#[derive(Resource, Debug, Default)]
+pub struct Drags {
+    drags: HashMap<u64, DragState>,
+    ended: Vec<u64>,
+}
+
+impl Drags {
+    /// The state of the drag made with `pointer`, if it is pressed.
+    pub fn get(&self, pointer: u64) -> Option<&DragState> {
+        self.drags.get(&pointer)
+    }
+
+    /// Attach a payload to the drag made with `pointer`, replacing any existing payload. Does
+    /// nothing if the pointer is not pressed.
+    pub fn set_payload<T: Any + Send + Sync>(&mut self, pointer: u64, payload: T) {
+        if let Some(drag) = self.drags.get_mut(&pointer) {
+            drag.payload = Some(Box::new(payload));
+        }
+    }
+
+    /// The payload attached to the drag made with `pointer`, if it has a payload of type `T`.
+    pub fn payload<T: Any + Send + Sync>(&self, pointer: u64) -> Option<&T> {
+        self.drags
+            .get(&pointer)?
+            .payload
+            .as_ref()?
+            .downcast_ref::<T>()
+    }
+}
+
+/// Defines an event sent to the entity being dragged.
+macro_rules! dragged_event {
+    ($(#[$meta:meta])* $name:ident { $($(#[$field_meta:meta])* $field:ident: $ty:ty,)* }) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Debug, Event)]
+        pub struct $name {
+            /// The entity being dragged.
+            pub target: Entity,
+            /// The pointer dragging the entity.
+            pub pointer: u64,
+            /// The position of the pointer.
+            pub position: Vec2,
+            $($(#[$field_meta])* pub $field: $ty,)*
+        }
+
+        impl EntityEvent for $name {
+            fn target(&self) -> Entity {
+                self.target
+            }
+        }
+    };
+}
+
+/// Defines an event sent to the entity under the pointer while something is dragged.
+macro_rules! drop_target_event {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Debug, Event)]
+        pub struct $name {
+            /// The entity under the pointer.
+            pub target: Entity,
+            /// The pointer dragging the entity.
+            pub pointer: u64,
+            /// The position of the pointer.
+            pub position: Vec2,
+            /// The entity being dragged, if any.
+            pub dragged: Option<Entity>,
+        }
+
+        impl EntityEvent for $name {
+            fn target(&self) -> Entity {
+                self.target
+            }
+        }
+    };
+}
+
+dragged_event!(
+    /// Sent to an entity when the pointer first moves after being pressed over it.
+    DragStart {}
+);
+dragged_event!(
+    /// Sent to the dragged entity every time the pointer moves, including the first time.
+    Drag {
+        /// How far the pointer moved since the last [`Drag`].
+        delta: Vec2,
+        /// How far the pointer moved since it was pressed.
+        distance: Vec2,
+    }
+);
+dragged_event!(
+    /// Sent to the dragged entity when the pointer is released, after the [`Drop`].
+    DragEnd {}
+);
+drop_target_event!(
+    /// Sent to an entity when something is dragged over it.
+    DragEnter
+);
+drop_target_event!(
+    /// Sent to the entity under the pointer every time the pointer moves while dragging.
+    DragOver
+);
+drop_target_event!(
+    /// Sent to an entity when something that was dragged over it is dragged away. This is not sent
+    /// when something is dropped on the entity.
+    DragLeave
+);
+drop_target_event!(
+    /// Sent to the entity under the pointer when the pointer is released, ending a drag.
+    Drop
+);
+
+/// Run the drag and drop state machine for each [`PointerInput`], sending the drag and drop
+/// events.
+#[allow(clippy::too_many_arguments)]
+pub fn send_drag_events(
+    mut input: EventReader<PointerInput>,
+    mut drags: ResMut<Drags>,
+    mut drag_start: EventWriter<DragStart>,
+    mut drag: EventWriter<Drag>,
+    mut drag_end: EventWriter<DragEnd>,
+    mut drag_enter: EventWriter<DragEnter>,
+    mut drag_over: EventWriter<DragOver>,
+    mut drag_leave: EventWriter<DragLeave>,
+    mut drop: EventWriter<Drop>,
+) {
+    let Drags { drags, ended } = drags.as_mut();
+    for pointer in ended.drain(..) {
+        drags.remove(&pointer);
+    }
+    for &PointerInput {
+        pointer,
+        action,
+        position,
+        entity,
+    } in input.iter()
+    {
+        if action == PointerAction::Press {
+            drags.insert(
+                pointer,
+                DragState {
+                    dragged: entity,
+                    start: position,
+                    position,
+                    over: None,
+                    dragging: false,
+                    payload: None,
+                },
+            );
+            ended.retain(|ended| *ended != pointer);
+            continue;
+        }
+        let Some(state) = drags.get_mut(&pointer) else {
+            continue;
+        };
+        if ended.contains(&pointer) {
+            continue;
+        }
+        let dragged = state.dragged;
+        if action == PointerAction::Move {
+            if !state.dragging {
+                state.dragging = true;
+                if let Some(target) = dragged {
+                    drag_start.send(DragStart {
+                        target,
+                        pointer,
+                        position: state.start,
+                    });
+                }
+            }
+            if let Some(target) = dragged {
+                drag.send(Drag {
+                    target,
+                    pointer,
+                    position,
+                    delta: position - state.position,
+                    distance: position - state.start,
+                });
+            }
+            state.position = position;
+            update_over(state, entity, pointer, &mut drag_leave, &mut drag_enter);
+            if let Some(target) = entity {
+                drag_over.send(DragOver {
+                    target,
+                    pointer,
+                    position,
+                    dragged,
+                });
+            }
+            continue;
+        }
+        // The pointer was released.
+        ended.push(pointer);
+        if !state.dragging {
+            continue;
+        }
+        state.position = position;
+        update_over(state, entity, pointer, &mut drag_leave, &mut drag_enter);
+        if let Some(target) = state.over {
+            drop.send(Drop {
+                target,
+                pointer,
+                position,
+                dragged,
+            });
+        }
+        if let Some(target) = dragged {
+            drag_end.send(DragEnd {
+                target,
+                pointer,
+                position,
+            });
+        }
+    }
+}
+
+/// Send a [`DragLeave`] and [`DragEnter`] if the entity under the pointer of a drag has changed.
+fn update_over(
+    state: &mut DragState,
+    entity: Option<Entity>,
+    pointer: u64,
+    drag_leave: &mut EventWriter<DragLeave>,
+    drag_enter: &mut EventWriter<DragEnter>,
+) {
+    if state.over == entity {
+        return;
+    }
+    let (position, dragged) = (state.position, state.dragged);
+    if let Some(target) = state.over {
+        drag_leave.send(DragLeave {
+            target,
+            pointer,
+            position,
+            dragged,
+        });
+    }
+    if let Some(target) = entity {
+        drag_enter.send(DragEnter {
+            target,
+            pointer,
+            position,
+            dragged,
+        });
+    }
+    state.over = entity;
+}
@@ -0,0 +1,203 @@
+//! Events and callbacks that are defined at runtime, for scripting languages and other dynamic
+//! environments that cannot use the generic API.
+//!
+//! A [`DynamicEvent`] is an [`EntityEvent`] whose payload is a `Box<dyn Reflect>`, identified by a
+//! string name instead of a Rust type. Event types are registered at runtime in the
+//! [`DynamicEventTypes`] resource, either by name, or from a reflected Rust type. Dynamic events
+//! propagate through the same listeners, hierarchy, and dispatcher as every other event, and can be
+//! handled by callbacks created at runtime with [`On::run_dynamic`].
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     dynamic::{DynamicEvent, DynamicEventPlugin, DynamicEventTypes},
+//! #     event_listener::On,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_reflect::{DynamicStruct, ReflectRef, Struct};
+//! # #[derive(Resource, Default)]
+//! # struct Healed(f32);
+//! let mut app = App::new();
+//! app.add_plugins(DynamicEventPlugin).init_resource::<Healed>();
+//! app.world.resource_mut::<DynamicEventTypes>().register("heal");
+//!
+//! // A callback defined at runtime, for example by a script.
+//! let player = app
+//!     .world
+//!     .spawn(On::run_dynamic("heal", |world, event| {
+//!         let ReflectRef::Struct(payload) = event.payload.reflect_ref() else {
+//!             return;
+//!         };
+//!         if let Some(amount) = payload.field("amount").and_then(|f| f.downcast_ref::<f32>()) {
+//!             world.resource_mut::<Healed>().0 += amount;
+//!         }
+//!     }))
+//!     .id();
+//!
+//! let mut payload = DynamicStruct::default();
+//! payload.insert("amount", 5.0f32);
+//! let event = app
+//!     .world
+//!     .resource::<DynamicEventTypes>()
+//!     .event("heal", player, Box::new(payload))
+//!     .unwrap();
+//! app.world.send_event(event);
+//! app.update();
+//! assert_eq!(app.world.resource::<Healed>().0, 5.0);
+//! ```
+
+use std::{any::TypeId, borrow::Cow};
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_reflect::{Reflect, TypePath};
+use bevy_utils::HashMap;
+
+use crate::{
+    callbacks::ListenerInput,
+    event_listener::{EntityEvent, On},
+    EventListenerPlugin,
+};
+
+/// Adds the [`DynamicEventTypes`] resource, and an [`EventListenerPlugin`] for [`DynamicEvent`].
+pub struct DynamicEventPlugin;
+
+impl Plugin for DynamicEventPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DynamicEventTypes>()
+            .add_plugins(EventListenerPlugin::<DynamicEvent>::default());
+    }
+}
+
+/// An event whose type is only known at runtime.
+#[derive(Event, Debug)]
+pub struct DynamicEvent {
+    /// The name of the event type, as registered in the [`DynamicEventTypes`].
+    pub event_type: Cow<'static, str>,
+    /// The entity the event targets.
+    pub target: Entity,
+    /// The event data.
+    pub payload: Box<dyn Reflect>,
+    /// Should this event bubble up the hierarchy?
+    pub bubbles: bool,
+}
+
+impl DynamicEvent {
+    /// Create a bubbling event of the given type. Prefer [`DynamicEventTypes::event`], which
+    /// checks that the event type is registered.
+    pub fn new(
+        event_type: impl Into<Cow<'static, str>>,
+        target: Entity,
+        payload: Box<dyn Reflect>,
+    ) -> Self {
+        Self {
+            event_type: event_type.into(),
+            target,
+            payload,
+            bubbles: true,
+        }
+    }
+
+    /// Downcast the payload to a concrete type, if it is one.
+    pub fn payload<T: Reflect>(&self) -> Option<&T> {
+        self.payload.downcast_ref::<T>()
+    }
+}
+
+impl Clone for DynamicEvent {
+    fn clone(&self) -> Self {
+        Self {
+            event_type: self.event_type.clone(),
+            target: self.target,
+            payload: self.payload.clone_value(),
+            bubbles: self.bubbles,
+        }
+    }
+}
+
+impl EntityEvent for DynamicEvent {
+    fn target(&self) -> Entity {
+        self.target
+    }
+
+    fn can_bubble(&self) -> bool {
+        self.bubbles
+    }
+}
+
+/// A resource storing the event types that can be sent as a [`DynamicEvent`].
+///
+/// Event types registered from a Rust type with [`DynamicEventTypes::register_type`] only accept
+/// payloads of that type. Event types registered by name accept any payload.
+#[derive(Resource, Default, Debug)]
+pub struct DynamicEventTypes {
+    types: HashMap<Cow<'static, str>, Option<TypeId>>,
+}
+
+impl DynamicEventTypes {
+    /// Register an event type by name, accepting any payload.
+    pub fn register(&mut self, name: impl Into<Cow<'static, str>>) -> &mut Self {
+        self.types.insert(name.into(), None);
+        self
+    }
+
+    /// Register an event type for the Rust type `T`, named after its [`TypePath::type_path`],
+    /// accepting only payloads of that type.
+    pub fn register_type<T: Reflect + TypePath>(&mut self) -> &mut Self {
+        self.types
+            .insert(T::type_path().into(), Some(TypeId::of::<T>()));
+        self
+    }
+
+    /// Remove an event type. Returns `true` if it was registered.
+    pub fn unregister(&mut self, name: &str) -> bool {
+        self.types.remove(name).is_some()
+    }
+
+    /// Returns `true` if an event type is registered under `name`.
+    pub fn contains(&self, name: &str) -> bool {
+        self.types.contains_key(name)
+    }
+
+    /// The type of payload accepted by the event type registered under `name`, if it was
+    /// registered from a Rust type.
+    pub fn type_id(&self, name: &str) -> Option<TypeId> {
+        self.types.get(name).copied().flatten()
+    }
+
+    /// The names of all registered event types.
+    pub fn names(&self) -> impl Iterator<Item = &str> + '_ {
+        self.types.keys().map(|name| name.as_ref())
+    }
+
+    /// Create an event of the type registered under `name`. Returns `None` if no event type is
+    /// registered under that name, or if the payload is not of the registered type.
+    pub fn event(
+        &self,
+        name: &str,
+        target: Entity,
+        payload: Box<dyn Reflect>,
+    ) -> Option<DynamicEvent> {
+        let (name, type_id) = self.types.get_key_value(name)?;
+        if type_id.is_some_and(|type_id| payload.as_any().type_id() != type_id) {
+            return None;
+        }
+        Some(DynamicEvent::new(name.clone(), target, payload))
+    }
+}
+
+impl On<DynamicEvent> {
+    /// Run a callback every time this listener is triggered by a [`DynamicEvent`] of the given
+    /// type. Other event types continue to propagate as if the listener did not exist.
+    ///
+    /// The callback is an arbitrary closure with exclusive access to the [`World`], so it can be
+    /// created at runtime, for example to call into a script.
+    pub fn run_dynamic(
+        event_type: impl Into<Cow<'static, str>>,
+        callback: impl FnMut(&mut World, &mut ListenerInput<DynamicEvent>) + Send + Sync + 'static,
+    ) -> Self {
+        let event_type = event_type.into();
+        On::run_exclusive(callback)
+            .filter(move |event: &DynamicEvent| event.event_type == event_type)
+    }
+}
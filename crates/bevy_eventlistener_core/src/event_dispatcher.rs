@@ -1,152 +1,2381 @@
-use bevy_ecs::prelude::*;
-use bevy_hierarchy::Parent;
+use std::{any::TypeId, hash::Hasher};
+
+use bevy_core::FrameCount;
+#[cfg(feature = "trace")]
+use bevy_core::Name;
+use bevy_ecs::{prelude::*, query::Has, system::SystemParam};
+use bevy_hierarchy::{Children, Parent};
+use bevy_time::Time;
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::{debug_span, error, info, warn};
 use bevy_utils::{HashMap, HashSet};
+use smallvec::SmallVec;
 
 use crate::{
-    callbacks::{CallbackSystem, ListenerInput},
-    event_listener::On,
+    callback_errors::PanicIsolation,
+    callbacks::{
+        BatchedEvent, CallbackSystem, EventPhase, ListenerData, ListenerInput, ListenerInputs,
+        ListenerResponses,
+    },
+    checksum::{event_checksum, StableHasher},
+    event_listener::{
+        DefaultAction, GlobalListeners, ListenerGroups, ListenerId, Listeners, On, Propagation,
+        PropagationBarrier,
+    },
+    history::{EventHistory, HistoryEntry, TriggeredListener},
+    hooks::EventHooks,
+    source::{EventSource, EventSources},
+    traversal::Traversal,
     EntityEvent,
 };
 
+/// An event waiting to be propagated by the [`EventDispatcher`], along with the target it is
+/// propagating from, the leaf node of its listener graph if it has one, and its source.
+pub(crate) type PendingEvent<E> = (E, Entity, Option<Entity>, Option<EventSource>);
+
+/// The resources that see events of type `E` besides the entity listeners, so events must not be
+/// discarded early if they exist.
+type Observers<'w, E> = (
+    Option<Res<'w, GlobalListeners<E>>>,
+    Option<Res<'w, EventHistory<E>>>,
+);
+
 /// Builds and executes the event listener callback graph.
 ///
-/// To traverse the entity hierarchy and read events without requiring callbacks implement `Clone`,
-/// we need to extract the callbacks out of their components before they can be run. This is because
-/// running callbacks requires mutable access to the [`World`], which we can't do if we are also
-/// trying to mutate the [`On`]'s inner callback state via `run` at the same time.
+/// Events are dispatched in two steps. First, the [`EventDispatcher::build`] system walks the
+/// entity hierarchy for each event, building a graph of the entities with event listeners. This
+/// only needs read access to the hierarchy, and is able to run in parallel with other systems.
+/// Then, the exclusive [`EventDispatcher::bubble_events`] system walks this graph to run the
+/// callbacks. Running callbacks requires mutable access to the [`World`], so each callback is taken
+/// out of its listener right before it is run, and returned immediately after.
+///
+/// Callbacks are always run one at a time, even for events whose propagation paths are disjoint.
+/// A callback is an arbitrary system, so it can access any part of the world, including the
+/// listeners and hierarchy of other events, and listeners can observe changes made by the
+/// listeners that ran before them, such as stopped propagation. Running callbacks in parallel
+/// would require knowing the access of every callback up front, and would change the order that
+/// listeners observe each other's effects in. Only graph construction, which does the bulk of the
+/// traversal work, runs in parallel with other systems.
 #[derive(Resource)]
 pub struct EventDispatcher<E: EntityEvent> {
     /// All the events of type `E` that were emitted this frame, and encountered an [`On<E>`] while
-    /// traversing the entity hierarchy. The `Entity` in the tuple is the leaf node to use when
-    /// traversing the listener graph.
-    pub(crate) events: Vec<(E, Entity)>,
+    /// traversing the entity hierarchy, along with the target the event is propagating from. Events
+    /// with multiple targets are included once for each target. The last `Entity` in the tuple is
+    /// the leaf node to use when traversing the listener graph. If there are [`GlobalListeners`], or
+    /// events are being forwarded, events that did not encounter any listeners are also included,
+    /// without a leaf node. The [`EventSource`] is included if it was recorded.
+    pub(crate) events: Vec<PendingEvent<E>>,
+    /// The events that could not be propagated within the [`EventDispatcher::with_frame_budget`]
+    /// last frame, along with their target and source. These are propagated before any new events.
+    pub(crate) carried: Vec<(E, Entity, Option<EventSource>)>,
+    /// The events that were sent while events of type `E` were paused with
+    /// [`EventDispatchControl::pause`], along with their source, in the order they were sent.
+    pub(crate) held: Vec<(E, Option<EventSource>)>,
+    /// The maximum time spent propagating events each frame, if there is one.
+    pub(crate) frame_budget: Option<std::time::Duration>,
+    /// The maximum number of events waiting to be propagated, and what to do with the rest.
+    pub(crate) queue_limit: Option<(usize, QueueOverflow)>,
     /// Traversing the entity hierarchy for each event can visit the same entity multiple times.
-    /// Storing the callbacks for each of these potentially visited entities in a graph structure is
-    /// necessary for a few reasons:
+    /// Storing the entities with listeners in a graph structure lets us skip over entities without
+    /// listeners, and jump to the next listener in the hierarchy without unnecessary traversal.
+    /// When bubbling many events of the same type `E` through the same entity tree, this can save a
+    /// significant amount of work.
     ///
-    /// - Callback systems cannot implement `Clone`, so we can only have one copy of each callback
-    ///   system.
-    /// - For complex hierarchies, this is more memory efficient.
-    /// - This allows us to jump to the next listener in the hierarchy without unnecessary
-    ///   traversal. When bubbling many events of the same type `E` through the same entity tree,
-    ///   this can save a significant amount of work.
-    pub(crate) listener_graph: HashMap<Entity, (CallbackSystem, Option<Entity>)>,
+    /// Each entity with a listener maps to the next ancestor with a listener, if there is one.
+    ///
+    /// If [`EventDispatcher::cache_listener_graph`] is enabled, the graph is kept between frames,
+    /// and only rebuilt when the hierarchy or the set of entities with listeners changes.
+    pub(crate) listener_graph: HashMap<Entity, Option<Entity>>,
+    /// Should the listener graph be kept between frames?
+    pub(crate) cache_graph: bool,
+    /// Should events only trigger the listeners on their target, without traversing the hierarchy?
+    pub(crate) target_only: bool,
+    /// Resolves the path of events dispatched with [`EventDispatcher::dispatch_now`], through the
+    /// [`Traversal`] component set with [`EventDispatcher::with_traversal`].
+    pub(crate) resolve_path: fn(&World, Entity, &mut Vec<Entity>),
+    /// Scratch space used to store the chain of listeners an event will visit while it is being
+    /// propagated, starting at the leaf node. This is lent to the [`ListenerInput`] of each event,
+    /// so it can be exposed with [`ListenerInput::path`].
+    pub(crate) path: Vec<Entity>,
+    /// Scratch space used to store the entities that a broadcast event has yet to visit, along with
+    /// their depth below the target.
+    pub(crate) stack: Vec<(Entity, usize)>,
+    /// Scratch space used to store the listeners of the entity currently being visited, along with
+    /// their priority.
+    pub(crate) slots: Vec<(i32, ListenerSlot)>,
+    /// The events that reached each batched listener this frame.
+    pub(crate) batches: ListenerBatches<E>,
+    /// The order events are propagated in.
+    pub(crate) order: EventOrder,
+    /// How events sent to the same target in the same frame are combined, if they are.
+    pub(crate) coalesce: Option<Coalesce<E>>,
+    /// Which events are sent as a [`DispatchedEvent`] once they have finished propagating.
+    pub(crate) forward: Option<ForwardEvents>,
+    /// Statistics about the events dispatched since they were last reset.
+    pub(crate) stats: DispatchStats,
+    /// The number of events propagated since the dispatcher was created.
+    pub(crate) total_events: usize,
+    /// The number of listeners triggered since the dispatcher was created.
+    pub(crate) total_listeners_triggered: usize,
+    /// The maximum depth of nested dispatch, if events sent by callbacks are dispatched
+    /// immediately, see [`EventDispatcher::with_nested_dispatch`].
+    pub(crate) nested: Option<usize>,
+    /// The ids of the events that were already dispatched by nested dispatch, which are skipped
+    /// the next time events are read.
+    pub(crate) nested_ids: HashSet<usize>,
+    /// The ids of the events that could not be dispatched by nested dispatch, and are left to be
+    /// read the next time the dispatch systems run.
+    pub(crate) deferred_ids: HashSet<usize>,
+    /// Hashes the events for the checksums, if the dispatcher is deterministic.
+    pub(crate) hash_event: Option<fn(&E, &mut StableHasher)>,
+    /// The checksum of every event dispatched since the dispatcher was created.
+    pub(crate) checksum: StableHasher,
+    /// The checksum of the events dispatched since [`EventDispatcher::reset_frame_checksum`] last
+    /// ran.
+    pub(crate) frame_checksum: StableHasher,
+    /// Should the time taken to propagate each event be measured?
+    pub(crate) measure_time: bool,
+    /// Should each event be logged once it has finished propagating?
+    #[cfg(feature = "trace")]
+    pub(crate) debug_logging: bool,
+    /// Should a warning be logged when an event reaches an entity that has been despawned?
+    #[cfg(feature = "trace")]
+    pub(crate) warn_despawned: bool,
+    /// Scratch space used to record the listeners triggered by each event, if debug logging or the
+    /// [`EventHistory`] is enabled.
+    pub(crate) triggered_log: Vec<TriggeredListener>,
+}
+
+/// Statistics about the events dispatched by an [`EventDispatcher`], since the statistics were
+/// last reset with [`EventDispatcher::reset_stats`].
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct DispatchStats {
+    /// The number of events propagated. Events with multiple [`targets`](EntityEvent::targets)
+    /// are counted once for each target.
+    pub events: usize,
+    /// The number of listeners triggered.
+    pub listeners_triggered: usize,
+    /// The sum of the length of each event's propagation path, see [`ListenerInput::path`].
+    pub total_depth: usize,
+    /// The total time spent propagating events, including running callbacks and default actions.
+    /// This is only measured if [`EventDispatcher::measure_time`] is enabled.
+    pub dispatch_time: std::time::Duration,
+}
+
+impl DispatchStats {
+    /// The average length of each event's propagation path.
+    pub fn average_depth(&self) -> f64 {
+        match self.events {
+            0 => 0.0,
+            events => self.total_depth as f64 / events as f64,
+        }
+    }
+}
+
+/// Selects which events are sent as a [`DispatchedEvent`] once they have finished propagating, see
+/// [`EventListenerPlugin::forward_events`](crate::EventListenerPlugin::forward_events).
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ForwardEvents {
+    /// Only forward events that did not trigger any listeners.
+    Unhandled,
+    /// Forward every event.
+    All,
+}
+
+/// An event that has finished propagating through the hierarchy.
+///
+/// When enabled with [`EventListenerPlugin::forward_events`](crate::EventListenerPlugin::forward_events),
+/// these are sent once an event has finished propagating, so normal systems can react to them with
+/// an [`EventReader`]. For example, this can be used to react to clicks that no listener handled.
+/// Events with multiple [`targets`](EntityEvent::targets) are forwarded once for each target.
+#[derive(Event, Clone, Debug)]
+pub struct DispatchedEvent<E: EntityEvent> {
+    /// The final state of the event data, after any changes made by listeners.
+    pub event: E,
+    /// The target the event propagated from.
+    pub target: Entity,
+    /// Did the event trigger any listeners? Global listeners count, the default action does not.
+    pub was_handled: bool,
+}
+
+/// The order in which the events of a single type, sent in the same frame, are propagated.
+///
+/// Either way, the order is deterministic: it only depends on the order the events were sent in.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum EventOrder {
+    /// Events are propagated in the order they were sent. Events with multiple
+    /// [`targets`](EntityEvent::targets) are propagated from each target in order, before the next
+    /// event.
+    #[default]
+    SendOrder,
+    /// Events are grouped by their target, and groups are propagated in the order their first event
+    /// was sent. Within a group, events are propagated in the order they were sent.
+    GroupedByTarget,
+}
+
+/// How the events of a single type, sent to the same target in the same frame, are combined into
+/// a single event before they are propagated, see
+/// [`EventListenerPlugin::coalesce_events`](crate::EventListenerPlugin::coalesce_events).
+///
+/// This is useful for high frequency events like pointer movement, where listeners only need the
+/// latest or accumulated value, not every intermediate sample. The combined event is propagated in
+/// the position of the first event sent to the target. Events with multiple
+/// [`targets`](EntityEvent::targets) are combined separately for each target.
+pub enum Coalesce<E> {
+    /// Only propagate the last event sent to each target.
+    Latest,
+    /// Combine the events sent to each target with a function that merges a later event into an
+    /// earlier one, in the order they were sent.
+    Merge(fn(&mut E, E)),
+}
+
+impl<E> Coalesce<E> {
+    /// Combine the `later` event into the `earlier` one.
+    fn apply(&self, earlier: &mut E, later: E) {
+        match self {
+            Coalesce::Latest => *earlier = later,
+            Coalesce::Merge(merge) => merge(earlier, later),
+        }
+    }
+}
+
+impl<E> Clone for Coalesce<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for Coalesce<E> {}
+
+impl<E> std::fmt::Debug for Coalesce<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Coalesce::Latest => write!(f, "Latest"),
+            Coalesce::Merge(_) => write!(f, "Merge"),
+        }
+    }
+}
+
+/// What happens when more events of a single type are waiting to be propagated than the limit
+/// set with [`EventListenerPlugin::with_queue_limit`](crate::EventListenerPlugin::with_queue_limit).
+///
+/// The limit counts the events sent since the last time events were dispatched, along with any
+/// events carried over from the last frame by a
+/// [`frame budget`](EventDispatcher::with_frame_budget). Events over the limit are discarded before
+/// they are propagated, or even resolved, so a runaway sender can't stall dispatch.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum QueueOverflow {
+    /// Discard the oldest events, starting with the events carried over from the last frame.
+    DropOldest,
+    /// Discard the newest events.
+    DropNewest,
+    /// Panic in debug builds, so the overflow is noticed during development, and discard the
+    /// newest events in release builds.
+    PanicInDebug,
+    /// Discard the newest events, and log a warning. Warnings are only logged with the `trace`
+    /// feature.
+    Warn,
+}
+
+/// The query used to find listeners while walking up the entity hierarchy.
+type ListenerQuery<'w, 's, E, T> = Query<
+    'w,
+    's,
+    (
+        Option<&'static On<E>>,
+        Option<&'static Listeners<E>>,
+        Option<&'static T>,
+        Has<PropagationBarrier>,
+    ),
+>;
+
+/// The events that reached each batched listener while events were propagating, so each batched
+/// callback can be run once, after every event has finished propagating. See
+/// [`On::run_batched`].
+pub(crate) struct ListenerBatches<E: EntityEvent> {
+    /// The position of each listener's batch in `batches`.
+    index: HashMap<ListenerSlot, usize>,
+    batches: Vec<(ListenerSlot, Vec<BatchedEvent<E>>)>,
+}
+
+impl<E: EntityEvent> Default for ListenerBatches<E> {
+    fn default() -> Self {
+        Self {
+            index: HashMap::new(),
+            batches: Vec::new(),
+        }
+    }
+}
+
+impl<E: EntityEvent> ListenerBatches<E> {
+    /// Add an event to the batch of the listener in `slot`.
+    fn push(&mut self, slot: ListenerSlot, event: BatchedEvent<E>) {
+        let index = *self.index.entry(slot).or_insert_with(|| {
+            self.batches.push((slot, Vec::new()));
+            self.batches.len() - 1
+        });
+        self.batches[index].1.push(event);
+    }
+
+    /// Run each batched listener once with the events that reached it, in the order the listeners
+    /// were first reached.
+    fn run(&mut self, world: &mut World) {
+        self.index.clear();
+        for (slot, events) in self.batches.drain(..) {
+            let Some(mut on) = slot.get_mut::<E>(world) else {
+                continue;
+            };
+            let once = on.once;
+            let mut callback = on.take();
+            let inputs = ListenerInputs {
+                listener: slot.entity().unwrap_or(Entity::PLACEHOLDER),
+                events,
+                data: on.data.clone(),
+            };
+            callback.run_batch(world, inputs);
+            return_callback(world, slot, callback, once);
+        }
+    }
+}
+
+/// The query used to find changes that invalidate a cached listener graph.
+type GraphChangesQuery<'w, 's, E, T> = Query<
+    'w,
+    's,
+    (),
+    Or<(
+        Added<On<E>>,
+        Added<Listeners<E>>,
+        Added<PropagationBarrier>,
+        Changed<T>,
+    )>,
+>;
+
+/// The system params used by [`EventDispatcher::build`] to find out if a cached listener graph is
+/// out of date, see [`EventDispatcher::cache_listener_graph`].
+#[derive(SystemParam)]
+pub struct ListenerGraphChanges<'w, 's, E: EntityEvent, T: Traversal> {
+    changed: GraphChangesQuery<'w, 's, E, T>,
+    removed_on: RemovedComponents<'w, 's, On<E>>,
+    removed_listeners: RemovedComponents<'w, 's, Listeners<E>>,
+    removed_barriers: RemovedComponents<'w, 's, PropagationBarrier>,
+    removed_traversal: RemovedComponents<'w, 's, T>,
+}
+
+impl<'w, 's, E: EntityEvent, T: Traversal> ListenerGraphChanges<'w, 's, E, T> {
+    /// Returns `true` if listeners or relationships were added, changed, or removed since this was
+    /// last called.
+    fn any(&mut self) -> bool {
+        // Every reader must be cleared, so the same removals are not seen again next time.
+        let removed = [
+            take_removed(&mut self.removed_on),
+            take_removed(&mut self.removed_listeners),
+            take_removed(&mut self.removed_barriers),
+            take_removed(&mut self.removed_traversal),
+        ];
+        removed.contains(&true) || !self.changed.is_empty()
+    }
+}
+
+/// Consume the removals seen by `removed`, returning `true` if there were any. Removals that
+/// happened too long ago to be read are counted too.
+fn take_removed<C: Component>(removed: &mut RemovedComponents<C>) -> bool {
+    let missed = removed
+        .events()
+        .is_some_and(|events| removed.reader().missed_events(events) > 0);
+    let any = missed || !removed.is_empty();
+    removed.clear();
+    any
+}
+
+/// The query used to check if any entity has a listener.
+type WithListenersQuery<'w, 's, E> = Query<'w, 's, (), Or<(With<On<E>>, With<Listeners<E>>)>>;
+
+/// Identifies one of the listeners visited by an event while it is propagating.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub(crate) enum ListenerSlot {
+    /// The entity's [`On`] component.
+    Component(Entity),
+    /// A listener in the entity's [`Listeners`] component.
+    List(Entity, ListenerId),
+    /// A listener in the [`GlobalListeners`] resource.
+    Global(ListenerId),
+}
+
+impl ListenerSlot {
+    /// Add the slots for all listeners of a node in the propagation path to `slots`, along with
+    /// their priority. A node of `None` represents the global listeners.
+    pub(crate) fn collect<E: EntityEvent>(
+        world: &World,
+        node: Option<Entity>,
+        slots: &mut Vec<(i32, ListenerSlot)>,
+    ) {
+        let Some(entity) = node else {
+            if let Some(global) = world.get_resource::<GlobalListeners<E>>() {
+                slots.extend(
+                    global
+                        .listeners
+                        .listeners
+                        .iter()
+                        .map(|(id, on)| (on.priority, ListenerSlot::Global(*id))),
+                );
+            }
+            return;
+        };
+        let Some(entity_ref) = world.get_entity(entity) else {
+            return;
+        };
+        if let Some(on) = entity_ref.get::<On<E>>() {
+            slots.push((on.priority, ListenerSlot::Component(entity)));
+        }
+        if let Some(list) = entity_ref.get::<Listeners<E>>() {
+            slots.extend(
+                list.listeners
+                    .iter()
+                    .map(|(id, on)| (on.priority, ListenerSlot::List(entity, *id))),
+            );
+        }
+    }
+
+    /// The entity the listener in this slot is on, or `None` for global listeners.
+    pub(crate) fn entity(self) -> Option<Entity> {
+        match self {
+            ListenerSlot::Component(entity) | ListenerSlot::List(entity, _) => Some(entity),
+            ListenerSlot::Global(_) => None,
+        }
+    }
+
+    /// Get mutable access to the listener in this slot, if it still exists.
+    pub(crate) fn get_mut<E: EntityEvent>(self, world: &mut World) -> Option<Mut<'_, On<E>>> {
+        let (listeners, id) = match self {
+            ListenerSlot::Component(entity) => return world.get_mut::<On<E>>(entity),
+            ListenerSlot::List(entity, id) => (world.get_mut::<Listeners<E>>(entity)?, id),
+            ListenerSlot::Global(id) => (
+                world
+                    .get_resource_mut::<GlobalListeners<E>>()?
+                    .map_unchanged(|global| &mut global.listeners),
+                id,
+            ),
+        };
+        let index = listeners.index_of(id)?;
+        Some(listeners.map_unchanged(|listeners| &mut listeners.listeners[index].1))
+    }
+
+    /// Remove the listener in this slot.
+    pub(crate) fn remove<E: EntityEvent>(self, world: &mut World) {
+        match self {
+            ListenerSlot::Component(entity) => {
+                if let Some(mut entity) = world.get_entity_mut(entity) {
+                    entity.remove::<On<E>>();
+                }
+            }
+            ListenerSlot::List(entity, id) => {
+                if let Some(mut listeners) = world.get_mut::<Listeners<E>>(entity) {
+                    listeners.remove(id);
+                }
+            }
+            ListenerSlot::Global(id) => {
+                if let Some(mut global) = world.get_resource_mut::<GlobalListeners<E>>() {
+                    global.remove(id);
+                }
+            }
+        }
+    }
 }
 
 impl<E: EntityEvent> EventDispatcher<E> {
     /// For each event, we need to build a chain of event listeners in the entity tree starting at
     /// the event's target. This does not need a node for every entity in the tree, instead, only
     /// the entities with event listeners are included.
-    pub fn build(
+    ///
+    /// The hierarchy is traversed through the [`Traversal`] component `T`, which is usually
+    /// [`Parent`](bevy_hierarchy::Parent).
+    ///
+    /// If no entity has a listener for `E`, and there is nothing else that needs to see the events,
+    /// the events are discarded without traversing the hierarchy. Checking this only depends on the
+    /// number of archetypes, not the number of entities.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build<T: Traversal>(
         mut events: EventReader<E>,
-        mut listeners: Query<(Option<&mut On<E>>, Option<&Parent>)>,
+        listeners: ListenerQuery<E, T>,
+        with_listeners: WithListenersQuery<E>,
         mut dispatcher: ResMut<EventDispatcher<E>>,
         mut dead_branch_nodes: Local<HashSet<Entity>>,
         mut target_cache: Local<HashMap<Entity, Entity>>,
+        (global, history): Observers<E>,
+        default_action: Option<Res<DefaultAction<E>>>,
+        mut group_order: Local<HashMap<Entity, usize>>,
+        mut uncoalesced: Local<Vec<PendingEvent<E>>>,
+        mut changes: ListenerGraphChanges<E, T>,
+        mut carried: Local<Vec<(E, Entity, Option<EventSource>)>>,
+        mut hooks: Option<ResMut<EventHooks<E>>>,
+        mut intercepted: Local<Vec<(E, Option<EventSource>)>>,
+        control: Option<Res<EventDispatchControl>>,
+        mut sources: Option<ResMut<EventSources<E>>>,
     ) {
         // Reuse allocated memory
         dispatcher.events.clear();
-        dispatcher.listener_graph.clear();
-        dead_branch_nodes.clear();
-        target_cache.clear();
+        // Paused events are held until they are resumed, and events carried over from the last
+        // frame stay where they are.
+        if control.is_some_and(|control| control.is_paused::<E>()) {
+            let dispatcher = dispatcher.as_mut();
+            let nested_ids = &dispatcher.nested_ids;
+            let sent = events
+                .iter_with_id()
+                .filter(|(_, id)| !nested_ids.contains(&id.id))
+                .map(|(event, id)| {
+                    let source = sources.as_mut().and_then(|sources| sources.take(id.id));
+                    (event.clone(), source)
+                });
+            dispatcher.held.extend(sent);
+            dispatcher.nested_ids.clear();
+            dispatcher.deferred_ids.clear();
+            if let Some(sources) = sources.as_mut() {
+                sources.clear();
+            }
+            return;
+        }
+        std::mem::swap(&mut dispatcher.carried, &mut carried);
+        if !dispatcher.cache_graph || changes.any() {
+            dispatcher.listener_graph.clear();
+            dead_branch_nodes.clear();
+            target_cache.clear();
+        }
+
+        // Global listeners need to see every event, even if it never encounters an entity listener.
+        // Forwarding unhandled events, hooks, the history and the default action also need them.
+        let keep_all = dispatcher.forward.is_some()
+            || global.is_some_and(|g| !g.is_empty())
+            || history.is_some()
+            || default_action.is_some()
+            || hooks.as_ref().is_some_and(|hooks| hooks.has_after());
 
-        for event in events.iter() {
+        // Nothing can observe the events when there are no entity listeners, global listeners,
+        // forwarder, after hooks, history or default action, so everything is cleared.
+        if with_listeners.is_empty() && !keep_all {
+            events.clear();
+            carried.clear();
+            dispatcher.held.clear();
+            dispatcher.nested_ids.clear();
+            dispatcher.deferred_ids.clear();
+            if let Some(sources) = sources.as_mut() {
+                sources.clear();
+            }
+            return;
+        }
+
+        // Events that were held while paused are dispatched before the events sent since.
+        let mut held = std::mem::take(&mut dispatcher.held);
+
+        // Hooks may change or cancel events before their targets are known. This reads every
+        // event, so only the intercepted events are left to iterate over below.
+        intercepted.clear();
+        // Events that were already dispatched by nested dispatch are skipped. They were all sent
+        // since the events were last read.
+        let nested_ids = std::mem::take(&mut dispatcher.nested_ids);
+        let mut pending = held.len() + events.len().saturating_sub(nested_ids.len());
+        let mut sent = held
+            .iter_mut()
+            .map(|(event, source)| (&*event, source.take()))
+            .chain(
+                events
+                    .iter_with_id()
+                    .filter(|(_, id)| !nested_ids.contains(&id.id))
+                    .map(|(event, id)| {
+                        let source = sources.as_mut().and_then(|sources| sources.take(id.id));
+                        (event, source)
+                    }),
+            );
+        if let Some(hooks) = hooks.as_mut().filter(|hooks| hooks.has_before()) {
+            for (event, source) in sent.by_ref() {
+                let mut event = event.clone();
+                if hooks.run_before(&mut event) {
+                    intercepted.push((event, source));
+                }
+            }
+            pending = intercepted.len();
+        }
+
+        // Events over the limit are discarded before they are resolved. Events cancelled by hooks
+        // don't count towards the limit.
+        let (skip, take) = match dispatcher.queue_limit {
+            Some((limit, overflow)) if carried.len() + pending > limit => {
+                limit_queue(limit, overflow, &mut carried, pending)
+            }
+            _ => (0, usize::MAX),
+        };
+
+        // Events carried over from last frame are resolved again, since the hierarchy may have
+        // changed. They are propagated first, and are not coalesced or reordered with new events.
+        let carried_len = carried.len();
+        let carried_events = carried
+            .iter_mut()
+            .map(|(event, target, source)| (&*event, *target, source.take()));
+        let new_events = sent
+            .chain(
+                intercepted
+                    .iter_mut()
+                    .map(|(event, source)| (&*event, source.take())),
+            )
+            .skip(skip)
+            .take(take)
+            .flat_map(|(event, source)| {
+                event
+                    .targets()
+                    .into_iter()
+                    .map(move |target| (event, target, source.clone()))
+            });
+        let mut new_start = None;
+        for (index, (event, target, source)) in carried_events.chain(new_events).enumerate() {
+            if index == carried_len {
+                new_start = Some(dispatcher.events.len());
+            }
+            // Events without a target skip the hierarchy, and are only seen by global listeners.
+            if target == Entity::PLACEHOLDER {
+                if keep_all {
+                    dispatcher
+                        .events
+                        .push((event.to_owned(), target, None, source));
+                }
+                continue;
+            }
+            // Broadcast events travel down the hierarchy, so they don't use the listener graph.
+            if event.propagation().is_downward() {
+                dispatcher
+                    .events
+                    .push((event.to_owned(), target, None, source));
+                continue;
+            }
+            // Only the target's own listeners are needed, so the hierarchy isn't traversed.
+            if dispatcher.target_only {
+                let has_listener = listeners.get(target).is_ok_and(|(on, list, ..)| {
+                    on.is_some() || list.is_some_and(|list| !list.is_empty())
+                });
+                if has_listener || keep_all {
+                    let leaf = has_listener.then_some(target);
+                    dispatcher
+                        .events
+                        .push((event.to_owned(), target, leaf, source));
+                }
+                continue;
+            }
             // if the target belongs to a dead branch, exit early.
-            if dead_branch_nodes.contains(&event.target()) {
+            if dead_branch_nodes.contains(&target) {
+                if keep_all {
+                    dispatcher
+                        .events
+                        .push((event.to_owned(), target, None, source));
+                }
                 continue;
             }
             // if the target has already been used to traverse the graph, use the cached value.
-            if let Some(first_listener) = target_cache.get(&event.target()) {
-                dispatcher.events.push((event.to_owned(), *first_listener));
+            if let Some(first_listener) = target_cache.get(&target) {
+                dispatcher
+                    .events
+                    .push((event.to_owned(), target, Some(*first_listener), source));
                 continue;
             }
             build_branch_depth_first(
                 event,
+                target,
                 &mut dispatcher,
-                &mut listeners,
+                &listeners,
                 &mut dead_branch_nodes,
                 &mut target_cache,
+                source.clone(),
             );
+            // The target is only cached if a listener was found. Otherwise, the target is in a
+            // dead branch, or no longer exists.
+            if keep_all && !target_cache.contains_key(&target) {
+                dispatcher
+                    .events
+                    .push((event.to_owned(), target, None, source));
+            }
         }
-    }
+        carried.clear();
+        // Discard the events that were not taken because of the queue limit.
+        events.clear();
+        held.clear();
+        dispatcher.held = held;
+        dispatcher.nested_ids = nested_ids;
+        dispatcher.nested_ids.clear();
+        dispatcher.deferred_ids.clear();
+        if let Some(sources) = sources.as_mut() {
+            sources.clear();
+        }
+        let new_start = new_start.unwrap_or(dispatcher.events.len());
 
-    /// Once we are done bubbling, we need to add the callback systems back into the components we
-    /// moved them from when building the tree.
-    pub fn cleanup(mut listeners: Query<&mut On<E>>, mut callbacks: ResMut<EventDispatcher<E>>) {
-        for (entity, (callback, _)) in callbacks.listener_graph.drain() {
-            if let Ok(mut listener) = listeners.get_mut(entity) {
-                listener.callback = callback;
+        if let Some(coalesce) = dispatcher.coalesce {
+            // Each target keeps the position of its first event, which later events are merged
+            // into.
+            group_order.clear();
+            uncoalesced.extend(dispatcher.events.drain(new_start..));
+            for (event, target, leaf, source) in uncoalesced.drain(..) {
+                match group_order.get(&target) {
+                    // The merged event keeps the source of the first event.
+                    Some(&index) => coalesce.apply(&mut dispatcher.events[index].0, event),
+                    None => {
+                        group_order.insert(target, dispatcher.events.len());
+                        dispatcher.events.push((event, target, leaf, source));
+                    }
+                }
             }
         }
+
+        if dispatcher.order == EventOrder::GroupedByTarget {
+            // Groups are ordered by the position of the first event sent to each target. The sort
+            // is stable, preserving the send order within each group.
+            group_order.clear();
+            for (index, (_, target, ..)) in dispatcher.events[new_start..].iter().enumerate() {
+                group_order.entry(*target).or_insert(index);
+            }
+            dispatcher.events[new_start..].sort_by_key(|(_, target, ..)| group_order[target]);
+        }
+    }
+
+    /// A run condition that returns `true` if [`EventDispatcher::build`] found events to
+    /// propagate. This is used to skip the exclusive [`EventDispatcher::bubble_events`] system
+    /// when none of the events sent this frame can trigger a listener.
+    pub fn has_events(dispatcher: Res<EventDispatcher<E>>) -> bool {
+        !dispatcher.events.is_empty()
     }
 
-    /// Bubbles [`EntityEvent`]s up the entity hierarchy, running  callbacks.
+    /// A run condition that returns `true` if some events were carried over from the last frame,
+    /// because they could not be propagated within the
+    /// [`frame budget`](EventDispatcher::with_frame_budget).
+    pub fn has_carried_events(dispatcher: Res<EventDispatcher<E>>) -> bool {
+        !dispatcher.carried.is_empty()
+    }
+
+    /// A run condition that returns `true` if some events were held while events of type `E` were
+    /// paused with [`EventDispatchControl::pause`].
+    pub fn has_held_events(dispatcher: Res<EventDispatcher<E>>) -> bool {
+        !dispatcher.held.is_empty()
+    }
+
+    /// The number of events held while events of type `E` are paused with
+    /// [`EventDispatchControl::pause`].
+    pub fn held_events(&self) -> usize {
+        self.held.len()
+    }
+
+    /// The number of events carried over to the next frame, because they could not be propagated
+    /// within the [`frame budget`](EventDispatcher::with_frame_budget).
+    pub fn carried_events(&self) -> usize {
+        self.carried.len()
+    }
+
+    /// Once we are done bubbling, clear the listener graph, unless it is cached. The memory is
+    /// retained, and reused the next time the graph is built.
+    pub fn cleanup(mut dispatcher: ResMut<EventDispatcher<E>>) {
+        if !dispatcher.cache_graph {
+            dispatcher.listener_graph.clear();
+        }
+    }
+
+    /// Propagates [`EntityEvent`]s through the entity hierarchy, running callbacks.
+    ///
+    /// Each event is first sent down the chain of listeners from the root to the target, running
+    /// capture listeners, then bubbled back up from the target to the root, running all other
+    /// listeners. If the event cannot bubble, only the capture listeners and the listeners on the
+    /// target itself are run. [`GlobalListeners`] behave as if they were on a root above every
+    /// hierarchy: global capture listeners are run before any entity listeners, and all other
+    /// global listeners are run once the event has finished bubbling.
+    ///
+    /// Events with multiple [`targets`](EntityEvent::targets) propagate independently from each
+    /// target. Events with [`Propagation::Broadcast`] are instead sent down the hierarchy from the target to
+    /// all of its descendants, and events with [`Propagation::FindHandler`] are sent down until
+    /// they reach a listener.
+    ///
+    /// Once an event has finished propagating, its [`DefaultAction`] is run, unless a listener
+    /// prevented it.
+    ///
+    /// If enabled, events are then forwarded as a [`DispatchedEvent`].
+    ///
+    /// Events are propagated one at a time, in the [`EventOrder`] of the dispatcher. If the
+    /// dispatcher has a [`frame budget`](EventDispatcher::with_frame_budget), propagation stops
+    /// once the budget is used up, and the remaining events are propagated next frame, before any
+    /// new events.
+    ///
+    /// This is an exclusive system, because callbacks are systems that can access any part of the
+    /// world, and a callback can change what the next callback sees, for example by stopping
+    /// propagation or editing the event. Only the callbacks need exclusive access: paths are
+    /// resolved by [`EventDispatcher::build`], which runs in parallel with other systems, and this
+    /// system is skipped in frames without events. Apps that send events every frame, and whose
+    /// listeners only use commands, can keep the rest of their schedule parallel by dispatching in
+    /// a schedule of their own, with
+    /// [`EventListenerPlugin::in_schedule`](crate::EventListenerPlugin::in_schedule).
+    ///
+    /// When an entity has more than one listener, they are run in order of priority. Listeners with
+    /// the same priority are run in the order they were added, starting with the [`On`] component,
+    /// followed by the [`Listeners`].
+    ///
+    /// The path of every event is a snapshot of the hierarchy, resolved by
+    /// [`EventDispatcher::build`] before any callbacks are run. Callbacks that reparent entities
+    /// do not change which entities the events dispatched in the same frame visit, even those
+    /// that have not started propagating yet, so the listeners that are triggered only depend on
+    /// the hierarchy at the start of dispatch. Broadcast events are the exception, as they find
+    /// the [`Children`] of each entity as they visit it.
+    ///
+    /// Callbacks can despawn any entity, including the target, their own entity, or an ancestor
+    /// the event has yet to reach. Entities that were despawned are skipped, and the event
+    /// continues to propagate to the rest of its path. Use
+    /// [`EventListenerPlugin::with_despawn_warnings`](crate::EventListenerPlugin::with_despawn_warnings)
+    /// to log the entities that were skipped.
+    ///
+    /// A callback that despawns the target doesn't stop the event from bubbling up to its
+    /// ancestors:
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     callbacks::Listener,
+    /// #     event_listener::{EntityEvent, Listeners, On},
+    /// #     EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_hierarchy::BuildWorldChildren;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # #[derive(Resource, Default)]
+    /// # struct Log(Vec<&'static str>);
+    /// # let log = |name| move |mut log: ResMut<Log>| log.0.push(name);
+    /// # let mut app = App::new();
+    /// # app.add_plugins(EventListenerPlugin::<Click>::default())
+    /// #     .init_resource::<Log>();
+    /// let parent = app.world.spawn(On::<Click>::run(log("parent"))).id();
+    /// let target = app
+    ///     .world
+    ///     .spawn(On::<Click>::run(
+    ///         |click: Listener<Click>, mut commands: Commands, mut log: ResMut<Log>| {
+    ///             log.0.push("target");
+    ///             commands.entity(click.target).despawn();
+    ///         },
+    ///     ))
+    ///     .set_parent(parent)
+    ///     .id();
+    ///
+    /// app.world.send_event(Click { target });
+    /// app.update();
+    /// assert!(app.world.get_entity(target).is_none());
+    /// assert_eq!(app.world.resource::<Log>().0, ["target", "parent"]);
+    /// ```
+    ///
+    /// A callback that despawns its own entity skips the remaining listeners on that entity:
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     callbacks::Listener,
+    /// #     event_listener::{EntityEvent, Listeners, On},
+    /// #     EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_hierarchy::BuildWorldChildren;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # #[derive(Resource, Default)]
+    /// # struct Log(Vec<&'static str>);
+    /// # let log = |name| move |mut log: ResMut<Log>| log.0.push(name);
+    /// # let mut app = App::new();
+    /// # app.add_plugins(EventListenerPlugin::<Click>::default())
+    /// #     .init_resource::<Log>();
+    /// let parent = app.world.spawn(On::<Click>::run(log("parent"))).id();
+    /// let mut listeners = Listeners::<Click>::default();
+    /// listeners.push(On::run(
+    ///     |click: Listener<Click>, mut commands: Commands, mut log: ResMut<Log>| {
+    ///         log.0.push("despawn");
+    ///         commands.entity(click.listener()).despawn();
+    ///     },
+    /// ));
+    /// listeners.push(On::run(log("skipped")));
+    /// let target = app.world.spawn(listeners).set_parent(parent).id();
+    ///
+    /// app.world.send_event(Click { target });
+    /// app.update();
+    /// assert_eq!(app.world.resource::<Log>().0, ["despawn", "parent"]);
+    /// ```
+    ///
+    /// A callback that despawns an ancestor the event has not reached yet skips that ancestor, and
+    /// the event continues to the ancestors above it:
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     callbacks::Listener,
+    /// #     event_listener::{EntityEvent, Listeners, On},
+    /// #     EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_hierarchy::BuildWorldChildren;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # #[derive(Resource, Default)]
+    /// # struct Log(Vec<&'static str>);
+    /// # let log = |name| move |mut log: ResMut<Log>| log.0.push(name);
+    /// # let mut app = App::new();
+    /// # app.add_plugins(EventListenerPlugin::<Click>::default())
+    /// #     .init_resource::<Log>();
+    /// let root = app.world.spawn(On::<Click>::run(log("root"))).id();
+    /// let parent = app
+    ///     .world
+    ///     .spawn(On::<Click>::run(log("parent")))
+    ///     .set_parent(root)
+    ///     .id();
+    /// let target = app
+    ///     .world
+    ///     .spawn(On::<Click>::run(
+    ///         move |mut commands: Commands, mut log: ResMut<Log>| {
+    ///             log.0.push("target");
+    ///             commands.entity(parent).despawn();
+    ///         },
+    ///     ))
+    ///     .set_parent(parent)
+    ///     .id();
+    ///
+    /// app.world.send_event(Click { target });
+    /// app.update();
+    /// assert_eq!(app.world.resource::<Log>().0, ["target", "root"]);
+    /// ```
     pub fn bubble_events(world: &mut World) {
         world.resource_scope(|world, mut dispatcher: Mut<EventDispatcher<E>>| {
-            let dispatcher = dispatcher.as_mut();
-            dispatcher.events.drain(..).for_each(|(event_data, leaf)| {
-                let mut listener = leaf;
-                let can_bubble = event_data.can_bubble();
-
-                world.insert_resource(ListenerInput {
-                    listener,
-                    event_data,
-                    propagate: true,
-                });
-                while let Some((callback, next_node)) = dispatcher.listener_graph.get_mut(&listener)
+            let mut events = std::mem::take(&mut dispatcher.events);
+            // Wall clock time is different on every machine, so deterministic dispatchers ignore the
+            // frame budget.
+            let frame_start = dispatcher
+                .frame_budget
+                .filter(|_| dispatcher.hash_event.is_none())
+                .map(|budget| (budget, std::time::Instant::now()));
+            let mut drain = events.drain(..);
+            for (index, (event_data, target, leaf, source)) in drain.by_ref().enumerate() {
+                // At least one event is propagated each frame, so dispatch always makes progress.
+                if index > 0 && frame_start.is_some_and(|(budget, start)| start.elapsed() >= budget)
                 {
-                    world.resource_mut::<ListenerInput<E>>().listener = listener;
-                    callback.run(world);
-                    if !can_bubble || !world.resource::<ListenerInput<E>>().propagate {
-                        break;
-                    }
-                    match next_node {
-                        Some(next_node) => listener = *next_node,
-                        _ => break,
+                    dispatcher.carried.push((event_data, target, source));
+                    break;
+                }
+                let EventDispatcher {
+                    path,
+                    listener_graph,
+                    ..
+                } = dispatcher.as_mut();
+                path.clear();
+                let mut next_node = leaf;
+                while let Some(next) = next_node.and_then(|entity| {
+                    path.push(entity);
+                    listener_graph.get(&entity)
+                }) {
+                    next_node = *next;
+                }
+                let Some((event_data, triggered, _)) =
+                    dispatcher.dispatch(world, event_data, target, source)
+                else {
+                    continue;
+                };
+                forward_event(world, dispatcher.forward, event_data, target, triggered);
+            }
+            dispatcher
+                .carried
+                .extend(drain.map(|(event, target, _, source)| (event, target, source)));
+            // Give the memory back, so it can be reused by the next frame's events.
+            dispatcher.events = events;
+            dispatcher.batches.run(world);
+        });
+    }
+
+    /// Propagate a single event immediately, instead of sending it to be propagated the next time
+    /// the dispatch systems run. Returns the final state of the event data once it has finished
+    /// propagating, including any changes made by listeners, unless it was lost during
+    /// propagation. Events with multiple [`targets`](EntityEvent::targets) are propagated from
+    /// each target in turn, and the final state from the last target is returned.
+    ///
+    /// The event's path is resolved from the hierarchy as it is when this is called. The event is
+    /// otherwise handled exactly like a sent event: it runs the [`DefaultAction`], is forwarded as
+    /// a [`DispatchedEvent`] if enabled, and is counted in the [`DispatchStats`]. Batched listeners
+    /// are run before this returns.
+    ///
+    /// This can't be used while events of the same type are being dispatched, for example from one
+    /// of their callbacks, and returns `None` without propagating the event.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     callbacks::ListenerMut, event_dispatcher::EventDispatcher,
+    /// #     event_listener::{EntityEvent, On}, EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Clone, Event)]
+    /// struct Ask {
+    ///     target: Entity,
+    ///     answer: Option<u32>,
+    /// }
+    /// # impl EntityEvent for Ask {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins(EventListenerPlugin::<Ask>::default());
+    /// let target = app
+    ///     .world
+    ///     .spawn(On::<Ask>::run(|mut ask: ListenerMut<Ask>| {
+    ///         ask.answer = Some(42);
+    ///     }))
+    ///     .id();
+    ///
+    /// let ask = Ask { target, answer: None };
+    /// let ask = EventDispatcher::dispatch_now(&mut app.world, ask).unwrap();
+    /// assert_eq!(ask.answer, Some(42));
+    /// ```
+    pub fn dispatch_now(world: &mut World, event: E) -> Option<E> {
+        Self::request(world, event).map(|(event, _)| event)
+    }
+
+    /// Propagate a single event immediately, like [`EventDispatcher::dispatch_now`], and also
+    /// return the [`ListenerResponses`] recorded by the listeners it triggered. For events with
+    /// multiple [`targets`](EntityEvent::targets), only the responses from the last target are
+    /// returned.
+    pub fn request(world: &mut World, mut event: E) -> Option<(E, ListenerResponses)> {
+        let Some(mut dispatcher) = world.remove_resource::<EventDispatcher<E>>() else {
+            #[cfg(feature = "trace")]
+            error!(
+                "Can't dispatch event {:?} immediately, the EventDispatcher is missing, or events of \
+                the same type are being dispatched",
+                std::any::type_name::<E>(),
+            );
+            return None;
+        };
+        // Hooks are only run once the event is known to be dispatched.
+        if let Some(mut hooks) = world.get_resource_mut::<EventHooks<E>>() {
+            if !hooks.run_before(&mut event) {
+                world.insert_resource(dispatcher);
+                return None;
+            }
+        }
+        let mut output = None;
+        for target in event.targets() {
+            let dispatcher = &mut dispatcher;
+            if target == Entity::PLACEHOLDER || !event.propagation().is_downward() {
+                (dispatcher.resolve_path)(world, target, &mut dispatcher.path);
+            }
+            output = dispatcher.dispatch(world, event.clone(), target, None).map(
+                |(event_data, triggered, responses)| {
+                    if dispatcher.forward.is_some() {
+                        let forwarded = event_data.clone();
+                        forward_event(world, dispatcher.forward, forwarded, target, triggered);
                     }
+                    (event_data, responses)
+                },
+            );
+        }
+        dispatcher.batches.run(world);
+        world.insert_resource(dispatcher);
+        output
+    }
+
+    /// Propagate a single event from `target`, along the path of entities with listeners stored
+    /// in `self.path`, starting at the leaf. Broadcast events ignore the path. Once the event has
+    /// finished propagating, the events sent by its callbacks are dispatched if they are nested,
+    /// see [`EventDispatcher::with_nested_dispatch`].
+    ///
+    /// Returns the final state of the event data, the number of listeners it triggered, and their
+    /// responses, unless it was lost during propagation.
+    fn dispatch(
+        &mut self,
+        world: &mut World,
+        event_data: E,
+        target: Entity,
+        source: Option<EventSource>,
+    ) -> Option<(E, usize, ListenerResponses)> {
+        let Some(marks) = NestedDispatch::begin::<E>(world, target) else {
+            return self.dispatch_single(world, event_data, target, source);
+        };
+        let output = self.dispatch_single(world, event_data, target, source);
+        // Events of this type are dispatched last, so the events sent by the nested events of
+        // other types are also found.
+        let mut own = None;
+        for (nested, from) in marks {
+            if nested.type_id == TypeId::of::<E>() {
+                own = Some(from);
+            } else {
+                (nested.flush)(world, from);
+            }
+        }
+        if let Some(from) = own {
+            self.dispatch_nested(world, from);
+        }
+        world.resource_mut::<NestedDispatch>().stack.pop();
+        output
+    }
+
+    /// Dispatch the events of type `E` with an id of at least `from`, which were sent while
+    /// another event was propagating, unless they were already dispatched or deferred, see
+    /// [`EventDispatcher::with_nested_dispatch`].
+    fn dispatch_nested(&mut self, world: &mut World, from: usize) {
+        let Some(max_depth) = self.nested else {
+            return;
+        };
+        // Paused events are held the next time events are read.
+        if world
+            .get_resource::<EventDispatchControl>()
+            .is_some_and(|control| control.is_paused::<E>())
+        {
+            return;
+        }
+        for id in from..next_event_id::<E>(world) {
+            if self.nested_ids.contains(&id) || self.deferred_ids.contains(&id) {
+                continue;
+            }
+            let Some(mut event) = world
+                .resource::<Events<E>>()
+                .get_event(id)
+                .map(|(event, _)| event.clone())
+            else {
+                continue;
+            };
+            let stack = &world.resource::<NestedDispatch>().stack;
+            let cycle = event
+                .targets()
+                .iter()
+                .any(|target| stack.contains(&(TypeId::of::<E>(), *target)));
+            if cycle || stack.len() > max_depth {
+                #[cfg(feature = "trace")]
+                warn!(
+                    "Event {:?} targeting {:?} is dispatched later instead of immediately, {}",
+                    std::any::type_name::<E>(),
+                    event.target(),
+                    if cycle {
+                        "because an event of the same type is already propagating from its target"
+                    } else {
+                        "because the nested dispatch depth limit was reached"
+                    },
+                );
+                self.deferred_ids.insert(id);
+                continue;
+            }
+            self.nested_ids.insert(id);
+            let source = world
+                .get_resource_mut::<EventSources<E>>()
+                .and_then(|mut sources| sources.take(id));
+            if let Some(mut hooks) = world.get_resource_mut::<EventHooks<E>>() {
+                if !hooks.run_before(&mut event) {
+                    continue;
+                }
+            }
+            for target in event.targets() {
+                if target == Entity::PLACEHOLDER || !event.propagation().is_downward() {
+                    (self.resolve_path)(world, target, &mut self.path);
+                }
+                if let Some((event_data, triggered, _)) =
+                    self.dispatch(world, event.clone(), target, source.clone())
+                {
+                    forward_event(world, self.forward, event_data, target, triggered);
                 }
-                world.remove_resource::<ListenerInput<E>>();
+            }
+        }
+    }
+
+    /// Propagate a single event, see [`EventDispatcher::dispatch`].
+    fn dispatch_single(
+        &mut self,
+        world: &mut World,
+        event_data: E,
+        target: Entity,
+        source: Option<EventSource>,
+    ) -> Option<(E, usize, ListenerResponses)> {
+        #[cfg(feature = "trace")]
+        let _span = debug_span!(
+            "propagate",
+            event = std::any::type_name::<E>(),
+            target = ?target,
+        )
+        .entered();
+        let history = world.contains_resource::<EventHistory<E>>();
+        #[cfg(feature = "trace")]
+        let record_triggered = history || self.debug_logging;
+        #[cfg(not(feature = "trace"))]
+        let record_triggered = history;
+        let start = self.measure_time.then(std::time::Instant::now);
+        let propagation = event_data.propagation();
+        let mut input = ListenerInput::new(event_data, target);
+        input.source = source;
+        #[cfg(feature = "trace")]
+        {
+            input.warn_despawned = self.warn_despawned;
+        }
+        if record_triggered {
+            self.triggered_log.clear();
+            input.triggered_log = Some(std::mem::take(&mut self.triggered_log));
+        }
+        input.path = std::mem::take(&mut self.path);
+        let downward = match propagation {
+            _ if target == Entity::PLACEHOLDER => None,
+            Propagation::Broadcast { max_depth } => Some((max_depth, false)),
+            Propagation::FindHandler { max_depth } => Some((max_depth, true)),
+            Propagation::Bubble => None,
+        };
+        let input = if let Some((max_depth, find_handler)) = downward {
+            broadcast(
+                world,
+                input,
+                max_depth,
+                find_handler,
+                &mut self.stack,
+                &mut self.slots,
+                &mut self.batches,
+            )
+        } else {
+            propagate(
+                world,
+                input,
+                &mut self.slots,
+                &mut self.batches,
+                self.resolve_path,
+            )
+        };
+        let input = match input {
+            Some(input) if !input.default_prevented => run_default_action(world, input),
+            input => input,
+        };
+        if let Some(input) = &input {
+            run_after_hooks(world, input);
+        }
+        if let Some(start) = start {
+            self.stats.dispatch_time += start.elapsed();
+        }
+        self.stats.events += 1;
+        self.total_events += 1;
+        if let Some(hash_event) = self.hash_event {
+            let checksum = match &input {
+                Some(input) => event_checksum(
+                    hash_event,
+                    Some(&input.event_data),
+                    target,
+                    &input.path,
+                    input.triggered,
+                ),
+                None => event_checksum(hash_event, None, target, &[], 0),
+            };
+            self.checksum.write_u64(checksum);
+            self.frame_checksum.write_u64(checksum);
+        }
+        let mut input = input?;
+        self.stats.listeners_triggered += input.triggered;
+        self.total_listeners_triggered += input.triggered;
+        self.stats.total_depth += input.path.len();
+        if let Some(log) = input.triggered_log.take() {
+            #[cfg(feature = "trace")]
+            if self.debug_logging {
+                log_propagation(world, &input, &log);
+            }
+            if let Some(mut history) = world.get_resource_mut::<EventHistory<E>>() {
+                history.push(HistoryEntry {
+                    event: input.event_data.clone(),
+                    target: input.target,
+                    path: input.path.clone(),
+                    propagation_stopped: log.iter().any(|l| l.stopped_propagation),
+                    default_prevented: input.default_prevented,
+                    listeners: log.clone(),
+                });
+            }
+            self.triggered_log = log;
+        }
+        // Reclaim the path so its memory can be reused by the next event.
+        self.path = std::mem::take(&mut input.path);
+        Some((input.event_data, input.triggered, input.responses))
+    }
+}
+
+/// Apply the queue `limit` to the `carried` events, and the `sent` events read this frame, which
+/// are over the limit. Returns how many of the sent events to skip, and how many to take after that.
+fn limit_queue<E: EntityEvent>(
+    limit: usize,
+    overflow: QueueOverflow,
+    carried: &mut Vec<(E, Entity, Option<EventSource>)>,
+    sent: usize,
+) -> (usize, usize) {
+    let excess = carried.len() + sent - limit;
+    match overflow {
+        QueueOverflow::PanicInDebug if cfg!(debug_assertions) => panic!(
+            "{} events of type {:?} are waiting to be dispatched, over the limit of {}",
+            carried.len() + sent,
+            std::any::type_name::<E>(),
+            limit,
+        ),
+        #[cfg(feature = "trace")]
+        QueueOverflow::Warn => warn!(
+            "{} events of type {:?} are waiting to be dispatched, discarding the newest {}",
+            carried.len() + sent,
+            std::any::type_name::<E>(),
+            excess,
+        ),
+        _ => {}
+    }
+    if overflow == QueueOverflow::DropOldest {
+        let from_carried = excess.min(carried.len());
+        carried.drain(..from_carried);
+        (excess - from_carried, usize::MAX)
+    } else {
+        carried.truncate(limit);
+        (0, limit - carried.len())
+    }
+}
+
+/// Run the [`EventHooks`] for an event that has finished propagating, if there are any.
+fn run_after_hooks<E: EntityEvent>(world: &mut World, input: &ListenerInput<E>) {
+    if !world
+        .get_resource::<EventHooks<E>>()
+        .is_some_and(|hooks| hooks.has_after())
+    {
+        return;
+    }
+    world.resource_scope(|world, mut hooks: Mut<EventHooks<E>>| hooks.run_after(world, input));
+}
+
+/// A [`Command`](bevy_ecs::system::Command) that propagates an event as soon as it is applied,
+/// with [`EventDispatcher::dispatch_now`]. The final state of the event is discarded.
+///
+/// If events of the same type are being dispatched when the command is applied, for example
+/// because it was added by one of their callbacks, the event is sent instead, and propagates the
+/// next time the dispatch systems run.
+pub struct DispatchNow<E: EntityEvent> {
+    event: E,
+}
+
+impl<E: EntityEvent> DispatchNow<E> {
+    /// Create a command that will propagate the `event`.
+    pub fn new(event: E) -> Self {
+        Self { event }
+    }
+}
+
+impl<E: EntityEvent> bevy_ecs::system::Command for DispatchNow<E> {
+    fn apply(self, world: &mut World) {
+        if world.contains_resource::<EventDispatcher<E>>() {
+            EventDispatcher::dispatch_now(world, self.event);
+        } else {
+            world.send_event(self.event);
+        }
+    }
+}
+
+/// Pauses and resumes the dispatch of each event type.
+///
+/// While events of type `E` are paused, the events that are sent are held by the
+/// [`EventDispatcher<E>`] instead of propagating, and the events carried over from the last frame
+/// by a [`frame budget`](EventDispatcher::with_frame_budget) wait where they are. Once the events
+/// are resumed, the held events are dispatched the next time the dispatch systems run, in the
+/// order they were sent, before any event sent since. This is useful to freeze interaction during a
+/// cutscene, without losing the inputs the player buffered during it.
+///
+/// Other systems reading events of type `E` still see the events as they are sent, and the
+/// [`DelayedEvents<E>`](crate::delayed::DelayedEvents) keep counting down.
+///
+/// ```
+/// # use bevy_eventlistener_core::{
+/// #     event_dispatcher::EventDispatchControl,
+/// #     event_listener::{EntityEvent, On},
+/// #     EventListenerPlugin,
+/// # };
+/// # use bevy_app::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// # #[derive(Clone, Event)]
+/// # struct Jump {
+/// #     target: Entity,
+/// # }
+/// # impl EntityEvent for Jump {
+/// #     fn target(&self) -> Entity {
+/// #         self.target
+/// #     }
+/// # }
+/// # #[derive(Resource, Default)]
+/// # struct Jumps(usize);
+/// let mut app = App::new();
+/// app.add_plugins(EventListenerPlugin::<Jump>::default())
+///     .init_resource::<Jumps>();
+/// let player = app
+///     .world
+///     .spawn(On::<Jump>::run(|mut jumps: ResMut<Jumps>| jumps.0 += 1))
+///     .id();
+///
+/// // The cutscene starts.
+/// app.world.resource_mut::<EventDispatchControl>().pause::<Jump>();
+/// app.world.send_event(Jump { target: player });
+/// app.update();
+/// app.update();
+/// assert_eq!(app.world.resource::<Jumps>().0, 0);
+///
+/// // The cutscene ends, and the buffered jump happens.
+/// app.world.resource_mut::<EventDispatchControl>().resume::<Jump>();
+/// app.update();
+/// assert_eq!(app.world.resource::<Jumps>().0, 1);
+/// ```
+#[derive(Resource, Default, Debug)]
+pub struct EventDispatchControl {
+    paused: HashSet<TypeId>,
+}
+
+impl EventDispatchControl {
+    /// Hold the events of type `E` instead of dispatching them, until they are resumed.
+    pub fn pause<E: EntityEvent>(&mut self) {
+        self.paused.insert(TypeId::of::<E>());
+    }
+
+    /// Dispatch the events of type `E` again, starting with the events that were held while they
+    /// were paused.
+    pub fn resume<E: EntityEvent>(&mut self) {
+        self.paused.remove(&TypeId::of::<E>());
+    }
+
+    /// Returns `true` if the events of type `E` are paused.
+    pub fn is_paused<E: EntityEvent>(&self) -> bool {
+        self.paused.contains(&TypeId::of::<E>())
+    }
+}
+
+/// The event types that are dispatched as soon as they are sent by a callback, and the events that
+/// are propagating, see [`EventDispatcher::with_nested_dispatch`]. This is only added once an
+/// event type uses nested dispatch.
+#[derive(Resource, Default)]
+pub(crate) struct NestedDispatch {
+    types: Vec<NestedType>,
+    /// The type and target of each event that is propagating, starting with the outermost.
+    stack: Vec<(TypeId, Entity)>,
+}
+
+/// An event type that uses nested dispatch, with its type erased.
+#[derive(Clone, Copy)]
+struct NestedType {
+    type_id: TypeId,
+    /// The id the next event of this type will be sent with.
+    next_id: fn(&World) -> usize,
+    /// Dispatch the events of this type sent since the id, see
+    /// [`EventDispatcher::dispatch_nested`].
+    flush: fn(&mut World, usize),
+}
+
+impl NestedDispatch {
+    /// Dispatch the events of type `E` that are sent by callbacks immediately.
+    pub(crate) fn register<E: EntityEvent>(world: &mut World) {
+        let mut nested = world.get_resource_or_insert_with(NestedDispatch::default);
+        if nested
+            .types
+            .iter()
+            .all(|ty| ty.type_id != TypeId::of::<E>())
+        {
+            nested.types.push(NestedType {
+                type_id: TypeId::of::<E>(),
+                next_id: next_event_id::<E>,
+                flush: flush_nested::<E>,
+            });
+        }
+    }
+
+    /// Record that an event of type `E` is propagating from `target`, and return the id the next
+    /// event of each nested type will be sent with, so the events sent by its callbacks can be
+    /// found once it has finished propagating. Returns `None` if no event type uses nested
+    /// dispatch.
+    fn begin<E: EntityEvent>(
+        world: &mut World,
+        target: Entity,
+    ) -> Option<SmallVec<[(NestedType, usize); 4]>> {
+        let nested = world.get_resource::<NestedDispatch>()?;
+        let marks = nested
+            .types
+            .iter()
+            .map(|ty| (*ty, (ty.next_id)(world)))
+            .collect();
+        world
+            .resource_mut::<NestedDispatch>()
+            .stack
+            .push((TypeId::of::<E>(), target));
+        Some(marks)
+    }
+}
+
+/// The id the next event of type `E` will be sent with.
+fn next_event_id<E: Event>(world: &World) -> usize {
+    world
+        .get_resource::<Events<E>>()
+        .map_or(0, |events| events.oldest_id() + events.len())
+}
+
+/// Dispatch the events of type `E` sent since the id `from`, see
+/// [`EventDispatcher::dispatch_nested`]. If events of type `E` are already being dispatched further
+/// up the stack, the events are left for that dispatcher, which looks for them once its own event
+/// has finished propagating.
+fn flush_nested<E: EntityEvent>(world: &mut World, from: usize) {
+    let Some(mut dispatcher) = world.remove_resource::<EventDispatcher<E>>() else {
+        return;
+    };
+    dispatcher.dispatch_nested(world, from);
+    dispatcher.batches.run(world);
+    world.insert_resource(dispatcher);
+}
+
+/// Send an event that has finished propagating as a [`DispatchedEvent`], if it is selected by
+/// `forward`.
+fn forward_event<E: EntityEvent>(
+    world: &mut World,
+    forward: Option<ForwardEvents>,
+    event: E,
+    target: Entity,
+    triggered: usize,
+) {
+    let forward = match forward {
+        Some(ForwardEvents::All) => true,
+        Some(ForwardEvents::Unhandled) => triggered == 0,
+        None => false,
+    };
+    if forward {
+        world.send_event(DispatchedEvent {
+            target,
+            was_handled: triggered > 0,
+            event,
+        });
+    }
+}
+
+/// Resolve the path of an event dispatched with [`EventDispatcher::dispatch_now`] into `path`,
+/// walking the hierarchy through the [`Traversal`] component `T` like [`EventDispatcher::build`].
+fn resolve_path<E: EntityEvent, T: Traversal>(
+    world: &World,
+    target: Entity,
+    path: &mut Vec<Entity>,
+) {
+    path.clear();
+    let mut next = Some(target);
+    while let Some(entity) = next {
+        let Some(entity_ref) = world.get_entity(entity) else {
+            break;
+        };
+        let has_listener = entity_ref.contains::<On<E>>()
+            || entity_ref
+                .get::<Listeners<E>>()
+                .is_some_and(|list| !list.is_empty());
+        if has_listener {
+            path.push(entity);
+        }
+        if entity_ref.contains::<PropagationBarrier>() {
+            break;
+        }
+        next = entity_ref.get::<T>().and_then(Traversal::next);
+    }
+}
+
+/// The parts of propagation that depend on the event type, so that the propagation loops in
+/// [`propagate_erased`] and [`broadcast_erased`] are only compiled once, instead of once for every
+/// event type. The loops make up most of the dispatch code, while the listeners they run are
+/// inherently typed.
+trait ErasedPropagation {
+    /// The name of the event type, for error messages.
+    #[cfg(feature = "trace")]
+    fn event_name(&self) -> &'static str;
+    /// The state of the event being propagated. Only called while the input has not been lost.
+    fn state(&mut self) -> PropagationState<'_>;
+    /// Run the listeners on a single node of the path, see [`run_listeners`]. Returns `false` if
+    /// the input was lost, and propagation cannot continue.
+    fn run_node(
+        &mut self,
+        world: &mut World,
+        node: Option<Entity>,
+        capture: Option<bool>,
+        phase: EventPhase,
+    ) -> bool;
+}
+
+/// Mutable access to the fields of a [`ListenerInput`] that don't depend on the event type.
+struct PropagationState<'a> {
+    target: &'a mut Entity,
+    path: &'a mut Vec<Entity>,
+    path_len: &'a mut usize,
+    propagate: &'a mut bool,
+    skip_remaining: bool,
+    /// The number of listeners triggered so far.
+    triggered: usize,
+    retarget: &'a mut Option<Entity>,
+}
+
+impl PropagationState<'_> {
+    /// Was propagation stopped by the listeners on the current node?
+    fn stopped(&self) -> bool {
+        self.skip_remaining || !*self.propagate
+    }
+}
+
+/// An event of type `E` being propagated, along with the buffers reused between events.
+struct TypedPropagation<'a, E: EntityEvent> {
+    input: Option<ListenerInput<E>>,
+    slots: &'a mut Vec<(i32, ListenerSlot)>,
+    batches: &'a mut ListenerBatches<E>,
+}
+
+impl<E: EntityEvent> ErasedPropagation for TypedPropagation<'_, E> {
+    #[cfg(feature = "trace")]
+    fn event_name(&self) -> &'static str {
+        std::any::type_name::<E>()
+    }
+
+    fn state(&mut self) -> PropagationState<'_> {
+        let input = self
+            .input
+            .as_mut()
+            .expect("propagation stops as soon as the input is lost");
+        PropagationState {
+            target: &mut input.target,
+            path: &mut input.path,
+            path_len: &mut input.path_len,
+            propagate: &mut input.propagate,
+            skip_remaining: input.skip_remaining,
+            triggered: input.triggered,
+            retarget: &mut input.retarget,
+        }
+    }
+
+    fn run_node(
+        &mut self,
+        world: &mut World,
+        node: Option<Entity>,
+        capture: Option<bool>,
+        phase: EventPhase,
+    ) -> bool {
+        let Some(input) = self.input.take() else {
+            return false;
+        };
+        self.input = run_listeners(world, node, capture, phase, input, self.slots, self.batches);
+        self.input.is_some()
+    }
+}
+
+/// Resolve the path of an event dispatched with [`EventDispatcher::target_only`], which only
+/// contains the `target`, if it has a listener.
+fn resolve_target_only<E: EntityEvent>(world: &World, target: Entity, path: &mut Vec<Entity>) {
+    path.clear();
+    let Some(entity_ref) = world.get_entity(target) else {
+        return;
+    };
+    let has_listener = entity_ref.contains::<On<E>>()
+        || entity_ref
+            .get::<Listeners<E>>()
+            .is_some_and(|list| !list.is_empty());
+    if has_listener {
+        path.push(target);
+    }
+}
+
+/// Propagate a single event along the path of entities with listeners stored in the `input`,
+/// starting at the leaf. Returns the final state of the input, unless it was lost during
+/// propagation.
+///
+/// If a listener retargets the event, propagation starts again from the new target, whose path is
+/// resolved with `resolve_path`.
+fn propagate<E: EntityEvent>(
+    world: &mut World,
+    input: ListenerInput<E>,
+    slots: &mut Vec<(i32, ListenerSlot)>,
+    batches: &mut ListenerBatches<E>,
+    resolve_path: fn(&World, Entity, &mut Vec<Entity>),
+) -> Option<ListenerInput<E>> {
+    let can_bubble = input.event_data.can_bubble();
+    let mut propagation = TypedPropagation {
+        input: Some(input),
+        slots,
+        batches,
+    };
+    propagate_erased(world, &mut propagation, can_bubble, resolve_path);
+    propagation.input
+}
+
+/// The propagation loop of [`propagate`], shared by every event type.
+fn propagate_erased(
+    world: &mut World,
+    propagation: &mut dyn ErasedPropagation,
+    can_bubble: bool,
+    resolve_path: fn(&World, Entity, &mut Vec<Entity>),
+) {
+    let mut previous_targets = Vec::new();
+
+    loop {
+        let state = propagation.state();
+        let target = *state.target;
+        // Indices past the end of the path represent the global listeners, which surround it.
+        // Global capture listeners have already run if the event was retargeted.
+        let len = state.path.len();
+        let capture = std::iter::once(len)
+            .filter(|_| previous_targets.is_empty())
+            .chain((0..len).rev())
+            .map(|index| (index, true));
+        let bubble = (0..len)
+            .chain(std::iter::once(len))
+            .map(|index| (index, false));
+
+        let mut retarget = None;
+        for (index, capture_phase) in capture.chain(bubble) {
+            let state = propagation.state();
+            let node = state.path.get(index).copied();
+            *state.path_len = len.min(index + 1);
+            // Global listeners are at the target of events without a target.
+            let at_target = node.unwrap_or(Entity::PLACEHOLDER) == target;
+            let phase = match (at_target, capture_phase) {
+                (true, _) => EventPhase::AtTarget,
+                (false, true) => EventPhase::Capture,
+                // Events that can't bubble skip the target's ancestors, but not the global
+                // listeners.
+                (false, false) if can_bubble || node.is_none() => EventPhase::Bubble,
+                (false, false) => continue,
+            };
+            if !propagation.run_node(world, node, Some(capture_phase), phase) {
+                return;
+            }
+            let state = propagation.state();
+            // Propagation is only stopped once all listeners on this node have run.
+            if state.stopped() {
+                *state.retarget = None;
+                break;
+            }
+            if let Some(new_target) = state.retarget.take() {
+                retarget = Some(new_target);
+                break;
+            }
+        }
+
+        let Some(new_target) = retarget else {
+            return;
+        };
+        previous_targets.push(target);
+        if previous_targets.contains(&new_target) {
+            #[cfg(feature = "trace")]
+            error!(
+                "Event {:?} can't be retargeted to {:?}, it has already propagated from it",
+                propagation.event_name(),
+                new_target,
+            );
+            return;
+        }
+        let state = propagation.state();
+        *state.target = new_target;
+        resolve_path(world, new_target, state.path);
+    }
+}
+
+/// Broadcast a single event down the hierarchy, from the target to all of its descendants.
+///
+/// Descendants are visited depth first, in the order of their [`Children`]. Stopping propagation
+/// prevents the event from reaching the descendants of the current entity, but the event will
+/// continue to be broadcast to the rest of the hierarchy. Returns the final state of the input,
+/// unless it was lost during propagation. The path of the input is used to store the path from the
+/// target to the current entity.
+///
+/// If `find_handler` is set, the event stops once the listeners on the first entity that has a
+/// listener triggered by it have run, see [`Propagation::FindHandler`].
+fn broadcast<E: EntityEvent>(
+    world: &mut World,
+    input: ListenerInput<E>,
+    max_depth: Option<usize>,
+    find_handler: bool,
+    stack: &mut Vec<(Entity, usize)>,
+    slots: &mut Vec<(i32, ListenerSlot)>,
+    batches: &mut ListenerBatches<E>,
+) -> Option<ListenerInput<E>> {
+    let mut propagation = TypedPropagation {
+        input: Some(input),
+        slots,
+        batches,
+    };
+    broadcast_erased(world, &mut propagation, max_depth, find_handler, stack);
+    propagation.input
+}
+
+/// The broadcast loop of [`broadcast`], shared by every event type.
+fn broadcast_erased(
+    world: &mut World,
+    propagation: &mut dyn ErasedPropagation,
+    max_depth: Option<usize>,
+    find_handler: bool,
+    stack: &mut Vec<(Entity, usize)>,
+) {
+    let state = propagation.state();
+    let target = *state.target;
+    state.path.clear();
+    state.path.push(target);
+    *state.path_len = 1;
+
+    if !propagation.run_node(world, None, Some(true), EventPhase::Capture) {
+        return;
+    }
+    if propagation.state().stopped() {
+        return;
+    }
+
+    stack.clear();
+    stack.push((target, 0));
+    while let Some((entity, depth)) = stack.pop() {
+        let phase = match depth {
+            0 => EventPhase::AtTarget,
+            _ => EventPhase::Broadcast,
+        };
+        let state = propagation.state();
+        *state.propagate = true;
+        state.path.truncate(depth);
+        state.path.push(entity);
+        *state.path_len = depth + 1;
+        let triggered = state.triggered;
+        if !propagation.run_node(world, Some(entity), None, phase) {
+            return;
+        }
+        let state = propagation.state();
+        if state.skip_remaining || (find_handler && state.triggered > triggered) {
+            return;
+        }
+        if !*state.propagate || max_depth.is_some_and(|max_depth| depth >= max_depth) {
+            continue;
+        }
+        if let Some(children) = world.get::<Children>(entity) {
+            // Children are pushed in reverse, so they are popped off the stack in order.
+            stack.extend(children.iter().rev().map(|child| (*child, depth + 1)));
+        }
+    }
+
+    let state = propagation.state();
+    *state.propagate = true;
+    state.path.truncate(1);
+    *state.path_len = 1;
+    propagation.run_node(world, None, Some(false), EventPhase::Bubble);
+}
+
+/// Run the [`DefaultAction`] for an event that has finished propagating, if there is one. Returns
+/// the input once the default action is done with it.
+fn run_default_action<E: EntityEvent>(
+    world: &mut World,
+    mut input: ListenerInput<E>,
+) -> Option<ListenerInput<E>> {
+    let Some(mut default_action) = world.get_resource_mut::<DefaultAction<E>>() else {
+        return Some(input);
+    };
+    let mut callback = std::mem::take(&mut default_action.callback);
+    input.listener = input.target();
+    input.phase = EventPhase::AtTarget;
+    input.data = ListenerData::default();
+    let output = callback.run(world, input);
+    // The default action has no listener to disable if it panicked.
+    PanicIsolation::<E>::take_disable(world);
+    if let Some(mut default_action) = world.get_resource_mut::<DefaultAction<E>>() {
+        if matches!(default_action.callback, CallbackSystem::Empty) {
+            default_action.callback = callback;
+        }
+    }
+    output
+}
+
+/// Return a callback to the listener in `slot` once it has run, or remove the listener if it
+/// should only be triggered `once`. The callback is dropped if the listener was replaced while it
+/// ran.
+fn return_callback<E: EntityEvent>(
+    world: &mut World,
+    slot: ListenerSlot,
+    callback: CallbackSystem<E>,
+    once: bool,
+) {
+    let disable = PanicIsolation::<E>::take_disable(world);
+    if let Some(mut on) = slot.get_mut::<E>(world) {
+        if matches!(on.callback, CallbackSystem::Empty) {
+            if once {
+                slot.remove::<E>(world);
+            } else {
+                on.callback = callback;
+                // The callback panicked, see `PanicIsolation`.
+                on.disabled |= disable;
+            }
+        }
+    }
+}
+
+/// Run the listeners on a single node in the propagation path, in order of priority. A node of
+/// `None` represents the global listeners. If `capture` is set, only listeners that match it are
+/// run.
+///
+/// Returns the input once all listeners have run, or `None` if it was lost and propagation cannot
+/// continue. If a listener requested that the remaining listeners be skipped, the input is returned
+/// early.
+fn run_listeners<E: EntityEvent>(
+    world: &mut World,
+    node: Option<Entity>,
+    capture: Option<bool>,
+    phase: EventPhase,
+    mut input: ListenerInput<E>,
+    slots: &mut Vec<(i32, ListenerSlot)>,
+    batches: &mut ListenerBatches<E>,
+) -> Option<ListenerInput<E>> {
+    #[cfg(feature = "trace")]
+    let warn = input
+        .warn_despawned
+        .then(|| (std::any::type_name::<E>(), input.target));
+    #[cfg(not(feature = "trace"))]
+    let warn = None;
+    if node_despawned(world, node, warn) {
+        return Some(input);
+    }
+    slots.clear();
+    ListenerSlot::collect::<E>(world, node, slots);
+    // This is a stable sort, so listeners with equal priority keep their order.
+    slots.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+    let frame = world.get_resource::<FrameCount>().map(|frame| frame.0);
+
+    for (_, slot) in slots.iter().copied() {
+        if !should_trigger(world, slot, capture, phase, &input) {
+            continue;
+        }
+        let Some(mut on) = slot.get_mut::<E>(world) else {
+            continue;
+        };
+        input.listener = node.unwrap_or(Entity::PLACEHOLDER);
+        input.phase = phase;
+        input.data = on.data.clone();
+        input.triggered += 1;
+        on.times_fired += 1;
+        on.last_fired_frame = frame.or(on.last_fired_frame);
+        if on.callback.is_batched() {
+            // Batched listeners are run once every event has finished propagating.
+            let event = BatchedEvent {
+                event: input.event_data.clone(),
+                target: input.target,
+                phase,
+                source: input.source.clone(),
+            };
+            batches.push(slot, event);
+            if let Some(log) = input.triggered_log.as_mut() {
+                log.push(TriggeredListener {
+                    listener: input.listener,
+                    phase,
+                    stopped_propagation: false,
+                });
+            }
+            continue;
+        }
+        let once = on.once;
+        let mut callback = on.take();
+        let propagating = input.propagate && !input.skip_remaining;
+        #[cfg(feature = "trace")]
+        let span = debug_span!(
+            "callback",
+            system = %callback.name(),
+            listener = ?input.listener,
+            target = ?input.target,
+            depth = input.depth(),
+            phase = ?phase,
+        )
+        .entered();
+        let output = callback.run(world, input);
+        #[cfg(feature = "trace")]
+        drop(span);
+        return_callback(world, slot, callback, once);
+        let Some(output) = output else {
+            // A callback removed the `ListenerInput` resource, we can't continue.
+            #[cfg(feature = "trace")]
+            error!(
+                "The ListenerInput for event {:?} was removed by a callback in {:?}, stopping \
+                propagation",
+                std::any::type_name::<E>(),
+                slot,
+            );
+            return None;
+        };
+        input = output;
+        if let Some(log) = input.triggered_log.as_mut() {
+            log.push(TriggeredListener {
+                listener: input.listener,
+                phase,
+                stopped_propagation: propagating && (input.skip_remaining || !input.propagate),
             });
+        }
+        if input.skip_remaining {
+            break;
+        }
+    }
+    Some(input)
+}
+
+/// Returns `true` if the `node` was despawned by a callback after the path was built, so it
+/// should be skipped. If `warn` is set to the name of the event type and its target, a warning is
+/// logged.
+fn node_despawned(
+    world: &World,
+    node: Option<Entity>,
+    #[cfg_attr(not(feature = "trace"), allow(unused_variables))] warn: Option<(&str, Entity)>,
+) -> bool {
+    let Some(entity) = node else {
+        return false;
+    };
+    if world.entities().contains(entity) {
+        return false;
+    }
+    #[cfg(feature = "trace")]
+    if let Some((event_name, target)) = warn {
+        warn!(
+            "Event {:?} targeting {:?} skipped entity {:?}, which was despawned while the event \
+            was propagating",
+            event_name, target, entity,
+        );
+    }
+    true
+}
+
+/// Log the final state of an event that has finished propagating, along with the listeners it
+/// triggered. Entities are described with their [`Name`], if they have one.
+#[cfg(feature = "trace")]
+fn log_propagation<E: EntityEvent>(
+    world: &World,
+    input: &ListenerInput<E>,
+    log: &[TriggeredListener],
+) {
+    use std::fmt::Write;
+
+    let describe = |entity: Entity| match world.get::<Name>(entity) {
+        _ if entity == Entity::PLACEHOLDER => "global".to_string(),
+        Some(name) => format!("{entity:?} ({name})"),
+        None => format!("{entity:?}"),
+    };
+    let path: Vec<_> = input.path.iter().map(|entity| describe(*entity)).collect();
+    let mut message = format!(
+        "{} targeting {}, with listeners on [{}]",
+        std::any::type_name::<E>(),
+        describe(input.target),
+        path.join(", "),
+    );
+    if log.is_empty() {
+        message.push_str("\n  no listeners were triggered");
+    }
+    for triggered in log {
+        let _ = write!(
+            message,
+            "\n  triggered {} in {:?}",
+            describe(triggered.listener),
+            triggered.phase,
+        );
+        if triggered.stopped_propagation {
+            message.push_str(", which stopped propagation");
+        }
+    }
+    if input.default_prevented {
+        message.push_str("\n  the default action was prevented");
+    }
+    info!("{message}");
+}
+
+/// Returns `true` if the listener in `slot` exists and should be triggered by the `input`. The
+/// cheapest checks are done first, so the listener's run conditions are only evaluated if its
+/// filters pass and its group is enabled. The rate limit is checked last, so only events that
+/// pass every other check count towards it. An event suppressed by a deferred cooldown is stored
+/// on the listener, to trigger it once the cooldown is over.
+fn should_trigger<E: EntityEvent>(
+    world: &mut World,
+    slot: ListenerSlot,
+    capture: Option<bool>,
+    phase: EventPhase,
+    input: &ListenerInput<E>,
+) -> bool {
+    let Some(mut on) = slot.get_mut::<E>(world) else {
+        return false;
+    };
+    if on.disabled || on.removing || capture.is_some_and(|capture| capture != on.capture) {
+        return false;
+    }
+    if !on.target_mode.allows(slot.entity() == Some(input.target)) {
+        return false;
+    }
+    if !on.filters.iter().all(|filter| filter(input)) {
+        return false;
+    }
+    if let Some(mut delegate) = on.delegate.take() {
+        let matches = delegate.matches(world, input.target);
+        let Some(mut next) = slot.get_mut::<E>(world) else {
+            return false;
+        };
+        next.delegate = Some(delegate);
+        if !matches {
+            return false;
+        }
+    }
+    if !group_and_conditions_met::<E>(world, slot) {
+        return false;
+    }
+    let Some(now) = world.get_resource::<Time>().map(Time::elapsed) else {
+        return true;
+    };
+    let Some(mut on) = slot.get_mut::<E>(world) else {
+        return false;
+    };
+    let Some(limit) = on.rate_limit.as_mut() else {
+        return true;
+    };
+    if limit.allow(now) {
+        return true;
+    }
+    if limit.defers() {
+        let event = BatchedEvent {
+            event: input.event_data.clone(),
+            target: input.target,
+            phase,
+            source: input.source.clone(),
+        };
+        if on.deferred.replace(event).is_none() {
+            world
+                .get_resource_or_insert_with(CooldownQueue::<E>::default)
+                .slots
+                .push(slot);
+        }
+    }
+    false
+}
+
+/// Returns `true` if the group of the listener in `slot` is enabled, and its run conditions are met.
+fn group_and_conditions_met<E: EntityEvent>(world: &mut World, slot: ListenerSlot) -> bool {
+    let Some(mut on) = slot.get_mut::<E>(world) else {
+        return false;
+    };
+    if let Some(group) = on.group.clone() {
+        let groups = world.get_resource::<ListenerGroups>();
+        if groups.is_some_and(|groups| !groups.is_enabled(&group)) {
+            return false;
+        }
+        let Some(next) = slot.get_mut::<E>(world) else {
+            return false;
+        };
+        on = next;
+    }
+    if on.conditions.is_empty() {
+        return true;
+    }
+    let mut conditions = std::mem::take(&mut on.conditions);
+    let met = conditions.iter_mut().all(|condition| condition.run(world));
+    let Some(mut on) = slot.get_mut::<E>(world) else {
+        return false;
+    };
+    on.conditions = conditions;
+    met
+}
+
+/// The listeners with an event suppressed by a deferred cooldown, see
+/// [`On::with_deferred_cooldown`](crate::event_listener::On::with_deferred_cooldown).
+#[derive(Resource)]
+pub(crate) struct CooldownQueue<E: EntityEvent> {
+    slots: Vec<ListenerSlot>,
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E: EntityEvent> Default for CooldownQueue<E> {
+    fn default() -> Self {
+        Self {
+            slots: Vec::new(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent> CooldownQueue<E> {
+    /// A run condition that returns `true` if a listener is waiting for its cooldown to be over.
+    pub(crate) fn has_pending(queue: Option<Res<CooldownQueue<E>>>) -> bool {
+        queue.is_some_and(|queue| !queue.slots.is_empty())
+    }
+
+    /// Trigger the listeners whose cooldown is over with the last event they suppressed. This is
+    /// added by the [`EventListenerPlugin`](crate::EventListenerPlugin), and runs every frame
+    /// before events are dispatched.
+    ///
+    /// A deferred event is dropped if its listener was disabled, its group was disabled, or its
+    /// run conditions are not met once the cooldown is over. While events of type `E` are paused,
+    /// deferred events are held like any other event.
+    ///
+    /// A listener triggered by a deferred event is recorded like a listener triggered during
+    /// propagation, in the dispatch statistics, the checksums and the [`EventHistory`]. The event is not dispatched again, so it is not
+    /// counted in [`DispatchStats::events`], and the [`EventHooks`] don't see it.
+    pub(crate) fn run(world: &mut World) {
+        let Some(now) = world.get_resource::<Time>().map(Time::elapsed) else {
+            return;
+        };
+        if world
+            .get_resource::<EventDispatchControl>()
+            .is_some_and(|control| control.is_paused::<E>())
+        {
+            return;
+        }
+        let mut slots = std::mem::take(&mut world.resource_mut::<CooldownQueue<E>>().slots);
+        let frame = world.get_resource::<FrameCount>().map(|frame| frame.0);
+        slots.retain(|slot| {
+            let Some(mut on) = slot.get_mut::<E>(world) else {
+                return false;
+            };
+            if on.deferred.is_none() {
+                return false;
+            }
+            let ready = match on.rate_limit.as_mut() {
+                Some(limit) => limit.allow_deferred(now),
+                None => false,
+            };
+            if !ready {
+                // The event is dropped if the listener stopped deferring events.
+                if !on.rate_limit.is_some_and(|limit| limit.defers()) {
+                    on.deferred = None;
+                    return false;
+                }
+                return true;
+            }
+            let Some(deferred) = on.deferred.take() else {
+                return false;
+            };
+            if on.disabled || on.removing || !group_and_conditions_met::<E>(world, *slot) {
+                return false;
+            }
+            let Some(mut on) = slot.get_mut::<E>(world) else {
+                return false;
+            };
+            let listener = slot.entity().unwrap_or(Entity::PLACEHOLDER);
+            on.times_fired += 1;
+            on.last_fired_frame = frame.or(on.last_fired_frame);
+            let once = on.once;
+            let data = on.data.clone();
+            let mut callback = on.take();
+            let (target, phase) = (deferred.target, deferred.phase);
+            let event = if callback.is_batched() {
+                let event = deferred.event.clone();
+                let inputs = ListenerInputs {
+                    listener,
+                    events: vec![deferred],
+                    data,
+                };
+                callback.run_batch(world, inputs);
+                Some(event)
+            } else {
+                let mut input = ListenerInput::new(deferred.event, deferred.target);
+                input.source = deferred.source;
+                input.listener = listener;
+                input.phase = deferred.phase;
+                input.data = data;
+                input.triggered = 1;
+                callback.run(world, input).map(|input| input.event_data)
+            };
+            return_callback(world, *slot, callback, once);
+            record_deferred(world, event, target, *slot, phase);
+            false
         });
+        // Listeners may have deferred new events while the callbacks ran.
+        world
+            .resource_mut::<CooldownQueue<E>>()
+            .slots
+            .append(&mut slots);
     }
 }
 
-/// Build a branch of the event bubbling graph, starting from the target entity, traversing up the
-/// hierarchy through the parents. Any event listeners that are found during traversal will be added
-/// as nodes to the graph.
-fn build_branch_depth_first<E: EntityEvent>(
+/// Record that the listener in `slot` was triggered by a deferred event, once its callback ran:
+/// in the [`DispatchStats`], in the checksums if the dispatcher is deterministic, and in the
+/// [`EventHistory`], with a path that only contains the listener. The `event` is `None` if it was
+/// lost while the callback ran.
+fn record_deferred<E: EntityEvent>(
+    world: &mut World,
+    event: Option<E>,
+    target: Entity,
+    slot: ListenerSlot,
+    phase: EventPhase,
+) {
+    let path: Vec<Entity> = slot.entity().into_iter().collect();
+    if let Some(mut dispatcher) = world.get_resource_mut::<EventDispatcher<E>>() {
+        dispatcher.stats.listeners_triggered += 1;
+        dispatcher.total_listeners_triggered += 1;
+        if let Some(hash_event) = dispatcher.hash_event {
+            let checksum = match &event {
+                Some(event) => event_checksum(hash_event, Some(event), target, &path, 1),
+                None => event_checksum(hash_event, None, target, &[], 0),
+            };
+            dispatcher.checksum.write_u64(checksum);
+            dispatcher.frame_checksum.write_u64(checksum);
+        }
+    }
+    let Some(event) = event else {
+        return;
+    };
+    if let Some(mut history) = world.get_resource_mut::<EventHistory<E>>() {
+        let listener = slot.entity().unwrap_or(Entity::PLACEHOLDER);
+        history.push(HistoryEntry {
+            event,
+            target,
+            path,
+            propagation_stopped: false,
+            default_prevented: false,
+            listeners: vec![TriggeredListener {
+                listener,
+                phase,
+                stopped_propagation: false,
+            }],
+        });
+    }
+}
+
+/// Build a branch of the event bubbling graph, starting from the `target` entity, traversing up the
+/// hierarchy through the [`Traversal`] component. Any event listeners that are found during
+/// traversal will be added as nodes to the graph.
+///
+/// The full branch is built even for events that cannot bubble, because capture listeners on the
+/// target's ancestors must still be run.
+fn build_branch_depth_first<E: EntityEvent, T: Traversal>(
     event: &E,
+    target: Entity,
     dispatcher: &mut ResMut<EventDispatcher<E>>,
-    listeners: &mut Query<(Option<&mut On<E>>, Option<&Parent>)>,
+    listeners: &ListenerQuery<E, T>,
     dead_branch_nodes: &mut HashSet<Entity>,
     target_cache: &mut HashMap<Entity, Entity>,
+    source: Option<EventSource>,
 ) {
+    let cache_graph = dispatcher.cache_graph;
     let graph = &mut dispatcher.listener_graph;
-    let mut this_node = event.target();
+    let mut this_node = target;
     let mut prev_node = None;
     let mut first_listener = None;
 
     loop {
-        if let Some((_, next_node)) = graph.get(&this_node) {
-            // If the current entity is already in the map, use it to jump ahead
+        if graph.contains_key(&this_node) {
+            // If the current entity is already in the map, the rest of the branch has already been
+            // mapped, so we only need to point the previous node to it.
             if first_listener.is_none() {
                 first_listener = Some(this_node);
             }
-            if prev_node.is_none() {
-                break; // We can break if we aren't in the middle of mapping a path
-            }
-            match next_node {
-                Some(next_node) => this_node = *next_node,
-                None => break, // Bubble reached the surface!
+            if let Some(prev_nodes_next_node @ None) = prev_node.and_then(|e| graph.get_mut(&e)) {
+                *prev_nodes_next_node = Some(this_node);
             }
-        } else if let Ok((event_listener, parent)) = listeners.get_mut(this_node) {
-            // Otherwise, get the current entity's data with a query
-            if let Some(mut event_listener) = event_listener {
+            break;
+        } else if let Ok((event_listener, listener_list, parent, barrier)) =
+            listeners.get(this_node)
+        {
+            // Otherwise, get the current entity's data with a query. A cached graph can't tell when
+            // listeners are added to an empty list, so the list always counts.
+            let has_listener = event_listener.is_some()
+                || listener_list.is_some_and(|list| cache_graph || !list.is_empty());
+            if has_listener {
                 // If it has an event listener, we need to add it to the map
-                graph.insert(this_node, (event_listener.take(), None));
+                graph.insert(this_node, None);
                 // We must also point the previous node to this node
-                if let Some((_, prev_nodes_next_node @ None)) =
-                    prev_node.and_then(|e| graph.get_mut(&e))
+                if let Some(prev_nodes_next_node @ None) = prev_node.and_then(|e| graph.get_mut(&e))
                 {
                     *prev_nodes_next_node = Some(this_node);
                 }
@@ -155,35 +2384,199 @@ fn build_branch_depth_first<E: EntityEvent>(
                 }
                 prev_node = Some(this_node);
             }
-            match parent {
-                Some(parent) => this_node = **parent,
+            // Events never bubble past a barrier, so it is treated like the root of the hierarchy.
+            let next = parent.and_then(Traversal::next).filter(|_| !barrier);
+            match next {
+                Some(parent) => this_node = parent,
                 None => {
                     if first_listener.is_none() {
                         // No listeners were found when traversing the entire branch. To prevent
                         // other events re-traversing this dead branch, we record the target as
                         // belonging to a dead branch.
-                        dead_branch_nodes.insert(event.target());
+                        dead_branch_nodes.insert(target);
                     }
-                    break; // Bubble reached the surface!
+                    break; // Bubble reached the surface, or a barrier!
                 }
             }
         } else {
-            // This branch can only be reached if the listeners.get_mut() call fails. Note that the
+            // This branch can only be reached if the listeners.get() call fails. Note that the
             // query allows all components to be optional, which means this can only fail if the
             // entity no longer exists. This can happen if the entity targeted by the event was
             // deleted before the bubbling system could run.
             break;
         }
-
-        if !event.can_bubble() {
-            break;
-        }
     }
 
     if let Some(first_listener) = first_listener {
         // Only add events if they interact with an event listener.
-        dispatcher.events.push((event.to_owned(), first_listener));
-        target_cache.insert(event.target(), first_listener);
+        dispatcher
+            .events
+            .push((event.to_owned(), target, Some(first_listener), source));
+        target_cache.insert(target, first_listener);
+    }
+}
+
+impl<E: EntityEvent> EventDispatcher<E> {
+    /// Create a dispatcher that propagates events in the given `order`.
+    pub fn with_order(order: EventOrder) -> Self {
+        Self {
+            order,
+            ..Default::default()
+        }
+    }
+
+    /// The statistics for the events dispatched since the last call to
+    /// [`EventDispatcher::reset_stats`].
+    pub fn stats(&self) -> DispatchStats {
+        self.stats
+    }
+
+    /// Reset the dispatch statistics, returning their previous value.
+    pub fn reset_stats(&mut self) -> DispatchStats {
+        std::mem::take(&mut self.stats)
+    }
+
+    /// The number of events propagated since the dispatcher was created. Unlike
+    /// [`DispatchStats::events`], this is never reset, so several tools can measure the throughput
+    /// of events at once.
+    pub fn total_events(&self) -> usize {
+        self.total_events
+    }
+
+    /// The number of listeners triggered since the dispatcher was created. Like
+    /// [`EventDispatcher::total_events`], this is never reset.
+    pub fn total_listeners_triggered(&self) -> usize {
+        self.total_listeners_triggered
+    }
+
+    /// The checksum of every event dispatched since the dispatcher was created, if it is
+    /// deterministic, see [`EventDispatcher::with_checksum`].
+    pub fn checksum(&self) -> Option<u64> {
+        self.hash_event.map(|_| self.checksum.finish())
+    }
+
+    /// The checksum of the events dispatched this frame, or the last time events were dispatched
+    /// if they have not been dispatched yet this frame, if the dispatcher is deterministic, see
+    /// [`EventDispatcher::with_checksum`].
+    pub fn frame_checksum(&self) -> Option<u64> {
+        self.hash_event.map(|_| self.frame_checksum.finish())
+    }
+
+    /// Reset the [`EventDispatcher::frame_checksum`], before the events of the frame are
+    /// dispatched. This is added by
+    /// [`EventListenerPlugin::deterministic`](crate::EventListenerPlugin::deterministic).
+    pub fn reset_frame_checksum(mut dispatcher: ResMut<EventDispatcher<E>>) {
+        dispatcher.frame_checksum = StableHasher::default();
+    }
+
+    /// Enable or disable measuring the time taken to propagate each event, for
+    /// [`DispatchStats::dispatch_time`]. This is disabled by default.
+    pub fn measure_time(&mut self, enabled: bool) {
+        self.measure_time = enabled;
+    }
+
+    /// Set the [`Traversal`] component that events dispatched with
+    /// [`EventDispatcher::dispatch_now`] bubble through. Defaults to [`Parent`]. This should match
+    /// the component used by [`EventDispatcher::build`].
+    pub fn with_traversal<T: Traversal>(mut self) -> Self {
+        self.resolve_path = resolve_path::<E, T>;
+        self
+    }
+
+    /// Keep the listener graph between frames, so the hierarchy does not need to be traversed
+    /// again for every event. The graph is rebuilt when a listener, [`PropagationBarrier`], or
+    /// [`Traversal`] component is added or removed, or a [`Traversal`] component changes.
+    ///
+    /// Checking for changes visits every entity with a [`Traversal`] component each frame that
+    /// events are dispatched, so this is only worth it for deep hierarchies with sparse listeners,
+    /// that receive many events.
+    pub fn cache_listener_graph(mut self) -> Self {
+        self.cache_graph = true;
+        self
+    }
+
+    /// Only trigger the listeners on the target of each event, without traversing the hierarchy to
+    /// find the listeners on its ancestors. This is a fast path for events that never need to
+    /// bubble: each event costs a single lookup of its target, no matter how deep it is in the
+    /// hierarchy. This replaces the [`Traversal`] set with [`EventDispatcher::with_traversal`].
+    ///
+    /// [`GlobalListeners`] are still triggered, and [broadcast](Propagation::Broadcast) events
+    /// still travel down the hierarchy.
+    pub fn target_only(mut self) -> Self {
+        self.target_only = true;
+        self.resolve_path = resolve_target_only::<E>;
+        self
+    }
+
+    /// Stop propagating events once `budget` has been spent doing so in a frame. The remaining
+    /// events are carried over, and propagated next frame before any new events, so events are
+    /// still propagated in order. This spreads the work of large bursts of events over several
+    /// frames, at the cost of some events being handled later.
+    ///
+    /// The budget is checked between events, so it can be exceeded by the time taken to propagate
+    /// a single event. At least one event is propagated every frame. Carried over events are
+    /// propagated through the hierarchy as it is in the frame they are propagated in.
+    pub fn with_frame_budget(mut self, budget: std::time::Duration) -> Self {
+        self.frame_budget = Some(budget);
+        self
+    }
+
+    /// Make dispatch deterministic, hashing every event with `hash_event` into the
+    /// [`EventDispatcher::checksum`] and [`EventDispatcher::frame_checksum`], see
+    /// [`EventListenerPlugin::deterministic`](crate::EventListenerPlugin::deterministic). The
+    /// [frame budget](EventDispatcher::with_frame_budget) is ignored, since it depends on how fast
+    /// the machine is.
+    pub fn with_checksum(mut self, hash_event: fn(&E, &mut StableHasher)) -> Self {
+        self.hash_event = Some(hash_event);
+        self
+    }
+
+    /// Dispatch the events of type `E` sent by callbacks as soon as the event whose callbacks sent
+    /// them has finished propagating, instead of the next time the dispatch systems run, see
+    /// [`EventListenerPlugin::dispatch_nested`](crate::EventListenerPlugin::dispatch_nested). The
+    /// dispatcher must also be registered with the `NestedDispatch` resource, which the plugin
+    /// does.
+    pub fn with_nested_dispatch(mut self, max_depth: usize) -> Self {
+        self.nested = Some(max_depth);
+        self
+    }
+
+    /// Limit the number of events waiting to be propagated to `limit`, handling the events over
+    /// the limit with the `overflow` policy, see [`QueueOverflow`].
+    pub fn with_queue_limit(mut self, limit: usize, overflow: QueueOverflow) -> Self {
+        self.queue_limit = Some((limit, overflow));
+        self
+    }
+
+    /// Combine the events sent to the same target in the same frame before they are propagated,
+    /// see [`Coalesce`].
+    pub fn coalesce_events(mut self, coalesce: Coalesce<E>) -> Self {
+        self.coalesce = Some(coalesce);
+        self
+    }
+
+    /// Send events selected by `forward` as a [`DispatchedEvent`] once they have finished
+    /// propagating. The [`DispatchedEvent<E>`] event must be added to the app.
+    pub fn forward_events(mut self, forward: ForwardEvents) -> Self {
+        self.forward = Some(forward);
+        self
+    }
+
+    /// Log the path of each event once it has finished propagating, along with the listeners it
+    /// triggered, see [`EventListenerPlugin::with_debug_logging`](crate::EventListenerPlugin::with_debug_logging).
+    #[cfg(feature = "trace")]
+    pub fn with_debug_logging(mut self) -> Self {
+        self.debug_logging = true;
+        self
+    }
+
+    /// Log a warning when an event reaches an entity that was despawned while the event was
+    /// propagating, see
+    /// [`EventListenerPlugin::with_despawn_warnings`](crate::EventListenerPlugin::with_despawn_warnings).
+    #[cfg(feature = "trace")]
+    pub fn with_despawn_warnings(mut self) -> Self {
+        self.warn_despawned = true;
+        self
     }
 }
 
@@ -192,6 +2585,35 @@ impl<E: EntityEvent> Default for EventDispatcher<E> {
         Self {
             events: Vec::new(),
             listener_graph: HashMap::new(),
+            path: Vec::new(),
+            stack: Vec::new(),
+            slots: Vec::new(),
+            batches: ListenerBatches::default(),
+            carried: Vec::new(),
+            held: Vec::new(),
+            frame_budget: None,
+            queue_limit: None,
+            cache_graph: false,
+            target_only: false,
+            resolve_path: resolve_path::<E, Parent>,
+            order: EventOrder::default(),
+            coalesce: None,
+            forward: None,
+            stats: DispatchStats::default(),
+            total_events: 0,
+            total_listeners_triggered: 0,
+            nested: None,
+            nested_ids: HashSet::new(),
+            deferred_ids: HashSet::new(),
+            hash_event: None,
+            checksum: StableHasher::default(),
+            frame_checksum: StableHasher::default(),
+            measure_time: false,
+            #[cfg(feature = "trace")]
+            debug_logging: false,
+            #[cfg(feature = "trace")]
+            warn_despawned: false,
+            triggered_log: Vec::new(),
         }
     }
 }
@@ -1,21 +1,241 @@
-use std::marker::PhantomData;
+use std::{
+    borrow::Cow,
+    marker::PhantomData,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
-use crate::callbacks::{CallbackSystem, ListenerInput};
+use crate::{
+    async_callbacks::AsyncCallbackTasks,
+    callback_errors::{CallbackError, CallbackErrorHandler},
+    callbacks::{
+        BatchedEvent, Callback, CallbackSystem, IntoCallback, ListenerCondition, ListenerData,
+        ListenerInput, RateLimit, SharedCallback, TargetFilter,
+    },
+};
 use bevy_ecs::{
     prelude::*,
-    system::{Command, EntityCommands},
+    query::ReadOnlyWorldQuery,
+    system::{Command, CommandQueue, EntityCommands},
 };
+use bevy_hierarchy::DespawnRecursiveExt;
 #[cfg(feature = "trace")]
 use bevy_utils::tracing::error;
+use bevy_utils::{synccell::SyncCell, HashSet};
+use smallvec::{smallvec, SmallVec};
+
+/// The entities an event propagates from, returned by [`EntityEvent::targets`].
+pub type EventTargets = SmallVec<[Entity; 4]>;
 
 /// An event that targets a specific entity, and should support event listeners and bubbling.
 pub trait EntityEvent: Event + Clone {
+    /// The entity this event is sent to.
+    ///
+    /// Events that do not always have a subject, like notifications, can return
+    /// [`Entity::PLACEHOLDER`] when they have no target. These events skip the entity hierarchy,
+    /// and are delivered straight to the [`GlobalListeners`], in the [`EventPhase::AtTarget`]
+    /// phase, even if they cannot bubble. The derive macro does this for `#[target]` fields of
+    /// type `Option<Entity>`.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::EntityEvent;
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Clone, Event)]
+    /// struct Notification {
+    ///     subject: Option<Entity>,
+    ///     message: String,
+    /// }
+    ///
+    /// impl EntityEvent for Notification {
+    ///     fn target(&self) -> Entity {
+    ///         self.subject.unwrap_or(Entity::PLACEHOLDER)
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// [`EventPhase::AtTarget`]: crate::callbacks::EventPhase::AtTarget
     fn target(&self) -> Entity;
+    /// All the entities targeted by this event. The event propagates independently from each
+    /// target, as if a copy of the event had been sent to each of them. Defaults to
+    /// [`EntityEvent::target`].
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{event_listener::EntityEvent, smallvec::SmallVec};
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Clone, Event)]
+    /// struct Explosion {
+    ///     hit: SmallVec<[Entity; 4]>,
+    /// }
+    ///
+    /// impl EntityEvent for Explosion {
+    ///     fn target(&self) -> Entity {
+    ///         self.hit[0]
+    ///     }
+    ///
+    ///     fn targets(&self) -> SmallVec<[Entity; 4]> {
+    ///         self.hit.clone()
+    ///     }
+    /// }
+    /// ```
+    fn targets(&self) -> EventTargets {
+        smallvec![self.target()]
+    }
     /// Should events of this type bubble up the entity hierarchy, starting from the target? This is
     /// enabled by default.
     fn can_bubble(&self) -> bool {
         true
     }
+    /// How should this event propagate through the entity hierarchy? Defaults to
+    /// [`Propagation::Bubble`].
+    fn propagation(&self) -> Propagation {
+        Propagation::Bubble
+    }
+}
+
+/// Describes how an [`EntityEvent`] propagates through the entity hierarchy.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum Propagation {
+    /// The event travels down from the root to the target, triggering capture listeners, then
+    /// bubbles back up to the root, triggering all other listeners.
+    #[default]
+    Bubble,
+    /// The event is delivered to the target, then broadcast down the hierarchy to all of the
+    /// target's descendants, visiting them depth first. Both capture and bubbling listeners are
+    /// triggered. Stopping propagation prevents the event from reaching the descendants of the
+    /// current entity, while the rest of the hierarchy is still visited.
+    ///
+    /// Broadcast events always travel through [`Children`](bevy_hierarchy::Children), regardless of
+    /// the [`Traversal`](crate::traversal::Traversal) used for bubbling.
+    Broadcast {
+        /// How many levels below the target the event can reach. The target's children are at a
+        /// depth of `1`. If `None`, every descendant is visited.
+        max_depth: Option<usize>,
+    },
+    /// The event searches down the hierarchy from the target, visiting its descendants depth first
+    /// like [`Propagation::Broadcast`], until it reaches an entity with a listener that is
+    /// triggered by it. Once the listeners on that entity have run, the event stops propagating.
+    /// This is useful for commands sent to a container that does not know which of its children
+    /// should respond, like closing whatever is open inside it.
+    ///
+    /// Global capture listeners are triggered before the search. Other global listeners are only
+    /// triggered if no entity handled the event, so they can act as a fallback. Stopping
+    /// propagation in a capture listener cancels the search.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, Propagation};
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Clone, Event)]
+    /// struct CloseMenu {
+    ///     container: Entity,
+    /// }
+    ///
+    /// impl EntityEvent for CloseMenu {
+    ///     fn target(&self) -> Entity {
+    ///         self.container
+    ///     }
+    ///
+    ///     fn propagation(&self) -> Propagation {
+    ///         Propagation::FindHandler { max_depth: None }
+    ///     }
+    /// }
+    /// ```
+    FindHandler {
+        /// How many levels below the target the search can reach. The target's children are at a
+        /// depth of `1`. If `None`, every descendant can be visited.
+        max_depth: Option<usize>,
+    },
+}
+
+impl Propagation {
+    /// Does the event travel down the hierarchy through [`Children`](bevy_hierarchy::Children),
+    /// instead of using the listener graph?
+    pub(crate) fn is_downward(self) -> bool {
+        matches!(
+            self,
+            Propagation::Broadcast { .. } | Propagation::FindHandler { .. }
+        )
+    }
+}
+
+/// A predicate on the event data, see [`On::filter`].
+type EventFilter<E> = Box<dyn Fn(&E) -> bool + Send + Sync>;
+
+/// Which targets a listener can be triggered by, relative to the entity it is on.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub(crate) enum TargetMode {
+    /// The listener is triggered by events targeting its entity or any of its descendants.
+    #[default]
+    Any,
+    /// The listener is only triggered by events targeting its own entity, see [`On::self_only`].
+    SelfOnly,
+    /// The listener is only triggered by events targeting one of its descendants, see
+    /// [`On::descendants_only`].
+    DescendantsOnly,
+}
+
+impl TargetMode {
+    /// Can a listener in this mode be triggered, depending on whether it is on the target?
+    pub(crate) fn allows(self, on_target: bool) -> bool {
+        match self {
+            TargetMode::Any => true,
+            TargetMode::SelfOnly => on_target,
+            TargetMode::DescendantsOnly => !on_target,
+        }
+    }
+}
+
+/// The timeout of a listener created with [`On::expires_after`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) struct Expiry {
+    /// How long the listener lives.
+    lifetime: std::time::Duration,
+    /// The [`Time::elapsed`](bevy_time::Time::elapsed) the listener is removed at, set the first
+    /// time the listener is checked.
+    deadline: Option<std::time::Duration>,
+}
+
+/// The listeners for events of type `E` that were added or changed.
+type ChangedListenersQuery<'w, 's, E> = Query<
+    'w,
+    's,
+    (Option<&'static On<E>>, Option<&'static Listeners<E>>),
+    Or<(Changed<On<E>>, Changed<Listeners<E>>)>,
+>;
+
+/// The number of listeners for events of type `E` created with [`On::expires_after`] or
+/// [`On::while_present`] that had not expired the last time [`On::remove_expired`] ran. The system
+/// only runs while there are any, or such a listener was added or changed, so triggering other
+/// listeners doesn't make it run. This is added by the
+/// [`EventListenerPlugin`](crate::EventListenerPlugin).
+#[derive(Resource)]
+pub struct ExpiringListeners<E: EntityEvent> {
+    remaining: usize,
+    phantom: PhantomData<fn() -> E>,
+}
+
+impl<E: EntityEvent> Default for ExpiringListeners<E> {
+    fn default() -> Self {
+        Self {
+            remaining: 0,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent> ExpiringListeners<E> {
+    /// A run condition that returns `true` if a listener may have to be removed by
+    /// [`On::remove_expired`].
+    pub(crate) fn has_expiring(
+        expiring: Res<ExpiringListeners<E>>,
+        changed: ChangedListenersQuery<E>,
+        global: Option<Res<GlobalListeners<E>>>,
+    ) -> bool {
+        let has_expiring = |list: &Listeners<E>| list.listeners.iter().any(|(_, on)| on.expiring());
+        expiring.remaining > 0
+            || changed
+                .iter()
+                .any(|(on, list)| on.is_some_and(On::expiring) || list.is_some_and(has_expiring))
+            || global.is_some_and(|global| global.is_changed() && has_expiring(&global.listeners))
+    }
 }
 
 /// An event listener with a callback that is triggered when an [`EntityEvent`] bubbles past or
@@ -29,7 +249,46 @@ pub trait EntityEvent: Event + Clone {
 pub struct On<E: EntityEvent> {
     phantom: PhantomData<E>,
     /// A function that is called when the event listener is triggered.
-    pub(crate) callback: CallbackSystem,
+    pub(crate) callback: CallbackSystem<E>,
+    /// Should this listener be triggered while the event is trickling down from the root to the
+    /// target, instead of while it is bubbling back up?
+    pub(crate) capture: bool,
+    /// Listeners with a higher priority are triggered before other listeners on the same entity.
+    pub(crate) priority: i32,
+    /// Should this listener be removed after it is triggered for the first time?
+    pub(crate) once: bool,
+    /// Run conditions that must all be met for this listener to be triggered.
+    pub(crate) conditions: Vec<ListenerCondition>,
+    /// Limits how often this listener can be triggered.
+    pub(crate) rate_limit: Option<RateLimit>,
+    /// Predicates on the event data that must all pass for this listener to be triggered.
+    pub(crate) filters: Vec<EventFilter<E>>,
+    /// The query filter the target of the event must match, set with [`On::delegate`].
+    pub(crate) delegate: Option<TargetFilter>,
+    /// The [`ListenerGroups`] group this listener belongs to, if any.
+    pub(crate) group: Option<Cow<'static, str>>,
+    /// Is this listener currently muted?
+    pub(crate) disabled: bool,
+    /// Which targets can trigger this listener.
+    pub(crate) target_mode: TargetMode,
+    /// When this listener is removed after a timeout, set with [`On::expires_after`].
+    pub(crate) expiry: Option<Expiry>,
+    /// The component this listener's entity must have, set with [`On::while_present`].
+    pub(crate) companion: Option<std::any::TypeId>,
+    /// Has this listener expired, and is it waiting to be removed? It is not triggered anymore.
+    pub(crate) removing: bool,
+    /// The data attached to this listener with [`On::with_data`].
+    pub(crate) data: ListenerData,
+    /// The name this listener was registered under in a
+    /// [`CallbackRegistry`](crate::callback_registry::CallbackRegistry), if it was created by one.
+    pub(crate) registered_name: Option<String>,
+    /// The last event suppressed by a cooldown, which triggers this listener once the cooldown is
+    /// over, see [`On::with_deferred_cooldown`].
+    pub(crate) deferred: Option<BatchedEvent<E>>,
+    /// The number of times this listener was triggered.
+    pub(crate) times_fired: u64,
+    /// The frame this listener was last triggered in.
+    pub(crate) last_fired_frame: Option<u32>,
 }
 
 impl<E: EntityEvent> On<E> {
@@ -38,13 +297,781 @@ impl<E: EntityEvent> On<E> {
     /// systems is that the callback system can access a resource with event data,
     /// [`ListenerInput`]. You can more easily access this with the system params
     /// [`Listener`](crate::callbacks::Listener) and [`ListenerMut`](crate::callbacks::ListenerMut).
-    pub fn run<Marker>(callback: impl IntoSystem<(), (), Marker>) -> Self {
+    ///
+    /// The callback can also be a tuple of systems, which are run in order every time the listener
+    /// is triggered. A system in the chain can call
+    /// [`ListenerInput::stop_immediate_propagation`] to skip the rest of the chain.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{callbacks::ListenerMut, event_listener::{EntityEvent, On}};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Drop {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Drop {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// fn validate(mut event: ListenerMut<Drop>) {
+    ///     # let invalid = false;
+    ///     if invalid {
+    ///         event.stop_immediate_propagation();
+    ///     }
+    /// }
+    /// fn accept() {}
+    /// fn play_sound() {}
+    ///
+    /// let _ = On::<Drop>::run((validate, accept, play_sound));
+    /// ```
+    pub fn run<Marker>(callback: impl IntoCallback<Marker>) -> Self {
+        Self::from_callback(callback.into_callback())
+    }
+
+    /// Run a callback system every time this event listener is triggered, like [`On::run`], and
+    /// record the value it returns as a response to the event, with [`ListenerInput::respond`].
+    /// Listeners triggered later, and the sender of the event, can read the responses, see
+    /// [`EventDispatcher::request`](crate::event_dispatcher::EventDispatcher::request).
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     event_dispatcher::EventDispatcher,
+    /// #     event_listener::{EntityEvent, On}, EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Clone, Event)]
+    /// struct CanDrop {
+    ///     target: Entity,
+    /// }
+    /// # impl EntityEvent for CanDrop {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins(EventListenerPlugin::<CanDrop>::default());
+    /// let slot = app.world.spawn(On::<CanDrop>::run_with_output(|| true)).id();
+    ///
+    /// let request = CanDrop { target: slot };
+    /// let (_, responses) = EventDispatcher::request(&mut app.world, request).unwrap();
+    /// assert_eq!(responses.first::<bool>(), Some(&true));
+    /// ```
+    pub fn run_with_output<T: Send + Sync + 'static, Marker>(
+        callback: impl IntoSystem<(), T, Marker>,
+    ) -> Self {
+        On::run(
+            callback.pipe(|In(response): In<T>, mut input: ResMut<ListenerInput<E>>| {
+                input.respond(response)
+            }),
+        )
+    }
+
+    /// Run a fallible callback system every time this event listener is triggered, like
+    /// [`On::run`]. If the callback returns an error, it is passed to the
+    /// [`CallbackErrorHandler<E>`](crate::callback_errors::CallbackErrorHandler) once the callback
+    /// has finished running, see the [`callback_errors`](crate::callback_errors) module.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Load {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Load {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// fn load_level() -> Result<(), std::io::Error> {
+    ///     std::fs::read("level.ron")?;
+    ///     Ok(())
+    /// }
+    ///
+    /// let _ = On::<Load>::run_fallible(load_level);
+    /// ```
+    pub fn run_fallible<Err, Marker>(callback: impl IntoSystem<(), Result<(), Err>, Marker>) -> Self
+    where
+        Err: Into<Box<dyn std::error::Error + Send + Sync>> + 'static,
+    {
+        On::run(callback.pipe(
+            |In(result): In<Result<(), Err>>,
+             input: Res<ListenerInput<E>>,
+             mut commands: Commands| {
+                let Err(error) = result else {
+                    return;
+                };
+                let error = CallbackError {
+                    error: error.into(),
+                    event: input.event_data.clone(),
+                    target: input.target,
+                    listener: input.listener,
+                    panicked: false,
+                };
+                commands.add(move |world: &mut World| CallbackErrorHandler::handle(world, error));
+            },
+        ))
+    }
+
+    /// Run a callback system that receives the [`ListenerInput`] as a system input, every time
+    /// this event listener is triggered.
+    ///
+    /// Unlike [`On::run`], the input is not inserted into the world as a resource. Instead, it is
+    /// passed to the callback with [`In`], and the callback must return it when it is done. This
+    /// lets the callback mutate the event or stop its propagation before giving it back to the
+    /// dispatcher, without the overhead of inserting and removing a resource.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{callbacks::ListenerInput, event_listener::{EntityEvent, On}};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct MyEvent {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for MyEvent {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// fn my_callback(In(mut event): In<ListenerInput<MyEvent>>) -> ListenerInput<MyEvent> {
+    ///     event.stop_propagation();
+    ///     event
+    /// }
+    /// # let _ = On::<MyEvent>::run_with_input(my_callback);
+    /// ```
+    pub fn run_with_input<Marker>(
+        callback: impl IntoSystem<ListenerInput<E>, ListenerInput<E>, Marker>,
+    ) -> Self {
+        Self::from_callback(Callback::Input(Box::new(IntoSystem::into_system(callback))))
+    }
+
+    /// Run a closure with exclusive access to the [`World`] every time this event listener is
+    /// triggered. The closure can make structural changes, like spawning entities and inserting
+    /// components, and see them right away, without going through [`Commands`]. The event is
+    /// passed as a mutable [`ListenerInput`], so the closure can also stop its propagation.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Split {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Split {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # #[derive(Component, Clone, Copy)]
+    /// # struct Size(f32);
+    /// # #[derive(Component)]
+    /// # struct Half;
+    /// let listener = On::<Split>::run_exclusive(|world, event| {
+    ///     let Some(&Size(size)) = world.get::<Size>(event.listener()) else {
+    ///         return;
+    ///     };
+    ///     let halves = [world.spawn(Size(size / 2.0)).id(), world.spawn(Size(size / 2.0)).id()];
+    ///     // The new entities can be used right away.
+    ///     for half in halves {
+    ///         world.entity_mut(half).insert(Half);
+    ///     }
+    ///     world.despawn(event.listener());
+    ///     event.stop_propagation();
+    /// });
+    /// ```
+    pub fn run_exclusive(
+        mut callback: impl FnMut(&mut World, &mut ListenerInput<E>) + Send + Sync + 'static,
+    ) -> Self {
+        Self::run_with_input(
+            move |In(mut input): In<ListenerInput<E>>, world: &mut World| {
+                callback(world, &mut input);
+                input
+            },
+        )
+    }
+
+    /// Run a callback system once per frame with every event that triggered this listener, instead
+    /// of once per event. The callback accesses the events with
+    /// [`ListenerBatch`](crate::callbacks::ListenerBatch). This avoids the overhead of running a
+    /// system for each event, when many events reach the same listener every frame, like pointer
+    /// movement.
+    ///
+    /// The callback is run once every event of the frame has finished propagating, so it cannot
+    /// stop the propagation of the events, or change them. Each event is recorded as it was when it
+    /// reached the listener. Filters, run conditions, and rate limits are still checked for each
+    /// event.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{callbacks::ListenerBatch, event_listener::{EntityEvent, On}};
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_math::Vec2;
+    /// # #[derive(Clone, Event)]
+    /// # struct PointerMove {
+    /// #     target: Entity,
+    /// #     delta: Vec2,
+    /// # }
+    /// # impl EntityEvent for PointerMove {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// let listener = On::<PointerMove>::run_batched(|moves: ListenerBatch<PointerMove>| {
+    ///     let total: Vec2 = moves.iter().map(|event| event.delta).sum();
+    ///     // ...
+    /// });
+    /// ```
+    pub fn run_batched<Marker>(callback: impl IntoSystem<(), (), Marker>) -> Self {
+        Self::from_callback(Callback::Batched(Box::new(IntoSystem::into_system(
+            callback,
+        ))))
+    }
+
+    /// Run a shared callback system from the [`SharedCallbacks`](crate::callbacks::SharedCallbacks)
+    /// resource every time this event listener is triggered. Use this instead of [`On::run`] when
+    /// many entities run the same callback, so the system is only stored and initialized once.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     callbacks::Listener, event_listener::{EntityEvent, On}, EventListenerAppExt,
+    /// #     EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// fn on_click(event: Listener<Click>) {
+    ///     // ...
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins(EventListenerPlugin::<Click>::default());
+    /// let on_click = app.add_shared_callback::<Click, _>(on_click);
+    /// for _ in 0..10_000 {
+    ///     app.world.spawn(On::<Click>::run_shared(on_click));
+    /// }
+    /// ```
+    pub fn run_shared(callback: SharedCallback<E>) -> Self {
+        Self::from_callback_system(CallbackSystem::Shared(callback))
+    }
+
+    /// Run a callback system the first time this event listener is triggered, then remove the
+    /// listener from its entity.
+    ///
+    /// The listener is removed as soon as the callback has run, so it will not be triggered again,
+    /// even by other events dispatched in the same frame. If the listener is in a [`Listeners`]
+    /// component, only this listener is removed.
+    pub fn once<Marker>(callback: impl IntoSystem<(), (), Marker>) -> Self {
         Self {
-            phantom: PhantomData,
-            callback: CallbackSystem::New(Box::new(IntoSystem::into_system(callback))),
+            once: true,
+            ..Self::run(callback)
+        }
+    }
+
+    /// Run a closure the first time this event listener is triggered, then remove the listener,
+    /// like [`On::once`]. The closure is only called once, so it can consume the data it captured,
+    /// like the sender of a channel. It has exclusive access to the [`World`], like
+    /// [`On::run_exclusive`].
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_eventlistener_core::EventListenerPlugin;
+    /// # #[derive(Clone, Event)]
+    /// # struct Loaded {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Loaded {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # let mut app = App::new();
+    /// # app.add_plugins(EventListenerPlugin::<Loaded>::default());
+    /// let (sender, receiver) = std::sync::mpsc::channel();
+    /// let level = app
+    ///     .world
+    ///     .spawn(On::<Loaded>::run_once_with(move |_world, event| {
+    ///         sender.send(event.target()).unwrap();
+    ///         drop(sender);
+    ///     }))
+    ///     .id();
+    ///
+    /// app.world.send_event(Loaded { target: level });
+    /// app.update();
+    /// assert_eq!(receiver.recv(), Ok(level));
+    /// // The closure consumed the sender, so the channel is closed.
+    /// assert!(receiver.recv().is_err());
+    /// ```
+    pub fn run_once_with(
+        callback: impl FnOnce(&mut World, &mut ListenerInput<E>) + Send + 'static,
+    ) -> Self {
+        // The cell makes the closure `Sync`, since it is only accessed mutably.
+        let mut callback = SyncCell::new(Some(callback));
+        Self {
+            once: true,
+            ..Self::run_exclusive(move |world, input| {
+                if let Some(callback) = callback.get().take() {
+                    callback(world, input);
+                }
+            })
+        }
+    }
+
+    /// Run a callback system every time this event listener is triggered, with the given priority.
+    /// See [`On::with_priority`] for more details.
+    pub fn run_with_priority<Marker>(
+        priority: i32,
+        callback: impl IntoSystem<(), (), Marker>,
+    ) -> Self {
+        Self::run(callback).with_priority(priority)
+    }
+
+    /// Set the priority of this listener. When an entity has more than one listener for the same
+    /// event, listeners with a higher priority are triggered first. Listeners with the same
+    /// priority are triggered in the order they were added, starting with the [`On`] component.
+    /// The default priority is `0`.
+    ///
+    /// Priority only affects the order of listeners on the same entity. Events always propagate
+    /// through the hierarchy in the same order: down from the root to the target while capturing,
+    /// then back up to the root while bubbling.
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Only trigger this listener if the run `condition` is met. Conditions are evaluated every time
+    /// the listener would be triggered, before its callback is initialized or run. If a listener
+    /// has more than one condition, all of them must be met.
+    ///
+    /// When a condition is not met, the listener is skipped, and the event continues to propagate
+    /// as if the listener did not exist. Conditions are run like normal systems, and cannot access
+    /// the [`ListenerInput`].
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// #[derive(Resource, PartialEq)]
+    /// struct Paused(bool);
+    ///
+    /// # fn open_door() {}
+    /// let listener = On::<Click>::run(open_door).run_if(resource_equals(Paused(false)));
+    /// ```
+    pub fn run_if<Marker>(mut self, condition: impl Condition<Marker>) -> Self {
+        self.conditions.push(ListenerCondition::new(condition));
+        self
+    }
+
+    /// Only trigger this listener for events that pass the `filter` predicate. Filters are checked
+    /// before any run conditions, and are much cheaper than running a callback system, because
+    /// they only need the event data. If a listener has more than one filter, all of them must
+    /// pass.
+    ///
+    /// Events that do not pass the filter continue to propagate as if the listener did not exist.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Clone, Event)]
+    /// struct Damage {
+    ///     target: Entity,
+    ///     amount: u32,
+    /// }
+    /// # impl EntityEvent for Damage {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    ///
+    /// # fn stagger() {}
+    /// let listener = On::<Damage>::run(stagger).filter(|event: &Damage| event.amount > 10);
+    /// ```
+    pub fn filter(mut self, filter: impl Fn(&E) -> bool + Send + Sync + 'static) -> Self {
+        self.filters.push(Box::new(filter));
+        self
+    }
+
+    /// Run a callback system when an event targeting an entity that matches the query filter `F`
+    /// reaches this listener, like delegated event handlers in the DOM. A single listener on a
+    /// container can handle the events of all its matching descendants, instead of one listener on
+    /// each of them. Use [`ListenerInput::target`] in the callback to find out which entity was
+    /// targeted.
+    ///
+    /// Events targeting entities that don't match the filter don't run the callback, and continue to
+    /// propagate as if the listener did not exist.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{callbacks::Listener, event_listener::{EntityEvent, On}};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// #[derive(Component)]
+    /// struct Row;
+    ///
+    /// fn select_row(click: Listener<Click>) {
+    ///     println!("selected {:?}", click.target());
+    /// }
+    ///
+    /// fn spawn_list(mut commands: Commands) {
+    ///     commands.spawn(On::<Click>::delegate::<With<Row>, _>(select_row));
+    /// }
+    /// ```
+    pub fn delegate<F: ReadOnlyWorldQuery + 'static, Marker>(
+        callback: impl IntoCallback<Marker>,
+    ) -> Self {
+        Self {
+            delegate: Some(TargetFilter::new::<F>()),
+            ..Self::run(callback)
+        }
+    }
+
+    /// Only trigger this listener when its own entity is the target of the event, ignoring events
+    /// that bubbled up from its descendants. This replaces [`On::descendants_only`]. Unlike comparing
+    /// [`ListenerInput::listener`] with the target in the callback, the callback is not run at all
+    /// for other events, which continue to propagate as if the listener did not exist.
+    ///
+    /// Global listeners are not on any entity, so they are never triggered in this mode.
+    pub fn self_only(mut self) -> Self {
+        self.target_mode = TargetMode::SelfOnly;
+        self
+    }
+
+    /// Only trigger this listener for events targeting one of its entity's descendants, never
+    /// when its own entity is the target. This is the opposite of [`On::self_only`], and replaces
+    /// it. It is useful for containers that react to the interactions of their children.
+    ///
+    /// Global listeners are not on any entity, so they are triggered by every event in this mode.
+    pub fn descendants_only(mut self) -> Self {
+        self.target_mode = TargetMode::DescendantsOnly;
+        self
+    }
+
+    /// Attach `data` to this listener, replacing any data attached before. The callback can read it
+    /// with [`ListenerInput::data`] while this listener is being triggered. This lets many
+    /// listeners share a callback, while each of them has its own parameters, without a side table
+    /// keyed by the listener's entity.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{callbacks::Listener, event_listener::{EntityEvent, On}};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// struct RowIndex(usize);
+    ///
+    /// fn select_row(click: Listener<Click>) {
+    ///     let RowIndex(index) = click.data::<RowIndex>().unwrap();
+    ///     println!("selected row {index}");
+    /// }
+    ///
+    /// fn spawn_rows(mut commands: Commands) {
+    ///     for index in 0..100 {
+    ///         commands.spawn(On::<Click>::run(select_row).with_data(RowIndex(index)));
+    ///     }
+    /// }
+    /// ```
+    pub fn with_data<T: Send + Sync + 'static>(mut self, data: T) -> Self {
+        self.data = ListenerData::new(data);
+        self
+    }
+
+    /// The data attached to this listener with [`On::with_data`], if it is of type `T`.
+    pub fn data<T: Send + Sync + 'static>(&self) -> Option<&T> {
+        self.data.get()
+    }
+
+    /// Disable this listener, so it is not triggered until it is enabled with
+    /// [`On::set_enabled`].
+    pub fn disabled(mut self) -> Self {
+        self.disabled = true;
+        self
+    }
+
+    /// Enable or disable this listener. While a listener is disabled, it is skipped, and events
+    /// continue to propagate as if it did not exist. Unlike removing the listener, this keeps its
+    /// callback system, along with any state the system has.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.disabled = !enabled;
+    }
+
+    /// Returns `true` if this listener can be triggered, see [`On::set_enabled`].
+    pub fn is_enabled(&self) -> bool {
+        !self.disabled
+    }
+
+    /// The name this listener was registered under, if it was created by a
+    /// [`CallbackRegistry`](crate::callback_registry::CallbackRegistry).
+    pub fn registered_name(&self) -> Option<&str> {
+        self.registered_name.as_deref()
+    }
+
+    /// The number of times this listener was triggered. Batched listeners count each event in a
+    /// batch.
+    pub fn times_fired(&self) -> u64 {
+        self.times_fired
+    }
+
+    /// The [`FrameCount`](bevy_core::FrameCount) of the frame this listener was last triggered in,
+    /// if it was triggered while the `FrameCount` resource exists.
+    pub fn last_fired_frame(&self) -> Option<u32> {
+        self.last_fired_frame
+    }
+
+    /// Add this listener to a named group, that can be disabled at runtime with the
+    /// [`ListenerGroups`] resource. A listener can only be in one group, this replaces any previous
+    /// group.
+    pub fn in_group(mut self, group: impl Into<Cow<'static, str>>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Only trigger this listener while the [`State<S>`] is equal to `state`. Events dispatched
+    /// while in any other state skip this listener. This is shorthand for
+    /// [`On::run_if`]`(in_state(state))`.
+    pub fn in_state<S: States>(self, state: S) -> Self {
+        self.run_if(in_state(state))
+    }
+
+    /// Trigger this listener at most once per `period`, also known as a cooldown. Events that
+    /// reach the listener less than `period` after it was last triggered are ignored, without
+    /// running the callback, and continue to propagate as if the listener did not exist. Use
+    /// [`On::with_deferred_cooldown`] to keep the suppressed events instead.
+    ///
+    /// Time is measured with [`Time::elapsed`](bevy_time::Time::elapsed). If there is no
+    /// [`Time`](bevy_time::Time) resource, the listener is never throttled. This replaces any
+    /// previous [`On::debounce`].
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// # use std::time::Duration;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// fn fire_weapon() {}
+    ///
+    /// let listener = On::<Click>::run(fire_weapon).throttle(Duration::from_millis(250));
+    /// ```
+    pub fn throttle(mut self, period: std::time::Duration) -> Self {
+        self.rate_limit = Some(RateLimit::Throttle {
+            period,
+            last_run: None,
+            defer: false,
+        });
+        self
+    }
+
+    /// Prevent this listener from being triggered again for a cooldown `period` after it was
+    /// triggered, like [`On::throttle`]. The last event that reached the listener during the
+    /// cooldown is kept, and triggers the listener once the cooldown is over, the next time the
+    /// events of type `E` are dispatched. Earlier events suppressed during the same cooldown are
+    /// dropped.
+    ///
+    /// The deferred event is not propagating anymore when the listener is triggered, so stopping
+    /// its propagation or preventing its default action has no effect. It is dropped if the
+    /// listener is disabled, its group is disabled, or its run conditions are not met once the
+    /// cooldown is over. When it is triggered, the listener is recorded in the
+    /// [`EventHistory`](crate::history::EventHistory), the
+    /// [`DispatchStats`](crate::event_dispatcher::DispatchStats) and the checksums of the
+    /// dispatcher, but the [`EventHooks`](crate::hooks::EventHooks) don't see the deferred event
+    /// again.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{event_listener::{EntityEvent, On}, EventListenerPlugin};
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_time::Time;
+    /// # use std::time::Duration;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # #[derive(Resource, Default)]
+    /// # struct Shots(usize);
+    /// let mut app = App::new();
+    /// app.add_plugins(EventListenerPlugin::<Click>::default())
+    ///     .init_resource::<Time>()
+    ///     .init_resource::<Shots>();
+    /// let gun = app
+    ///     .world
+    ///     .spawn(
+    ///         On::<Click>::run(|mut shots: ResMut<Shots>| shots.0 += 1)
+    ///             .with_deferred_cooldown(Duration::from_millis(250)),
+    ///     )
+    ///     .id();
+    /// let start = app.world.resource::<Time>().startup();
+    /// let update_at = |app: &mut App, millis| {
+    ///     let now = start + Duration::from_millis(millis);
+    ///     app.world.resource_mut::<Time>().update_with_instant(now);
+    ///     app.update();
+    /// };
+    ///
+    /// app.world.send_event(Click { target: gun });
+    /// update_at(&mut app, 0);
+    /// // This click is deferred until the cooldown is over.
+    /// app.world.send_event(Click { target: gun });
+    /// update_at(&mut app, 100);
+    /// assert_eq!(app.world.resource::<Shots>().0, 1);
+    ///
+    /// // The listener is disabled before the cooldown is over, so the deferred click is dropped.
+    /// app.world.get_mut::<On<Click>>(gun).unwrap().set_enabled(false);
+    /// update_at(&mut app, 300);
+    /// assert_eq!(app.world.resource::<Shots>().0, 1);
+    /// ```
+    pub fn with_deferred_cooldown(mut self, period: std::time::Duration) -> Self {
+        self.rate_limit = Some(RateLimit::Throttle {
+            period,
+            last_run: None,
+            defer: true,
+        });
+        self
+    }
+
+    /// Only trigger this listener for the first event after a quiet `period`. Every event that
+    /// reaches the listener restarts the period, so a continuous stream of events only triggers
+    /// the listener once, at its start. Ignored events do not run the callback, and continue to
+    /// propagate as if the listener did not exist.
+    ///
+    /// Time is measured with [`Time::elapsed`](bevy_time::Time::elapsed). If there is no
+    /// [`Time`](bevy_time::Time) resource, the listener is never debounced. This replaces any
+    /// previous [`On::throttle`].
+    pub fn debounce(mut self, period: std::time::Duration) -> Self {
+        self.rate_limit = Some(RateLimit::Debounce {
+            period,
+            last_event: None,
+        });
+        self
+    }
+
+    /// Remove this listener once `lifetime` has passed. The countdown starts the first time the
+    /// plugin runs after the listener was added, and the listener is removed before the events of
+    /// that frame are dispatched. If the listener is in a [`Listeners`] component, or in the
+    /// [`GlobalListeners`], only this listener is removed.
+    ///
+    /// Time is measured with [`Time::elapsed`](bevy_time::Time::elapsed). If there is no
+    /// [`Time`](bevy_time::Time) resource, the listener never expires.
+    pub fn expires_after(mut self, lifetime: std::time::Duration) -> Self {
+        self.expiry = Some(Expiry {
+            lifetime,
+            deadline: None,
+        });
+        self
+    }
+
+    /// Remove this listener as soon as its entity does not have a `C` component, such as the
+    /// marker of a temporary interaction. The listener is removed before the events of the frame
+    /// are dispatched, so it is never triggered once the component is gone, and it is removed
+    /// right away if the entity never had the component. If the listener is in a [`Listeners`]
+    /// component, only this listener is removed.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Pick {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Pick {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// #[derive(Component)]
+    /// struct Pickup;
+    ///
+    /// fn spawn_pickup(mut commands: Commands) {
+    ///     commands.spawn((
+    ///         Pickup,
+    ///         // Once the pickup is collected, and its marker removed, this listener goes too.
+    ///         On::<Pick>::run(|| {}).while_present::<Pickup>(),
+    ///     ));
+    /// }
+    /// ```
+    ///
+    /// Global listeners are not on any entity, so this has no effect on them.
+    pub fn while_present<C: Component>(mut self) -> Self {
+        self.companion = Some(std::any::TypeId::of::<C>());
+        self
+    }
+
+    /// Run a callback system during the capture phase, every time this event listener is
+    /// triggered.
+    ///
+    /// Events are first propagated down the hierarchy from the root to the target, running any
+    /// capture listeners along the way, before bubbling back up to the root. This allows ancestors
+    /// to intercept an event, and even stop its propagation, before any of their descendants have a
+    /// chance to handle it. Use [`ListenerInput::phase`] to check which phase the callback is being
+    /// run in.
+    pub fn capture<Marker>(callback: impl IntoSystem<(), (), Marker>) -> Self {
+        Self {
+            capture: true,
+            ..Self::run(callback)
         }
     }
 
+    /// Spawn an asynchronous task on the [`AsyncComputeTaskPool`](bevy_tasks::AsyncComputeTaskPool)
+    /// any time this event listener is triggered.
+    ///
+    /// The closure is given a clone of the [`ListenerInput`], and returns a future that will be run
+    /// in the background, without blocking the dispatch of other events. This is useful for
+    /// callbacks that perform slow work like file IO or pathfinding. When the future completes, the
+    /// [`CommandQueue`] it returns is applied to the world the next time the plugin runs.
+    ///
+    /// Because the task runs after the event has finished propagating, it cannot mutate the event
+    /// or stop its propagation.
+    pub fn run_async<F: std::future::Future<Output = CommandQueue> + Send + 'static>(
+        mut func: impl 'static + Send + Sync + FnMut(ListenerInput<E>) -> F,
+    ) -> Self {
+        Self::run(
+            move |event: Res<ListenerInput<E>>, mut tasks: ResMut<AsyncCallbackTasks>| {
+                tasks.spawn(func(event.to_owned()));
+            },
+        )
+    }
+
     /// Add a single [`Command`] any time this event listener is triggered. The command must
     /// implement `From<E>`.
     pub fn add_command<C: From<ListenerInput<E>> + Command + Send + Sync + 'static>() -> Self {
@@ -56,52 +1083,133 @@ impl<E: EntityEvent> On<E> {
     /// Get mutable access to [`Commands`] any time this event listener is triggered.
     pub fn commands_mut(
         mut func: impl 'static + Send + Sync + FnMut(&ListenerInput<E>, &mut Commands),
+    ) -> Self {
+        Self::commands_mut_with_control(move |event, commands| func(event, commands))
+    }
+
+    /// Like [`On::commands_mut`], but the closure gets mutable access to the [`ListenerInput`], so
+    /// it can stop the event's propagation. The other closure-based helpers have a
+    /// `_with_control` variant too.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// let listener = On::<Click>::commands_mut_with_control(|event, commands| {
+    ///     commands.entity(event.target()).despawn();
+    ///     event.stop_propagation();
+    /// });
+    /// ```
+    pub fn commands_mut_with_control(
+        mut func: impl 'static + Send + Sync + FnMut(&mut ListenerInput<E>, &mut Commands),
     ) -> Self {
         Self::run(
-            move |event: Res<ListenerInput<E>>, mut commands: Commands| {
-                func(&event, &mut commands);
+            move |mut event: ResMut<ListenerInput<E>>, mut commands: Commands| {
+                func(&mut event, &mut commands);
             },
         )
     }
 
     /// Get mutable access to the target entity's [`EntityCommands`] using a closure any time this
-    /// event listener is triggered.
+    /// event listener is triggered. The closure is not called if the target has been despawned,
+    /// for example by a listener that ran before this one.
     pub fn target_commands_mut(
         mut func: impl 'static + Send + Sync + FnMut(&ListenerInput<E>, &mut EntityCommands),
+    ) -> Self {
+        Self::target_commands_mut_with_control(move |event, entity| func(event, entity))
+    }
+
+    /// Like [`On::target_commands_mut`], but the closure gets mutable access to the
+    /// [`ListenerInput`], so it can stop the event's propagation.
+    pub fn target_commands_mut_with_control(
+        mut func: impl 'static + Send + Sync + FnMut(&mut ListenerInput<E>, &mut EntityCommands),
     ) -> Self {
         Self::run(
-            move |event: Res<ListenerInput<E>>, mut commands: Commands| {
-                func(&event, &mut commands.entity(event.target()));
+            move |mut event: ResMut<ListenerInput<E>>, mut commands: Commands| {
+                let target = event.target();
+                if let Some(mut entity) = commands.get_entity(target) {
+                    func(&mut event, &mut entity);
+                }
             },
         )
     }
 
-    /// Insert a bundle on the target entity any time this event listener is triggered.
+    /// Insert a bundle on the target entity any time this event listener is triggered, unless the
+    /// target has been despawned.
     pub fn target_insert(bundle: impl Bundle + Clone) -> Self {
         Self::run(
             move |event: Res<ListenerInput<E>>, mut commands: Commands| {
-                let bundle = bundle.clone();
-                commands.entity(event.target()).insert(bundle);
+                if let Some(mut entity) = commands.get_entity(event.target()) {
+                    entity.insert(bundle.clone());
+                }
             },
         )
     }
 
-    /// Remove a bundle from the target entity any time this event listener is triggered.
+    /// Remove a bundle from the target entity any time this event listener is triggered, unless
+    /// the target has been despawned.
     pub fn target_remove<B: Bundle>() -> Self {
         Self::run(|event: Res<ListenerInput<E>>, mut commands: Commands| {
-            commands.entity(event.target()).remove::<B>();
+            if let Some(mut entity) = commands.get_entity(event.target()) {
+                entity.remove::<B>();
+            }
+        })
+    }
+
+    /// Despawn the target entity and all of its descendants any time this event listener is
+    /// triggered.
+    pub fn target_despawn_recursive() -> Self {
+        Self::run(|event: Res<ListenerInput<E>>, mut commands: Commands| {
+            if let Some(entity) = commands.get_entity(event.target()) {
+                entity.despawn_recursive();
+            }
         })
     }
 
     /// Get mutable access to a specific component on the target entity using a closure any time
     /// this event listener is triggered. If the component does not exist, an error will be logged.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// #[derive(Component)]
+    /// struct Tint(f32);
+    ///
+    /// // Placed on the root of a hierarchy, this flashes whichever child was clicked.
+    /// let listener = On::<Click>::target_component_mut::<Tint>(|_event, tint| tint.0 = 1.0);
+    /// ```
     pub fn target_component_mut<C: Component>(
         mut func: impl 'static + Send + Sync + FnMut(&ListenerInput<E>, &mut C),
+    ) -> Self {
+        Self::target_component_mut_with_control(move |event, component| func(event, component))
+    }
+
+    /// Like [`On::target_component_mut`], but the closure gets mutable access to the
+    /// [`ListenerInput`], so it can stop the event's propagation.
+    pub fn target_component_mut_with_control<C: Component>(
+        mut func: impl 'static + Send + Sync + FnMut(&mut ListenerInput<E>, &mut C),
     ) -> Self {
         Self::run(
-            move |event: Res<ListenerInput<E>>, mut query: Query<&mut C>| {
+            move |mut event: ResMut<ListenerInput<E>>, mut query: Query<&mut C>| {
                 if let Ok(mut component) = query.get_mut(event.target()) {
-                    func(&event, &mut component);
+                    func(&mut event, &mut component);
                 } else {
                     #[cfg(feature = "trace")]
                     error!(
@@ -116,31 +1224,48 @@ impl<E: EntityEvent> On<E> {
     }
 
     /// Get mutable access to the listener entity's [`EntityCommands`] using a closure any time this
-    /// event listener is triggered.
+    /// event listener is triggered. The closure is not called if the listener entity has been
+    /// despawned, for example by another listener on the same entity.
     pub fn listener_commands_mut(
         mut func: impl 'static + Send + Sync + FnMut(&ListenerInput<E>, &mut EntityCommands),
+    ) -> Self {
+        Self::listener_commands_mut_with_control(move |event, entity| func(event, entity))
+    }
+
+    /// Like [`On::listener_commands_mut`], but the closure gets mutable access to the
+    /// [`ListenerInput`], so it can stop the event's propagation.
+    pub fn listener_commands_mut_with_control(
+        mut func: impl 'static + Send + Sync + FnMut(&mut ListenerInput<E>, &mut EntityCommands),
     ) -> Self {
         Self::run(
-            move |event: Res<ListenerInput<E>>, mut commands: Commands| {
-                func(&event, &mut commands.entity(event.listener()));
+            move |mut event: ResMut<ListenerInput<E>>, mut commands: Commands| {
+                let listener = event.listener();
+                if let Some(mut entity) = commands.get_entity(listener) {
+                    func(&mut event, &mut entity);
+                }
             },
         )
     }
 
-    /// Insert a bundle on the listener entity any time this event listener is triggered.
+    /// Insert a bundle on the listener entity any time this event listener is triggered, unless
+    /// the listener entity has been despawned.
     pub fn listener_insert(bundle: impl Bundle + Clone) -> Self {
         Self::run(
             move |event: Res<ListenerInput<E>>, mut commands: Commands| {
-                let bundle = bundle.clone();
-                commands.entity(event.listener()).insert(bundle);
+                if let Some(mut entity) = commands.get_entity(event.listener()) {
+                    entity.insert(bundle.clone());
+                }
             },
         )
     }
 
-    /// Remove a bundle from the listener entity any time this event listener is triggered.
+    /// Remove a bundle from the listener entity any time this event listener is triggered, unless
+    /// the listener entity has been despawned.
     pub fn listener_remove<B: Bundle>() -> Self {
         Self::run(|event: Res<ListenerInput<E>>, mut commands: Commands| {
-            commands.entity(event.listener()).remove::<B>();
+            if let Some(mut entity) = commands.get_entity(event.listener()) {
+                entity.remove::<B>();
+            }
         })
     }
 
@@ -148,11 +1273,19 @@ impl<E: EntityEvent> On<E> {
     /// this event listener is triggered. If the component does not exist, an error will be logged.
     pub fn listener_component_mut<C: Component>(
         mut func: impl 'static + Send + Sync + FnMut(&ListenerInput<E>, &mut C),
+    ) -> Self {
+        Self::listener_component_mut_with_control(move |event, component| func(event, component))
+    }
+
+    /// Like [`On::listener_component_mut`], but the closure gets mutable access to the
+    /// [`ListenerInput`], so it can stop the event's propagation.
+    pub fn listener_component_mut_with_control<C: Component>(
+        mut func: impl 'static + Send + Sync + FnMut(&mut ListenerInput<E>, &mut C),
     ) -> Self {
         Self::run(
-            move |event: Res<ListenerInput<E>>, mut query: Query<&mut C>| {
+            move |mut event: ResMut<ListenerInput<E>>, mut query: Query<&mut C>| {
                 if let Ok(mut component) = query.get_mut(event.listener()) {
-                    func(&event, &mut component);
+                    func(&mut event, &mut component);
                 } else {
                     #[cfg(feature = "trace")]
                     error!(
@@ -175,10 +1308,712 @@ impl<E: EntityEvent> On<E> {
         )
     }
 
+    /// Send the event `F` returned by the closure any time this event listener is triggered.
+    pub fn send_event_with<F: Event>(
+        func: impl 'static + Send + Sync + Fn(&ListenerInput<E>) -> F,
+    ) -> Self {
+        Self::run(
+            move |event: Res<ListenerInput<E>>, mut ev: EventWriter<F>| {
+                ev.send(func(&event));
+            },
+        )
+    }
+
+    /// Convert this event into another [`EntityEvent`] `F` any time this event listener is
+    /// triggered, and send it to be dispatched. This is useful for building high level events,
+    /// like a drag or a double click, out of lower level ones.
+    ///
+    /// The closure decides what the new event targets. Use [`ListenerInput::target`] to target the
+    /// same entity as the original event, or [`ListenerInput::listener`] to retarget it to the
+    /// entity this listener is on.
+    ///
+    /// The new event is dispatched the next time the [`EventDispatchSet<F>`](crate::EventDispatchSet)
+    /// runs. If it runs after the dispatch of `E` in the same schedule, this is the same frame.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::event_listener::{EntityEvent, On};
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// #[derive(Clone, Event)]
+    /// struct Pressed {
+    ///     button: Entity,
+    /// }
+    /// # impl EntityEvent for Pressed {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.button
+    /// #     }
+    /// # }
+    ///
+    /// // Any click on this button, or one of its children, presses the button.
+    /// let listener = On::<Click>::map_and_send(|event| Pressed {
+    ///     button: event.listener(),
+    /// });
+    /// ```
+    pub fn map_and_send<F: EntityEvent>(
+        func: impl 'static + Send + Sync + Fn(&ListenerInput<E>) -> F,
+    ) -> Self {
+        Self::send_event_with(func)
+    }
+
+    /// Create a listener with default settings that will run the `callback` when triggered.
+    pub(crate) fn from_callback(callback: Callback<E>) -> Self {
+        Self::from_callback_system(CallbackSystem::New(callback))
+    }
+
+    /// Create a listener with default settings from a callback in any state.
+    fn from_callback_system(callback: CallbackSystem<E>) -> Self {
+        Self {
+            phantom: PhantomData,
+            callback,
+            capture: false,
+            priority: 0,
+            once: false,
+            conditions: Vec::new(),
+            rate_limit: None,
+            filters: Vec::new(),
+            delegate: None,
+            group: None,
+            disabled: false,
+            target_mode: TargetMode::Any,
+            expiry: None,
+            companion: None,
+            removing: false,
+            data: ListenerData::default(),
+            registered_name: None,
+            deferred: None,
+            times_fired: 0,
+            last_fired_frame: None,
+        }
+    }
+
+    /// Returns `true` if this listener was created with [`On::expires_after`] or
+    /// [`On::while_present`].
+    fn expiring(&self) -> bool {
+        self.expiry.is_some() || self.companion.is_some()
+    }
+
+    /// Returns `true` if this listener has outlived its [`On::expires_after`] lifetime, or its
+    /// entity lost its [`On::while_present`] component. If the countdown has not started yet, it
+    /// is treated as starting `now`, see [`On::start_countdown`].
+    fn expired(
+        &self,
+        now: Option<std::time::Duration>,
+        has_companion: impl Fn(std::any::TypeId) -> bool,
+    ) -> bool {
+        if self
+            .companion
+            .is_some_and(|companion| !has_companion(companion))
+        {
+            return true;
+        }
+        let (Some(expiry), Some(now)) = (self.expiry, now) else {
+            return false;
+        };
+        now >= expiry.deadline.unwrap_or(now + expiry.lifetime)
+    }
+
+    /// Returns `true` if this listener has an [`On::expires_after`] countdown that can be started
+    /// `now`, but hasn't been yet.
+    fn countdown_pending(&self, now: Option<std::time::Duration>) -> bool {
+        now.is_some() && self.expiry.is_some_and(|expiry| expiry.deadline.is_none())
+    }
+
+    /// Start the [`On::expires_after`] countdown of this listener, if it hasn't started yet.
+    fn start_countdown(&mut self, now: std::time::Duration) {
+        if let Some(expiry) = self.expiry.as_mut() {
+            expiry.deadline.get_or_insert(now + expiry.lifetime);
+        }
+    }
+
+    /// A system that removes the listeners created with [`On::expires_after`] or
+    /// [`On::while_present`] once they have expired. This is added by the
+    /// [`EventListenerPlugin`](crate::EventListenerPlugin), and runs before events are dispatched,
+    /// in every frame where there are listeners that may expire, see [`ExpiringListeners`].
+    ///
+    /// Expired [`On<E>`] components stop being triggered right away, and are removed when the
+    /// commands are applied. Disabled listeners expire like any other. Listeners are only borrowed mutably to start their countdown or remove them, so
+    /// this doesn't mark them as changed every frame.
+    #[allow(clippy::too_many_arguments)]
+    pub fn remove_expired(
+        mut commands: Commands,
+        time: Option<Res<bevy_time::Time>>,
+        mut single: Query<(Entity, &mut On<E>)>,
+        mut lists: Query<(Entity, &mut Listeners<E>)>,
+        global: Option<ResMut<GlobalListeners<E>>>,
+        expiring: Option<ResMut<ExpiringListeners<E>>>,
+        entities: &bevy_ecs::entity::Entities,
+        archetypes: &bevy_ecs::archetype::Archetypes,
+        components: &bevy_ecs::component::Components,
+    ) {
+        let now = time.map(|time| time.elapsed());
+        let has_component = |entity: Entity, type_id: std::any::TypeId| {
+            let Some(id) = components.get_id(type_id) else {
+                return false;
+            };
+            entities
+                .get(entity)
+                .and_then(|location| archetypes.get(location.archetype_id))
+                .is_some_and(|archetype| archetype.contains(id))
+        };
+        // The listeners that may still expire, so the system keeps running while there are any.
+        let mut remaining = 0;
+        for (entity, mut on) in &mut single {
+            if on.removing || !on.expiring() {
+                continue;
+            }
+            if on.expired(now, |type_id| has_component(entity, type_id)) {
+                on.removing = true;
+                commands.entity(entity).remove::<On<E>>();
+                continue;
+            }
+            remaining += 1;
+            if let (true, Some(now)) = (on.countdown_pending(now), now) {
+                on.start_countdown(now);
+            }
+        }
+        for (entity, mut list) in &mut lists {
+            let expired = |on: &On<E>| {
+                on.expiring() && on.expired(now, |type_id| has_component(entity, type_id))
+            };
+            if list.listeners.iter().any(|(_, on)| expired(on)) {
+                list.listeners.retain(|(_, on)| !expired(on));
+            }
+            remaining += list
+                .listeners
+                .iter()
+                .filter(|(_, on)| on.expiring())
+                .count();
+            Listeners::start_countdowns(list, now);
+        }
+        if let Some(mut global) = global {
+            let expired = |on: &On<E>| on.expiry.is_some() && on.expired(now, |_| true);
+            if global.listeners.listeners.iter().any(|(_, on)| expired(on)) {
+                global.listeners.listeners.retain(|(_, on)| !expired(on));
+            }
+            let listeners = &global.listeners.listeners;
+            remaining += listeners
+                .iter()
+                .filter(|(_, on)| on.expiry.is_some())
+                .count();
+            let global = global.map_unchanged(|global| &mut global.listeners);
+            Listeners::start_countdowns(global, now);
+        }
+        if let Some(mut expiring) = expiring {
+            expiring.remaining = remaining;
+        }
+    }
+
     /// Take the boxed system callback out of this listener, leaving an empty one behind.
-    pub(crate) fn take(&mut self) -> CallbackSystem {
+    pub(crate) fn take(&mut self) -> CallbackSystem<E> {
         let mut temp = CallbackSystem::Empty;
         std::mem::swap(&mut self.callback, &mut temp);
         temp
     }
 }
+
+/// A unique identifier for a listener added to a [`Listeners`] component.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct ListenerId(u64);
+
+impl ListenerId {
+    /// Allocate a new, globally unique, [`ListenerId`].
+    pub fn new() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+impl Default for ListenerId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A list of independent event listeners for the same [`EntityEvent`] on a single entity.
+///
+/// An entity can only have a single [`On<E>`] component, so inserting a second one will replace
+/// the first. This component allows any number of listeners for the same event to coexist on an
+/// entity, which is useful when multiple plugins need to attach their own behavior without
+/// clobbering each other. Use the [`AddListener`] and [`RemoveListener`] commands to add and remove
+/// listeners without needing access to the component.
+///
+/// Listeners on an entity are triggered in order of their [priority](On::with_priority). If an
+/// entity has both an [`On<E>`] and a [`Listeners<E>`] with the same priority, the [`On<E>`] is
+/// triggered first, followed by each listener in this list, in the order they were added.
+#[derive(Component)]
+pub struct Listeners<E: EntityEvent> {
+    pub(crate) listeners: Vec<(ListenerId, On<E>)>,
+}
+
+impl<E: EntityEvent> Default for Listeners<E> {
+    fn default() -> Self {
+        Self {
+            listeners: Vec::new(),
+        }
+    }
+}
+
+impl<E: EntityEvent> Listeners<E> {
+    /// Add a listener to the end of the list, returning its [`ListenerId`].
+    pub fn push(&mut self, listener: On<E>) -> ListenerId {
+        let id = ListenerId::new();
+        self.insert(id, listener);
+        id
+    }
+
+    /// Add a listener with a preallocated [`ListenerId`] to the end of the list. If a listener with
+    /// this id already exists, it will be replaced.
+    pub fn insert(&mut self, id: ListenerId, listener: On<E>) {
+        match self.index_of(id) {
+            Some(index) => self.listeners[index].1 = listener,
+            None => self.listeners.push((id, listener)),
+        }
+    }
+
+    /// Remove the listener with the given [`ListenerId`], returning it if it existed.
+    pub fn remove(&mut self, id: ListenerId) -> Option<On<E>> {
+        let index = self.index_of(id)?;
+        Some(self.listeners.remove(index).1)
+    }
+
+    /// Get the listener with the given [`ListenerId`].
+    pub fn get(&self, id: ListenerId) -> Option<&On<E>> {
+        let index = self.index_of(id)?;
+        Some(&self.listeners[index].1)
+    }
+
+    /// Get mutable access to the listener with the given [`ListenerId`].
+    pub fn get_mut(&mut self, id: ListenerId) -> Option<&mut On<E>> {
+        let index = self.index_of(id)?;
+        Some(&mut self.listeners[index].1)
+    }
+
+    /// Does this list contain a listener with the given [`ListenerId`]?
+    pub fn contains(&self, id: ListenerId) -> bool {
+        self.index_of(id).is_some()
+    }
+
+    /// The number of listeners in this list.
+    pub fn len(&self) -> usize {
+        self.listeners.len()
+    }
+
+    /// Returns `true` if there are no listeners in this list.
+    pub fn is_empty(&self) -> bool {
+        self.listeners.is_empty()
+    }
+
+    /// The ids of the listeners in this list, in the order they were added.
+    pub fn ids(&self) -> impl Iterator<Item = ListenerId> + '_ {
+        self.listeners.iter().map(|(id, _)| *id)
+    }
+
+    pub(crate) fn index_of(&self, id: ListenerId) -> Option<usize> {
+        self.listeners
+            .iter()
+            .position(|(listener_id, _)| *listener_id == id)
+    }
+
+    /// Start the [`On::expires_after`] countdowns that haven't started yet. The list is only
+    /// borrowed mutably if there are any, so it isn't marked as changed every frame.
+    fn start_countdowns(mut list: Mut<Self>, now: Option<std::time::Duration>) {
+        let Some(now) = now else {
+            return;
+        };
+        if list
+            .listeners
+            .iter()
+            .any(|(_, on)| on.countdown_pending(Some(now)))
+        {
+            for (_, on) in &mut list.listeners {
+                on.start_countdown(now);
+            }
+        }
+    }
+}
+
+/// Event listeners that are triggered by every event of type `E`, regardless of its target.
+///
+/// Global listeners behave as if they were attached to a root entity above every hierarchy.
+/// Listeners created with [`On::capture`] are triggered before the event reaches any entity
+/// listeners, while all other listeners are triggered after the event has finished bubbling, or
+/// right after the listeners on the target for events that cannot bubble. This means that global
+/// listeners are skipped if an entity listener stops propagation.
+///
+/// While a global listener is running, [`ListenerInput::listener`] returns
+/// [`Entity::PLACEHOLDER`], because there is no listening entity.
+///
+/// Add global listeners with
+/// [`add_global_listener`](crate::EventListenerAppExt::add_global_listener), or by accessing this
+/// resource directly.
+#[derive(Resource)]
+pub struct GlobalListeners<E: EntityEvent> {
+    pub(crate) listeners: Listeners<E>,
+}
+
+impl<E: EntityEvent> Default for GlobalListeners<E> {
+    fn default() -> Self {
+        Self {
+            listeners: Listeners::default(),
+        }
+    }
+}
+
+impl<E: EntityEvent> std::ops::Deref for GlobalListeners<E> {
+    type Target = Listeners<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.listeners
+    }
+}
+
+impl<E: EntityEvent> std::ops::DerefMut for GlobalListeners<E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.listeners
+    }
+}
+
+/// A marker component that stops every event from bubbling past its entity, even if no listener
+/// stopped propagation.
+///
+/// The listeners on the entity itself are still triggered, but its ancestors are not part of the
+/// event's path, so none of their listeners are triggered, including capture listeners. This can
+/// be used to isolate a subtree from the rest of the hierarchy, such as the root of a modal dialog.
+/// [`GlobalListeners`] are not part of the hierarchy, so they are still triggered. Broadcast
+/// events are not affected.
+#[derive(Component, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct PropagationBarrier;
+
+/// A fallback callback that is run once an event has finished propagating, unless a listener
+/// called [`ListenerInput::prevent_default`].
+///
+/// This allows an event to have standard behavior that specific listeners can veto, like the
+/// default actions of DOM events. The default action is run even if propagation was stopped, and
+/// can access the final state of the event with [`Listener`](crate::callbacks::Listener) or
+/// [`ListenerMut`](crate::callbacks::ListenerMut). While it is running,
+/// [`ListenerInput::listener`] returns the target of the event.
+///
+/// Set the default action for an event with
+/// [`set_default_action`](crate::EventListenerAppExt::set_default_action).
+///
+/// The default action also runs for events that don't reach any listener, such as an event
+/// targeting an entity without listeners:
+///
+/// ```
+/// # use bevy_eventlistener_core::{
+/// #     event_listener::EntityEvent, EventListenerAppExt, EventListenerPlugin,
+/// # };
+/// # use bevy_app::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// # #[derive(Clone, Event)]
+/// # struct Submit {
+/// #     target: Entity,
+/// # }
+/// # impl EntityEvent for Submit {
+/// #     fn target(&self) -> Entity {
+/// #         self.target
+/// #     }
+/// # }
+/// # #[derive(Resource, Default)]
+/// # struct Submitted(usize);
+/// let mut app = App::new();
+/// app.add_plugins(EventListenerPlugin::<Submit>::default())
+///     .init_resource::<Submitted>()
+///     .set_default_action::<Submit, _>(|mut submitted: ResMut<Submitted>| submitted.0 += 1);
+///
+/// let form = app.world.spawn_empty().id();
+/// app.world.send_event(Submit { target: form });
+/// app.update();
+/// assert_eq!(app.world.resource::<Submitted>().0, 1);
+/// ```
+#[derive(Resource)]
+pub struct DefaultAction<E: EntityEvent> {
+    pub(crate) callback: CallbackSystem<E>,
+}
+
+impl<E: EntityEvent> DefaultAction<E> {
+    /// Create a default action that runs the `callback` system.
+    pub fn new<Marker>(callback: impl IntoSystem<(), (), Marker>) -> Self {
+        Self {
+            callback: CallbackSystem::New(Callback::Resource(Box::new(IntoSystem::into_system(
+                callback,
+            )))),
+        }
+    }
+}
+
+/// A resource used to enable and disable groups of listeners at runtime, for events of any type.
+///
+/// Listeners are added to a group with [`On::in_group`]. While a group is disabled, its listeners
+/// are skipped, and events continue to propagate as if they did not exist. Listeners are not
+/// removed from their entities, so they are triggered again as soon as their group is enabled.
+///
+/// ```
+/// # use bevy_eventlistener_core::event_listener::ListenerGroups;
+/// # use bevy_ecs::prelude::*;
+/// fn start_cutscene(mut groups: ResMut<ListenerGroups>) {
+///     groups.disable("gameplay");
+/// }
+///
+/// fn end_cutscene(mut groups: ResMut<ListenerGroups>) {
+///     groups.enable("gameplay");
+/// }
+/// ```
+#[derive(Resource, Default, Clone, Debug)]
+pub struct ListenerGroups {
+    disabled: HashSet<Cow<'static, str>>,
+}
+
+impl ListenerGroups {
+    /// Disable all listeners in the `group`.
+    pub fn disable(&mut self, group: impl Into<Cow<'static, str>>) {
+        self.disabled.insert(group.into());
+    }
+
+    /// Enable all listeners in the `group`. Groups are enabled by default.
+    pub fn enable(&mut self, group: &str) {
+        self.disabled.remove(group);
+    }
+
+    /// Returns `true` if the listeners in the `group` can be triggered.
+    pub fn is_enabled(&self, group: &str) -> bool {
+        !self.disabled.contains(group)
+    }
+
+    /// The names of all disabled groups.
+    pub fn disabled(&self) -> impl Iterator<Item = &str> + '_ {
+        self.disabled.iter().map(|group| group.as_ref())
+    }
+}
+
+/// A [`Command`] that adds an event listener to an entity's [`Listeners`], inserting the
+/// component if it does not exist yet, or to the [`GlobalListeners`].
+///
+/// The [`ListenerId`] is allocated when the command is created, so it can be stored and used to
+/// remove the listener later with [`RemoveListener`], or with the [`ListenerHandle`] returned by
+/// [`AddListener::handle`].
+pub struct AddListener<E: EntityEvent> {
+    /// The entity the listener is added to, or `None` for the [`GlobalListeners`].
+    entity: Option<Entity>,
+    id: ListenerId,
+    listener: On<E>,
+}
+
+impl<E: EntityEvent> AddListener<E> {
+    /// Create a command that will add the `listener` to the `entity`.
+    pub fn new(entity: Entity, listener: On<E>) -> Self {
+        Self {
+            entity: Some(entity),
+            id: ListenerId::new(),
+            listener,
+        }
+    }
+
+    /// Create a command that will add the `listener` to the [`GlobalListeners`].
+    pub fn global(listener: On<E>) -> Self {
+        Self {
+            entity: None,
+            id: ListenerId::new(),
+            listener,
+        }
+    }
+
+    /// The id that the listener will have once it is added.
+    pub fn id(&self) -> ListenerId {
+        self.id
+    }
+
+    /// A handle to the listener once it is added, which can be used to remove it.
+    pub fn handle(&self) -> ListenerHandle<E> {
+        ListenerHandle {
+            entity: self.entity,
+            id: self.id,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent> Command for AddListener<E> {
+    fn apply(self, world: &mut World) {
+        let Some(entity) = self.entity else {
+            world
+                .get_resource_or_insert_with(GlobalListeners::<E>::default)
+                .insert(self.id, self.listener);
+            return;
+        };
+        let Some(mut entity) = world.get_entity_mut(entity) else {
+            #[cfg(feature = "trace")]
+            error!(
+                "Could not add a listener for event {:?}, entity {:?} does not exist",
+                std::any::type_name::<E>(),
+                entity,
+            );
+            return;
+        };
+        match entity.get_mut::<Listeners<E>>() {
+            Some(mut listeners) => listeners.insert(self.id, self.listener),
+            None => {
+                let mut listeners = Listeners::default();
+                listeners.insert(self.id, self.listener);
+                entity.insert(listeners);
+            }
+        }
+    }
+}
+
+/// A [`Command`] that removes the listener with the given [`ListenerId`] from an entity's
+/// [`Listeners`], or from the [`GlobalListeners`]. Other listeners are left untouched.
+pub struct RemoveListener<E: EntityEvent> {
+    handle: ListenerHandle<E>,
+}
+
+impl<E: EntityEvent> RemoveListener<E> {
+    /// Create a command that will remove the listener with the `id` from the `entity`.
+    pub fn new(entity: Entity, id: ListenerId) -> Self {
+        Self {
+            handle: ListenerHandle::new(entity, id),
+        }
+    }
+
+    /// Create a command that will remove the listener with the `id` from the
+    /// [`GlobalListeners`].
+    pub fn global(id: ListenerId) -> Self {
+        Self {
+            handle: ListenerHandle::global(id),
+        }
+    }
+}
+
+impl<E: EntityEvent> From<ListenerHandle<E>> for RemoveListener<E> {
+    fn from(handle: ListenerHandle<E>) -> Self {
+        Self { handle }
+    }
+}
+
+impl<E: EntityEvent> Command for RemoveListener<E> {
+    fn apply(self, world: &mut World) {
+        self.handle.remove(world);
+    }
+}
+
+/// Refers to a single listener in an entity's [`Listeners`], or in the [`GlobalListeners`], so it
+/// can be removed later without touching the other listeners. Plugins can keep the handles of the
+/// listeners they added, to clean up only their own listeners.
+///
+/// Handles are returned by
+/// [`EventListenerWorldExt::add_listener`](crate::EventListenerWorldExt::add_listener) and
+/// [`EventListenerCommandsExt::add_listener`](crate::EventListenerCommandsExt::add_listener), along
+/// with their `add_global_listener` counterparts, including
+/// [`EventListenerAppExt::add_global_listener`](crate::EventListenerAppExt::add_global_listener).
+///
+/// ```
+/// # use bevy_eventlistener_core::{
+/// #     event_listener::{EntityEvent, On},
+/// #     EventListenerWorldExt,
+/// # };
+/// # use bevy_ecs::prelude::*;
+/// # #[derive(Clone, Event)]
+/// # struct Click {
+/// #     target: Entity,
+/// # }
+/// # impl EntityEvent for Click {
+/// #     fn target(&self) -> Entity {
+/// #         self.target
+/// #     }
+/// # }
+/// let mut world = World::new();
+/// let button = world.spawn_empty().id();
+/// let game = world.add_listener(button, On::<Click>::run(|| {}));
+/// let analytics = world.add_listener(button, On::<Click>::run(|| {}));
+///
+/// // Only the listener of the analytics plugin is removed.
+/// assert!(world.remove_listener(analytics).is_some());
+/// assert!(game.get(&world).is_some());
+/// assert!(analytics.get(&world).is_none());
+/// ```
+#[derive(Debug)]
+pub struct ListenerHandle<E> {
+    /// The entity the listener is on, or `None` for the [`GlobalListeners`].
+    entity: Option<Entity>,
+    id: ListenerId,
+    phantom: PhantomData<fn() -> E>,
+}
+
+impl<E> Clone for ListenerHandle<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for ListenerHandle<E> {}
+
+impl<E> PartialEq for ListenerHandle<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.entity == other.entity && self.id == other.id
+    }
+}
+
+impl<E> Eq for ListenerHandle<E> {}
+
+impl<E> std::hash::Hash for ListenerHandle<E> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.entity.hash(state);
+        self.id.hash(state);
+    }
+}
+
+impl<E: EntityEvent> ListenerHandle<E> {
+    /// A handle to the listener with the `id` in the [`Listeners`] of the `entity`.
+    pub fn new(entity: Entity, id: ListenerId) -> Self {
+        Self {
+            entity: Some(entity),
+            id,
+            phantom: PhantomData,
+        }
+    }
+
+    /// A handle to the listener with the `id` in the [`GlobalListeners`].
+    pub fn global(id: ListenerId) -> Self {
+        Self {
+            entity: None,
+            id,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The entity the listener is on, or `None` if it is a global listener.
+    pub fn entity(&self) -> Option<Entity> {
+        self.entity
+    }
+
+    /// The id of the listener.
+    pub fn id(&self) -> ListenerId {
+        self.id
+    }
+
+    /// Get the listener, if it still exists.
+    pub fn get<'w>(&self, world: &'w World) -> Option<&'w On<E>> {
+        match self.entity {
+            Some(entity) => world.get::<Listeners<E>>(entity)?.get(self.id),
+            None => world.get_resource::<GlobalListeners<E>>()?.get(self.id),
+        }
+    }
+
+    /// Remove the listener, returning it if it still existed.
+    pub fn remove(self, world: &mut World) -> Option<On<E>> {
+        match self.entity {
+            Some(entity) => world.get_mut::<Listeners<E>>(entity)?.remove(self.id),
+            None => world
+                .get_resource_mut::<GlobalListeners<E>>()?
+                .remove(self.id),
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Enums wrapping a family of related events, which are dispatched both as the enum and as the
+//! event in each variant.
+//!
+//! An [`EventFamily`] is an enum with a variant for each of its member events, usually derived
+//! with `#[derive(EventFamily)]`. The [`EventFamilyPlugin<F>`] adds an [`EventListenerPlugin`] for
+//! the family and for each of its members. When a family event is sent, it first propagates to
+//! the listeners of the enum, such as an [`On<UiEvent>`](crate::event_listener::On) that handles
+//! every kind of UI event, and then the event it wraps propagates to the listeners of the member
+//! type, such as an [`On<Click>`](crate::event_listener::On).
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     family::{EventFamily, EventFamilyPlugin, FamilyMembers, MemberSender},
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Clone, Event)]
+//! # struct Click {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! # #[derive(Clone, Event)]
+//! # struct Hover {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Hover {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! #[derive(Clone, Event)]
+//! enum UiEvent {
+//!     Click(Click),
+//!     Hover(Hover),
+//! }
+//!
+//! // This is what `#[derive(EventFamily)]` implements, along with `From` for each member.
+//! # impl EntityEvent for UiEvent {
+//! #     fn target(&self) -> Entity {
+//! #         match self {
+//! #             UiEvent::Click(click) => click.target(),
+//! #             UiEvent::Hover(hover) => hover.target(),
+//! #         }
+//! #     }
+//! # }
+//! impl EventFamily for UiEvent {
+//!     fn add_members(members: &mut FamilyMembers) {
+//!         members.add::<Click>();
+//!         members.add::<Hover>();
+//!     }
+//!
+//!     fn send_member(&self, sender: &mut MemberSender) {
+//!         match self {
+//!             UiEvent::Click(click) => sender.send(click),
+//!             UiEvent::Hover(hover) => sender.send(hover),
+//!         }
+//!     }
+//! }
+//! # #[derive(Resource, Default)]
+//! # struct Log(Vec<&'static str>);
+//!
+//! let mut app = App::new();
+//! app.add_plugins(EventFamilyPlugin::<UiEvent>::default())
+//!     .init_resource::<Log>();
+//!
+//! let button = app
+//!     .world
+//!     .spawn((
+//!         On::<UiEvent>::run(|mut log: ResMut<Log>| log.0.push("ui event")),
+//!         On::<Click>::run(|mut log: ResMut<Log>| log.0.push("click")),
+//!     ))
+//!     .id();
+//!
+//! app.world.send_event(UiEvent::Click(Click { target: button }));
+//! app.world.send_event(UiEvent::Hover(Hover { target: button }));
+//! app.update();
+//! assert_eq!(
+//!     app.world.resource::<Log>().0,
+//!     ["ui event", "ui event", "click"]
+//! );
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::{event::ManualEventReader, prelude::*, schedule::SystemConfigs};
+
+use crate::{
+    event_dispatcher::EventDispatcher, event_listener::EntityEvent, EventDispatchSet,
+    EventListenerPlugin, EventListenerSet,
+};
+
+/// An enum wrapping one of several member events, see the [module docs](self).
+///
+/// This is usually derived with `#[derive(EventFamily)]`, for an enum whose variants each wrap a
+/// single [`EntityEvent`]. The derive also implements [`EntityEvent`] for the enum, with the
+/// target and propagation of the event in each variant, and `From` for each member event.
+pub trait EventFamily: EntityEvent {
+    /// Add each member event type with [`FamilyMembers::add`].
+    fn add_members(members: &mut FamilyMembers);
+
+    /// Send the member event wrapped by this event with [`MemberSender::send`].
+    fn send_member(&self, sender: &mut MemberSender);
+}
+
+/// Adds an [`EventListenerPlugin`] with the default settings for the [`EventFamily`] `F` and for
+/// each of its members, and sends the member event of each family event once the family event has
+/// been dispatched, see the [module docs](self).
+///
+/// Member event types that already have an [`EventListenerPlugin`] keep it, so members that need
+/// other settings can be added with their own plugin before this one.
+pub struct EventFamilyPlugin<F> {
+    phantom: std::marker::PhantomData<fn() -> F>,
+}
+
+impl<F> Default for EventFamilyPlugin<F> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<F: EventFamily> Plugin for EventFamilyPlugin<F> {
+    fn build(&self, app: &mut App) {
+        add_listener_plugin::<F>(app);
+        let fan_out = fan_out::<F>
+            .in_set(EventListenerSet)
+            .after(EventDispatchSet::<F>::default());
+        let mut members = FamilyMembers {
+            app,
+            fan_out: Some(fan_out.into_configs()),
+        };
+        F::add_members(&mut members);
+        if let Some(fan_out) = members.fan_out.take() {
+            members.app.add_systems(PreUpdate, fan_out);
+        }
+    }
+}
+
+/// Add an [`EventListenerPlugin`] for events of type `E`, unless they already have one.
+fn add_listener_plugin<E: EntityEvent>(app: &mut App) {
+    if !app.world.contains_resource::<EventDispatcher<E>>() {
+        app.add_plugins(EventListenerPlugin::<E>::default());
+    }
+}
+
+/// Send the member event of each family event of type `F` that was sent since the last time this
+/// ran.
+fn fan_out<F: EventFamily>(world: &mut World, mut reader: Local<ManualEventReader<F>>) {
+    let Some(events) = world.get_resource::<Events<F>>() else {
+        return;
+    };
+    let family: Vec<F> = reader.iter(events).cloned().collect();
+    let mut sender = MemberSender { world };
+    for event in &family {
+        event.send_member(&mut sender);
+    }
+}
+
+/// Registers the member events of an [`EventFamily`], see [`EventFamily::add_members`].
+pub struct FamilyMembers<'a> {
+    app: &'a mut App,
+    /// The system sending the member events, which is ordered before each member is dispatched.
+    fan_out: Option<SystemConfigs>,
+}
+
+impl FamilyMembers<'_> {
+    /// Add the member event type `E`.
+    pub fn add<E: EntityEvent>(&mut self) {
+        add_listener_plugin::<E>(self.app);
+        self.fan_out = self
+            .fan_out
+            .take()
+            .map(|fan_out| fan_out.before(EventDispatchSet::<E>::default()));
+    }
+}
+
+/// Sends the member event of a family event, see [`EventFamily::send_member`].
+pub struct MemberSender<'w> {
+    world: &'w mut World,
+}
+
+impl MemberSender<'_> {
+    /// Send the member `event`, to be dispatched to the listeners of its own type.
+    pub fn send<E: EntityEvent>(&mut self, event: &E) {
+        self.world.send_event(event.clone());
+    }
+}
@@ -0,0 +1,412 @@
+//! Routing keyboard and gamepad input to the focused entity.
+//!
+//! The [`Focus`] resource tracks the entity that currently has input focus. The [`FocusPlugin`]
+//! sends keyboard and gamepad input as [`FocusedKeyboardInput`] and [`FocusedGamepadButton`]
+//! events targeting the focused entity, which then bubble up through its ancestors like any other
+//! [`EntityEvent`]. This means a text field can handle the keys it understands, and let the rest
+//! bubble up to the form or window containing it. When the focus changes, a [`FocusOut`] is sent
+//! to the entity that lost focus, followed by a [`FocusIn`] to the entity that gained it.
+//!
+//! Text fields and shortcuts are built on [`KeyDown`], [`KeyUp`] and [`TextInput`], which carry the
+//! [`Modifiers`] held when the input was received. [`KeyDown`] also reports whether it was sent by
+//! the key repeat of the operating system, while [`TextInput`] carries the characters typed, with
+//! the keyboard layout applied.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::Listener,
+//! #     event_listener::On,
+//! #     focus::{Focus, FocusPlugin, FocusedKeyboardInput},
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_input::{keyboard::KeyCode, InputPlugin};
+//! fn setup(mut commands: Commands, mut focus: ResMut<Focus>) {
+//!     let menu = commands
+//!         .spawn(On::<FocusedKeyboardInput>::run(
+//!             |event: Listener<FocusedKeyboardInput>| {
+//!                 if event.key_code == Some(KeyCode::Escape) {
+//!                     // close the menu
+//!                 }
+//!             },
+//!         ))
+//!         .id();
+//!     focus.set(menu);
+//! }
+//!
+//! App::new()
+//!     .add_plugins((InputPlugin, FocusPlugin))
+//!     .add_systems(Startup, setup);
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_input::{
+    gamepad::{Gamepad, GamepadButtonChangedEvent, GamepadButtonType},
+    keyboard::{KeyCode, KeyboardInput},
+    ButtonState, InputSystem,
+};
+use bevy_utils::HashSet;
+use bevy_window::ReceivedCharacter;
+
+use crate::{event_listener::EntityEvent, EventDispatchSet, EventListenerPlugin, EventListenerSet};
+
+/// Adds the [`Focus`] resource, and an [`EventListenerPlugin`] for each of the events in this
+/// module. Input events are read after the [`InputSystem`] set in the [`PreUpdate`] schedule, and
+/// dispatched in the same frame. The events from `bevy_input` and `bevy_window` must be added to
+/// the app, usually with the `InputPlugin` and `WindowPlugin`.
+///
+/// Focus changes are dispatched before input, so an entity that gains focus receives its
+/// [`FocusIn`] before any input sent to it in the same frame. The [`KeyDown`]s received in a frame
+/// are then dispatched before its [`TextInput`]s, followed by its [`KeyUp`]s.
+pub struct FocusPlugin;
+
+impl Plugin for FocusPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Focus>()
+            .add_plugins((
+                EventListenerPlugin::<FocusIn>::default(),
+                EventListenerPlugin::<FocusOut>::default(),
+                EventListenerPlugin::<FocusedKeyboardInput>::default(),
+                EventListenerPlugin::<KeyDown>::default(),
+                EventListenerPlugin::<TextInput>::default(),
+                EventListenerPlugin::<KeyUp>::default(),
+                EventListenerPlugin::<FocusedGamepadButton>::default(),
+            ))
+            .add_systems(
+                PreUpdate,
+                (
+                    send_focus_events,
+                    (send_focused_input_events, send_key_events),
+                )
+                    .chain()
+                    .after(InputSystem)
+                    .before(EventListenerSet),
+            )
+            .configure_sets(
+                PreUpdate,
+                (
+                    EventDispatchSet::<FocusOut>::default(),
+                    EventDispatchSet::<FocusIn>::default(),
+                    EventDispatchSet::<FocusedKeyboardInput>::default(),
+                    EventDispatchSet::<KeyDown>::default(),
+                    EventDispatchSet::<TextInput>::default(),
+                    EventDispatchSet::<KeyUp>::default(),
+                    EventDispatchSet::<FocusedGamepadButton>::default(),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// A resource storing the entity that has input focus, if any.
+///
+/// If the focused entity is despawned, the focus is cleared the next time input is routed.
+#[derive(Resource, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct Focus {
+    entity: Option<Entity>,
+}
+
+impl Focus {
+    /// The entity that has focus.
+    pub fn get(&self) -> Option<Entity> {
+        self.entity
+    }
+
+    /// Give focus to `entity`.
+    pub fn set(&mut self, entity: Entity) {
+        self.entity = Some(entity);
+    }
+
+    /// Remove focus from the focused entity, so input is not routed to any entity.
+    pub fn clear(&mut self) {
+        self.entity = None;
+    }
+}
+
+/// Sent to an entity when it gains focus.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct FocusIn {
+    /// The entity that gained focus.
+    pub target: Entity,
+}
+
+impl EntityEvent for FocusIn {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// Sent to an entity when it loses focus.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct FocusOut {
+    /// The entity that lost focus.
+    pub target: Entity,
+}
+
+impl EntityEvent for FocusOut {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// A [`KeyboardInput`] event, sent to the focused entity.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct FocusedKeyboardInput {
+    /// The entity that had focus when the input was received.
+    pub target: Entity,
+    /// The scan code of the key.
+    pub scan_code: u32,
+    /// The key code of the key.
+    pub key_code: Option<KeyCode>,
+    /// The press state of the key.
+    pub state: ButtonState,
+    /// The window that received the input.
+    pub window: Entity,
+}
+
+impl EntityEvent for FocusedKeyboardInput {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// The modifier keys held when a [`KeyDown`], [`KeyUp`] or [`TextInput`] was received, on either
+/// side of the keyboard.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub struct Modifiers {
+    /// Whether a shift key is held.
+    pub shift: bool,
+    /// Whether a control key is held.
+    pub control: bool,
+    /// Whether an alt key is held.
+    pub alt: bool,
+    /// Whether a super key, such as the Windows or Command key, is held.
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    /// Returns `true` if no modifier key is held.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The modifiers of the `held` keys. Each side of the keyboard is tracked separately, so
+    /// releasing one shift key doesn't clear [`Modifiers::shift`] while the other one is held.
+    fn from_held(held: &HashSet<KeyCode>) -> Self {
+        let any = |keys: [KeyCode; 2]| keys.iter().any(|key| held.contains(key));
+        Self {
+            shift: any([KeyCode::ShiftLeft, KeyCode::ShiftRight]),
+            control: any([KeyCode::ControlLeft, KeyCode::ControlRight]),
+            alt: any([KeyCode::AltLeft, KeyCode::AltRight]),
+            super_key: any([KeyCode::SuperLeft, KeyCode::SuperRight]),
+        }
+    }
+}
+
+/// Sent to the focused entity when a key is pressed, see the [module docs](self).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct KeyDown {
+    /// The entity that had focus when the key was pressed.
+    pub target: Entity,
+    /// The scan code of the key.
+    pub scan_code: u32,
+    /// The key code of the key.
+    pub key_code: Option<KeyCode>,
+    /// The modifier keys held, including this key if it is a modifier.
+    pub modifiers: Modifiers,
+    /// Whether this press was sent by the key repeat of the operating system, while the key was
+    /// already held.
+    pub repeat: bool,
+    /// The window that received the input.
+    pub window: Entity,
+}
+
+impl EntityEvent for KeyDown {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// Sent to the focused entity when a key is released, see the [module docs](self).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct KeyUp {
+    /// The entity that had focus when the key was released.
+    pub target: Entity,
+    /// The scan code of the key.
+    pub scan_code: u32,
+    /// The key code of the key.
+    pub key_code: Option<KeyCode>,
+    /// The modifier keys still held.
+    pub modifiers: Modifiers,
+    /// The window that received the input.
+    pub window: Entity,
+}
+
+impl EntityEvent for KeyUp {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// Sent to the focused entity when a character is typed, see the [module docs](self).
+///
+/// Control characters, such as backspace or enter, are not sent as text, and should be handled
+/// with [`KeyDown`] instead.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct TextInput {
+    /// The entity that had focus when the character was typed.
+    pub target: Entity,
+    /// The character typed.
+    pub character: char,
+    /// The modifier keys held at the end of the frame the character was typed in.
+    pub modifiers: Modifiers,
+    /// The window that received the input.
+    pub window: Entity,
+}
+
+impl EntityEvent for TextInput {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// A [`GamepadButtonChangedEvent`], sent to the focused entity.
+#[derive(Clone, Copy, PartialEq, Debug, Event)]
+pub struct FocusedGamepadButton {
+    /// The entity that had focus when the input was received.
+    pub target: Entity,
+    /// The gamepad the button belongs to.
+    pub gamepad: Gamepad,
+    /// The button that changed.
+    pub button_type: GamepadButtonType,
+    /// The new value of the button, between `0.0` and `1.0`.
+    pub value: f32,
+}
+
+impl EntityEvent for FocusedGamepadButton {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// Send a [`FocusOut`] and [`FocusIn`] when the [`Focus`] changes. Clears the focus if the focused
+/// entity no longer exists.
+pub fn send_focus_events(
+    mut focus: ResMut<Focus>,
+    entities: Query<()>,
+    mut previous: Local<Option<Entity>>,
+    mut focus_in: EventWriter<FocusIn>,
+    mut focus_out: EventWriter<FocusOut>,
+) {
+    if focus
+        .entity
+        .is_some_and(|entity| !entities.contains(entity))
+    {
+        focus.clear();
+    }
+    if *previous == focus.entity {
+        return;
+    }
+    if let Some(target) = previous.filter(|entity| entities.contains(*entity)) {
+        focus_out.send(FocusOut { target });
+    }
+    if let Some(target) = focus.entity {
+        focus_in.send(FocusIn { target });
+    }
+    *previous = focus.entity;
+}
+
+/// Send keyboard and gamepad input to the focused entity. Input received while no entity has focus
+/// is ignored.
+pub fn send_focused_input_events(
+    focus: Res<Focus>,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut gamepad: EventReader<GamepadButtonChangedEvent>,
+    mut focused_keyboard: EventWriter<FocusedKeyboardInput>,
+    mut focused_gamepad: EventWriter<FocusedGamepadButton>,
+) {
+    let Some(target) = focus.entity else {
+        keyboard.clear();
+        gamepad.clear();
+        return;
+    };
+    focused_keyboard.send_batch(keyboard.iter().map(|input| FocusedKeyboardInput {
+        target,
+        scan_code: input.scan_code,
+        key_code: input.key_code,
+        state: input.state,
+        window: input.window,
+    }));
+    focused_gamepad.send_batch(gamepad.iter().map(|input| FocusedGamepadButton {
+        target,
+        gamepad: input.gamepad,
+        button_type: input.button_type,
+        value: input.value,
+    }));
+}
+
+/// Send [`KeyDown`], [`KeyUp`] and [`TextInput`] events to the focused entity. The held keys and
+/// modifiers are tracked even while no entity has focus, so the state is correct when an entity
+/// gains it.
+#[allow(clippy::too_many_arguments)]
+pub fn send_key_events(
+    focus: Res<Focus>,
+    mut keyboard: EventReader<KeyboardInput>,
+    mut characters: EventReader<ReceivedCharacter>,
+    mut held: Local<HashSet<u32>>,
+    mut held_keys: Local<HashSet<KeyCode>>,
+    mut key_down: EventWriter<KeyDown>,
+    mut key_up: EventWriter<KeyUp>,
+    mut text_input: EventWriter<TextInput>,
+) {
+    for input in keyboard.iter() {
+        if let Some(key_code) = input.key_code {
+            match input.state {
+                ButtonState::Pressed => held_keys.insert(key_code),
+                ButtonState::Released => held_keys.remove(&key_code),
+            };
+        }
+        let modifiers = Modifiers::from_held(&held_keys);
+        let repeat = match input.state {
+            ButtonState::Pressed => !held.insert(input.scan_code),
+            ButtonState::Released => !held.remove(&input.scan_code),
+        };
+        let Some(target) = focus.entity else {
+            continue;
+        };
+        match input.state {
+            ButtonState::Pressed => key_down.send(KeyDown {
+                target,
+                scan_code: input.scan_code,
+                key_code: input.key_code,
+                modifiers,
+                repeat,
+                window: input.window,
+            }),
+            ButtonState::Released => key_up.send(KeyUp {
+                target,
+                scan_code: input.scan_code,
+                key_code: input.key_code,
+                modifiers,
+                window: input.window,
+            }),
+        }
+    }
+    let Some(target) = focus.entity else {
+        characters.clear();
+        return;
+    };
+    let modifiers = Modifiers::from_held(&held_keys);
+    text_input.send_batch(
+        characters
+            .iter()
+            .filter(|received| !received.char.is_control())
+            .map(|received| TextInput {
+                target,
+                character: received.char,
+                modifiers,
+                window: received.window,
+            }),
+    );
+}
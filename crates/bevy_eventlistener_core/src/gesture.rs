@@ -0,0 +1,221 @@
+//! Double and triple click gestures, recognized from a stream of click events.
+//!
+//! The [`MultiClickPlugin<E>`] watches the click events of type `E`. When the same entity is
+//! clicked more than once in quick succession, without the pointer moving too far between clicks,
+//! it sends a [`DoubleClick<E>`] and then a [`TripleClick<E>`] targeting that entity. These bubble
+//! like any other [`EntityEvent`]. The original click events are still dispatched as usual.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     gesture::{ClickEvent, DoubleClick, MultiClickPlugin},
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_math::Vec2;
+//! # use std::time::Duration;
+//! #[derive(Clone, Event)]
+//! struct Click {
+//!     target: Entity,
+//!     position: Vec2,
+//! }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//!
+//! impl ClickEvent for Click {
+//!     fn position(&self) -> Option<Vec2> {
+//!         Some(self.position)
+//!     }
+//! }
+//!
+//! App::new().add_plugins((
+//!     EventListenerPlugin::<Click>::default(),
+//!     MultiClickPlugin::<Click>::default().with_window(Duration::from_millis(300)),
+//! ));
+//! ```
+
+use std::time::Duration;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
+use bevy_time::Time;
+use bevy_utils::HashMap;
+
+use crate::{event_listener::EntityEvent, EventListenerPlugin, EventListenerSet};
+
+/// A click-style event that can be recognized as part of a multi-click gesture.
+pub trait ClickEvent: EntityEvent {
+    /// The position of the pointer when the click happened. Events that return `None` are never
+    /// rejected for moving too far between clicks.
+    fn position(&self) -> Option<Vec2> {
+        None
+    }
+}
+
+/// Sends a [`DoubleClick<E>`] and [`TripleClick<E>`] when an entity is clicked multiple times in
+/// quick succession. Adds an [`EventListenerPlugin`] for both.
+///
+/// The click events of type `E` are read in the [`PreUpdate`] schedule, after the
+/// [`EventListenerSet`], so clicks are dispatched before the gestures they complete. Gestures are
+/// then dispatched at the start of the next frame. This requires the [`Time`] resource.
+pub struct MultiClickPlugin<E> {
+    settings: MultiClickSettings<E>,
+}
+
+impl<E> Default for MultiClickPlugin<E> {
+    fn default() -> Self {
+        Self {
+            settings: MultiClickSettings::default(),
+        }
+    }
+}
+
+impl<E> MultiClickPlugin<E> {
+    /// Set the maximum time between two clicks of the same gesture. Defaults to 500 ms.
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.settings.window = window;
+        self
+    }
+
+    /// Set the maximum distance the pointer can move between two clicks of the same gesture, see
+    /// [`ClickEvent::position`]. Defaults to 4.0.
+    pub fn with_tolerance(mut self, tolerance: f32) -> Self {
+        self.settings.tolerance = tolerance;
+        self
+    }
+}
+
+impl<E: ClickEvent> Plugin for MultiClickPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.settings.clone())
+            .add_plugins((
+                EventListenerPlugin::<DoubleClick<E>>::default(),
+                EventListenerPlugin::<TripleClick<E>>::default(),
+            ))
+            .add_systems(
+                PreUpdate,
+                recognize_multi_clicks::<E>.after(EventListenerSet),
+            );
+    }
+}
+
+/// A resource storing the settings used to recognize multi-click gestures of the click event `E`.
+/// This can be changed at runtime.
+#[derive(Resource)]
+pub struct MultiClickSettings<E> {
+    /// The maximum time between two clicks of the same gesture.
+    pub window: Duration,
+    /// The maximum distance the pointer can move between two clicks of the same gesture.
+    pub tolerance: f32,
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E> Default for MultiClickSettings<E> {
+    fn default() -> Self {
+        Self {
+            window: Duration::from_millis(500),
+            tolerance: 4.0,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E> Clone for MultiClickSettings<E> {
+    fn clone(&self) -> Self {
+        Self {
+            window: self.window,
+            tolerance: self.tolerance,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Sent when an entity is clicked twice in quick succession.
+#[derive(Clone, Event)]
+pub struct DoubleClick<E: ClickEvent> {
+    /// The entity that was clicked.
+    pub target: Entity,
+    /// The click that completed the gesture.
+    pub click: E,
+}
+
+impl<E: ClickEvent> EntityEvent for DoubleClick<E> {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// Sent when an entity is clicked three times in quick succession, after the [`DoubleClick<E>`].
+/// The next click on the entity starts a new gesture.
+#[derive(Clone, Event)]
+pub struct TripleClick<E: ClickEvent> {
+    /// The entity that was clicked.
+    pub target: Entity,
+    /// The click that completed the gesture.
+    pub click: E,
+}
+
+impl<E: ClickEvent> EntityEvent for TripleClick<E> {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+/// The state of a multi-click gesture on a single entity.
+pub struct ClickSequence {
+    count: usize,
+    last_click: Duration,
+    position: Option<Vec2>,
+}
+
+/// Count the clicks on each entity, sending a [`DoubleClick<E>`] and [`TripleClick<E>`] when a
+/// gesture is recognized. Gestures that have not been continued within the window are forgotten.
+pub fn recognize_multi_clicks<E: ClickEvent>(
+    mut clicks: EventReader<E>,
+    settings: Res<MultiClickSettings<E>>,
+    time: Res<Time>,
+    mut sequences: Local<HashMap<Entity, ClickSequence>>,
+    mut double_click: EventWriter<DoubleClick<E>>,
+    mut triple_click: EventWriter<TripleClick<E>>,
+) {
+    let now = time.elapsed();
+    sequences.retain(|_, sequence| now.saturating_sub(sequence.last_click) <= settings.window);
+    for click in clicks.iter() {
+        let target = click.target();
+        let position = click.position();
+        let sequence = sequences.entry(target).or_insert(ClickSequence {
+            count: 0,
+            last_click: now,
+            position,
+        });
+        let moved = match (sequence.position, position) {
+            (Some(last), Some(position)) => last.distance(position) > settings.tolerance,
+            _ => false,
+        };
+        if moved || now.saturating_sub(sequence.last_click) > settings.window {
+            sequence.count = 0;
+        }
+        sequence.count += 1;
+        sequence.last_click = now;
+        sequence.position = position;
+        match sequence.count {
+            2 => double_click.send(DoubleClick {
+                target,
+                click: click.clone(),
+            }),
+            3 => {
+                triple_click.send(TripleClick {
+                    target,
+                    click: click.clone(),
+                });
+                sequences.remove(&target);
+            }
+            _ => {}
+        }
+    }
+}
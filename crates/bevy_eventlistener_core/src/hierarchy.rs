@@ -0,0 +1,140 @@
+//! Events sent when the entity hierarchy changes.
+//!
+//! The [`HierarchyEventsPlugin`] converts bevy's [`HierarchyEvent`] into [`ChildAdded`] and
+//! [`ChildRemoved`] events targeting the parent, and [`ParentChanged`] events targeting the child.
+//! These are dispatched through listeners like any other [`EntityEvent`], so layout and container
+//! widgets can react to structural changes the same way they react to clicks. [`ChildAdded`] and
+//! [`ChildRemoved`] bubble up from the parent, so a container also sees changes deeper in its
+//! hierarchy, while [`ParentChanged`] does not bubble.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::Listener,
+//! #     event_listener::On,
+//! #     hierarchy::{ChildAdded, HierarchyEventsPlugin},
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_hierarchy::BuildWorldChildren;
+//! let mut app = App::new();
+//! app.add_plugins(HierarchyEventsPlugin);
+//!
+//! let list = app
+//!     .world
+//!     .spawn(On::<ChildAdded>::run(|added: Listener<ChildAdded>| {
+//!         println!("{:?} was added to {:?}", added.child, added.parent);
+//!     }))
+//!     .id();
+//! app.world.spawn_empty().set_parent(list);
+//! app.update();
+//! ```
+//!
+//! Bevy only sends a [`HierarchyEvent`] when a child is added, removed or moved with the hierarchy
+//! commands and methods, such as `set_parent` or `remove_children`. Despawning a child does not
+//! send an event.
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::HierarchyEvent;
+
+use crate::{event_listener::EntityEvent, EventListenerPlugin, EventListenerSet};
+
+/// Sent when a child is added to a parent, targeting the parent, see the [module docs](self).
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChildAdded {
+    /// The parent the child was added to.
+    pub parent: Entity,
+    /// The child that was added.
+    pub child: Entity,
+}
+
+impl EntityEvent for ChildAdded {
+    fn target(&self) -> Entity {
+        self.parent
+    }
+}
+
+/// Sent when a child is removed from a parent, targeting the parent, see the
+/// [module docs](self).
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ChildRemoved {
+    /// The parent the child was removed from.
+    pub parent: Entity,
+    /// The child that was removed.
+    pub child: Entity,
+}
+
+impl EntityEvent for ChildRemoved {
+    fn target(&self) -> Entity {
+        self.parent
+    }
+}
+
+/// Sent when the parent of an entity changes, targeting the child, see the [module docs](self).
+/// This event is [target only](EventListenerPlugin::target_only), it does not bubble.
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ParentChanged {
+    /// The entity whose parent changed.
+    pub child: Entity,
+    /// The previous parent of the entity, if it had one.
+    pub previous_parent: Option<Entity>,
+    /// The new parent of the entity, if it has one.
+    pub new_parent: Option<Entity>,
+}
+
+impl EntityEvent for ParentChanged {
+    fn target(&self) -> Entity {
+        self.child
+    }
+}
+
+/// Sends the [`ChildAdded`], [`ChildRemoved`] and [`ParentChanged`] events, and adds an
+/// [`EventListenerPlugin`] for each of them, see the [module docs](self).
+///
+/// The events are sent in the [`PreUpdate`] schedule, right before the [`EventListenerSet`], so
+/// they are dispatched in the same frame as the [`HierarchyEvent`] they are converted from, if it
+/// was sent before then.
+pub struct HierarchyEventsPlugin;
+
+impl Plugin for HierarchyEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HierarchyEvent>()
+            .add_plugins((
+                EventListenerPlugin::<ChildAdded>::default(),
+                EventListenerPlugin::<ChildRemoved>::default(),
+                EventListenerPlugin::<ParentChanged>::default().target_only(),
+            ))
+            .add_systems(PreUpdate, send_hierarchy_events.before(EventListenerSet));
+    }
+}
+
+/// Convert the [`HierarchyEvent`]s sent since this system last ran.
+fn send_hierarchy_events(
+    mut hierarchy_events: EventReader<HierarchyEvent>,
+    mut added: EventWriter<ChildAdded>,
+    mut removed: EventWriter<ChildRemoved>,
+    mut parent_changed: EventWriter<ParentChanged>,
+) {
+    for event in hierarchy_events.iter() {
+        let (child, previous_parent, new_parent) = match *event {
+            HierarchyEvent::ChildAdded { child, parent } => (child, None, Some(parent)),
+            HierarchyEvent::ChildRemoved { child, parent } => (child, Some(parent), None),
+            HierarchyEvent::ChildMoved {
+                child,
+                previous_parent,
+                new_parent,
+            } => (child, Some(previous_parent), Some(new_parent)),
+        };
+        if let Some(parent) = previous_parent {
+            removed.send(ChildRemoved { parent, child });
+        }
+        if let Some(parent) = new_parent {
+            added.send(ChildAdded { parent, child });
+        }
+        parent_changed.send(ParentChanged {
+            child,
+            previous_parent,
+            new_parent,
+        });
+    }
+}
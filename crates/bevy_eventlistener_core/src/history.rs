@@ -0,0 +1,144 @@
+//! A bounded history of the events that were dispatched, for debugging tools.
+//!
+//! While the [`EventHistory<E>`] resource exists, the dispatcher records every event of type `E`
+//! once it has finished propagating, along with its target, its path, the listeners it triggered,
+//! and whether its propagation was stopped. Only the most recent events are kept. This can be used
+//! to build an in-game event inspector, or to check how an event propagated after the fact.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     history::EventHistory,
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Clone, Event)]
+//! # struct Click {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! let mut app = App::new();
+//! app.add_plugins(EventListenerPlugin::<Click>::default().with_history(16));
+//!
+//! let button = app.world.spawn(On::<Click>::run(|| {})).id();
+//! app.world.send_event(Click { target: button });
+//! app.update();
+//!
+//! let history = app.world.resource::<EventHistory<Click>>();
+//! let entry = history.latest().unwrap();
+//! assert_eq!(entry.target, button);
+//! assert_eq!(entry.listeners.len(), 1);
+//! assert!(!entry.propagation_stopped);
+//! ```
+
+use std::collections::VecDeque;
+
+use bevy_ecs::prelude::*;
+
+use crate::{callbacks::EventPhase, event_listener::EntityEvent};
+
+/// A listener that was triggered by an event, recorded in the [`EventHistory`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct TriggeredListener {
+    /// The entity the listener is on, or [`Entity::PLACEHOLDER`] for global listeners.
+    pub listener: Entity,
+    /// The phase of propagation the listener was triggered in.
+    pub phase: EventPhase,
+    /// Did this listener stop the event's propagation?
+    pub stopped_propagation: bool,
+}
+
+/// An event recorded in the [`EventHistory`] once it finished propagating.
+#[derive(Clone, Debug)]
+pub struct HistoryEntry<E> {
+    /// The final state of the event data, after any changes made by listeners.
+    pub event: E,
+    /// The target the event propagated from. Events with multiple
+    /// [`targets`](EntityEvent::targets) are recorded once for each target.
+    pub target: Entity,
+    /// The entities with listeners the event visited, see
+    /// [`ListenerInput::path`](crate::callbacks::ListenerInput::path). For broadcast events, this
+    /// only contains the target.
+    pub path: Vec<Entity>,
+    /// The listeners triggered by the event, in the order they were triggered.
+    pub listeners: Vec<TriggeredListener>,
+    /// Was the event's propagation stopped by a listener?
+    pub propagation_stopped: bool,
+    /// Was the event's default action prevented by a listener?
+    pub default_prevented: bool,
+}
+
+/// A resource storing the most recent events of type `E` that were dispatched, see the
+/// [module docs](self). Add it with
+/// [`EventListenerPlugin::with_history`](crate::EventListenerPlugin::with_history), or insert it
+/// at runtime. Removing the resource stops events from being recorded.
+#[derive(Resource, Debug)]
+pub struct EventHistory<E: EntityEvent> {
+    entries: VecDeque<HistoryEntry<E>>,
+    capacity: usize,
+}
+
+impl<E: EntityEvent> EventHistory<E> {
+    /// Create a history that keeps the last `capacity` events.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// The maximum number of events kept.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Change the maximum number of events kept, discarding the oldest events if there are too
+    /// many.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        while self.entries.len() > capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// The recorded events, from oldest to newest.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &HistoryEntry<E>> + '_ {
+        self.entries.iter()
+    }
+
+    /// The most recently recorded event.
+    pub fn latest(&self) -> Option<&HistoryEntry<E>> {
+        self.entries.back()
+    }
+
+    /// The number of events recorded.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if no events are recorded.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Discard every recorded event.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Record an event, discarding the oldest event if the history is full.
+    pub(crate) fn push(&mut self, entry: HistoryEntry<E>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+}
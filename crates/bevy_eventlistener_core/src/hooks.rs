@@ -0,0 +1,129 @@
+//! Hooks that see every event of a type before and after it propagates.
+//!
+//! The [`EventHooks<E>`] resource stores functions that are run for every event of type `E`,
+//! regardless of which listeners it reaches. Hooks run before propagation can change the event,
+//! including its target, or cancel it, before its path is resolved and before any listener is
+//! triggered. This is a single place to implement things like input remapping. Hooks run after
+//! propagation see the final state of each event, which can be used for auditing.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     hooks::EventHooks,
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Clone, Event)]
+//! # struct Click {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! # #[derive(Resource, Default)]
+//! # struct Clicks(usize);
+//! let mut app = App::new();
+//! app.add_plugins(EventListenerPlugin::<Click>::default())
+//!     .init_resource::<Clicks>();
+//! let button = app
+//!     .world
+//!     .spawn(On::<Click>::run(|mut clicks: ResMut<Clicks>| clicks.0 += 1))
+//!     .id();
+//! let label = app.world.spawn_empty().id();
+//!
+//! app.world
+//!     .resource_mut::<EventHooks<Click>>()
+//!     // Clicks on the label are sent to the button instead.
+//!     .before_propagation(move |click| {
+//!         if click.target == label {
+//!             click.target = button;
+//!         }
+//!         true
+//!     })
+//!     .after_propagation(|_world, input| {
+//!         println!("{:?} was clicked", input.target());
+//!     });
+//!
+//! app.world.send_event(Click { target: label });
+//! app.update();
+//! assert_eq!(app.world.resource::<Clicks>().0, 1);
+//! ```
+
+use bevy_ecs::prelude::*;
+
+use crate::{callbacks::ListenerInput, event_listener::EntityEvent};
+
+/// A hook run before an event propagates, which returns `false` to cancel the event.
+type BeforeHook<E> = Box<dyn FnMut(&mut E) -> bool + Send + Sync>;
+/// A hook run once an event has finished propagating.
+type AfterHook<E> = Box<dyn FnMut(&mut World, &ListenerInput<E>) + Send + Sync>;
+
+/// A resource storing the hooks run for every event of type `E`, see the [module docs](self).
+/// This is added by the [`EventListenerPlugin`](crate::EventListenerPlugin).
+#[derive(Resource)]
+pub struct EventHooks<E: EntityEvent> {
+    before: Vec<BeforeHook<E>>,
+    after: Vec<AfterHook<E>>,
+}
+
+impl<E: EntityEvent> Default for EventHooks<E> {
+    fn default() -> Self {
+        Self {
+            before: Vec::new(),
+            after: Vec::new(),
+        }
+    }
+}
+
+impl<E: EntityEvent> EventHooks<E> {
+    /// Add a hook that is run for every event before it propagates, in the order the hooks were
+    /// added. The hook can change the event, including its targets. If it returns `false`, the
+    /// event is cancelled, and the remaining hooks are not run.
+    ///
+    /// Hooks are run when the event's path is resolved, so they see the events sent in the frame
+    /// they are dispatched in, before any listener is triggered.
+    pub fn before_propagation(
+        &mut self,
+        hook: impl FnMut(&mut E) -> bool + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.before.push(Box::new(hook));
+        self
+    }
+
+    /// Add a hook that is run for every event once it has finished propagating, after its
+    /// [`DefaultAction`](crate::event_listener::DefaultAction), in the order the hooks were added.
+    /// Events with multiple [`targets`](EntityEvent::targets) are seen once for each target.
+    /// Events that were lost during propagation are not seen.
+    pub fn after_propagation(
+        &mut self,
+        hook: impl FnMut(&mut World, &ListenerInput<E>) + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.after.push(Box::new(hook));
+        self
+    }
+
+    /// Returns `true` if there are hooks run before events propagate.
+    pub(crate) fn has_before(&self) -> bool {
+        !self.before.is_empty()
+    }
+
+    /// Returns `true` if there are hooks run after events propagate.
+    pub(crate) fn has_after(&self) -> bool {
+        !self.after.is_empty()
+    }
+
+    /// Run the hooks before an event propagates. Returns `false` if the event was cancelled.
+    pub(crate) fn run_before(&mut self, event: &mut E) -> bool {
+        self.before.iter_mut().all(|hook| hook(event))
+    }
+
+    /// Run the hooks once an event has finished propagating.
+    pub(crate) fn run_after(&mut self, world: &mut World, input: &ListenerInput<E>) {
+        for hook in &mut self.after {
+            hook(world, input);
+        }
+    }
+}
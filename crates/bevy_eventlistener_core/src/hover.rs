@@ -0,0 +1,196 @@
+//! Enter and leave events, synthesized from a stream of raw hover events.
+//!
+//! Picking backends usually only report the topmost entity under each pointer. The
+//! [`HoverPlugin`] reads these reports as [`PointerOver`] events, and sends a [`PointerEnter`] to
+//! every entity the pointer has newly entered, and a [`PointerLeave`] to every entity it has left,
+//! with the same semantics as `mouseenter` and `mouseleave` in the DOM. A pointer is considered to
+//! be over an entity while it is over that entity or any of its descendants, so moving the pointer
+//! from a child to its parent only sends a [`PointerLeave`] to the child.
+//!
+//! Neither event bubbles. Instead, an event is sent to each entity that was entered or left, up to,
+//! but not including, the closest common ancestor of the previous and new hovered entities.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::Listener,
+//! #     event_listener::On,
+//! #     hover::{HoverPlugin, PointerEnter, PointerOver},
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_hierarchy::BuildWorldChildren;
+//! # #[derive(Resource, Default)]
+//! # struct Entered(Vec<Entity>);
+//! let mut app = App::new();
+//! app.add_plugins(HoverPlugin::new())
+//!     .init_resource::<Entered>();
+//!
+//! let record = |event: Listener<PointerEnter>, mut entered: ResMut<Entered>| {
+//!     entered.0.push(event.target);
+//! };
+//! let parent = app.world.spawn(On::<PointerEnter>::run(record)).id();
+//! let child = app.world.spawn(On::<PointerEnter>::run(record)).set_parent(parent).id();
+//!
+//! app.world.send_event(PointerOver { pointer: 0, entity: Some(child) });
+//! app.update();
+//! // The parent is entered before the child.
+//! assert_eq!(app.world.resource::<Entered>().0, [parent, child]);
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::Parent;
+use bevy_utils::HashMap;
+
+use crate::{
+    event_listener::EntityEvent, traversal::Traversal, EventDispatchSet, EventListenerPlugin,
+    EventListenerSet,
+};
+
+/// Sends [`PointerEnter`] and [`PointerLeave`] events from the [`PointerOver`] events sent by the
+/// app. The hierarchy of entities is traversed through the [`Traversal`] component `T`, which
+/// defaults to [`Parent`].
+///
+/// [`PointerOver`] events should be sent in the [`PreUpdate`] schedule, before the
+/// [`EventListenerSet`]. The enter and leave events are dispatched in the same frame, with every
+/// [`PointerLeave`] being dispatched before any [`PointerEnter`].
+pub struct HoverPlugin<T = Parent> {
+    phantom: std::marker::PhantomData<fn() -> T>,
+}
+
+impl<T> Default for HoverPlugin<T> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl HoverPlugin {
+    /// Create a plugin that traverses the [`Parent`] hierarchy.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<T: Traversal> Plugin for HoverPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.add_event::<PointerOver>()
+            .add_plugins((
+                EventListenerPlugin::<PointerEnter, T>::default(),
+                EventListenerPlugin::<PointerLeave, T>::default(),
+            ))
+            .add_systems(
+                PreUpdate,
+                send_enter_leave_events::<T>.before(EventListenerSet),
+            )
+            .configure_sets(
+                PreUpdate,
+                (
+                    EventDispatchSet::<PointerLeave>::default(),
+                    EventDispatchSet::<PointerEnter>::default(),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Reports that a pointer is over an entity, usually sent by a picking backend. The entity should
+/// be the topmost entity under the pointer, or `None` if the pointer is not over any entity.
+///
+/// The pointer is assumed to stay over the same entity until the next [`PointerOver`] event for the
+/// same pointer is sent, so these only need to be sent when the hovered entity changes.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct PointerOver {
+    /// Identifies the pointer, so the hover state of multiple pointers can be tracked
+    /// independently.
+    pub pointer: u64,
+    /// The entity the pointer is over.
+    pub entity: Option<Entity>,
+}
+
+/// Sent to an entity when a pointer moves over it or one of its descendants, after it was not over
+/// either. This event does not bubble.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct PointerEnter {
+    /// The entity that was entered.
+    pub target: Entity,
+    /// The pointer that entered the entity.
+    pub pointer: u64,
+}
+
+impl EntityEvent for PointerEnter {
+    fn target(&self) -> Entity {
+        self.target
+    }
+
+    fn can_bubble(&self) -> bool {
+        false
+    }
+}
+
+/// Sent to an entity when a pointer is no longer over it or any of its descendants. This event
+/// does not bubble.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+pub struct PointerLeave {
+    /// The entity that was left.
+    pub target: Entity,
+    /// The pointer that left the entity.
+    pub pointer: u64,
+}
+
+impl EntityEvent for PointerLeave {
+    fn target(&self) -> Entity {
+        self.target
+    }
+
+    fn can_bubble(&self) -> bool {
+        false
+    }
+}
+
+/// Convert [`PointerOver`] events into [`PointerEnter`] and [`PointerLeave`] events.
+///
+/// For each pointer, the chain of entities from the hovered entity up to the root of its hierarchy
+/// is stored. When the pointer moves over another entity, a [`PointerLeave`] is sent to each entity
+/// that is only in the old chain, starting with the previously hovered entity, then a
+/// [`PointerEnter`] is sent to each entity that is only in the new chain, ending with the newly
+/// hovered entity.
+pub fn send_enter_leave_events<T: Traversal>(
+    mut over: EventReader<PointerOver>,
+    hierarchy: Query<&T>,
+    mut hovered: Local<HashMap<u64, Vec<Entity>>>,
+    mut chain: Local<Vec<Entity>>,
+    mut enter: EventWriter<PointerEnter>,
+    mut leave: EventWriter<PointerLeave>,
+) {
+    for PointerOver { pointer, entity } in over.iter().copied() {
+        chain.clear();
+        let mut next = entity;
+        while let Some(entity) = next {
+            chain.push(entity);
+            next = hierarchy.get(entity).ok().and_then(Traversal::next);
+        }
+
+        let previous = hovered.entry(pointer).or_default();
+        // Both chains end at the root, so the entities they share are at the end of both.
+        let shared = previous
+            .iter()
+            .rev()
+            .zip(chain.iter().rev())
+            .take_while(|(a, b)| a == b)
+            .count();
+        leave.send_batch(
+            previous[..previous.len() - shared]
+                .iter()
+                .map(|&target| PointerLeave { target, pointer }),
+        );
+        enter.send_batch(
+            chain[..chain.len() - shared]
+                .iter()
+                .rev()
+                .map(|&target| PointerEnter { target, pointer }),
+        );
+        std::mem::swap(previous, &mut chain);
+    }
+}
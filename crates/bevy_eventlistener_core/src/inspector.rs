@@ -0,0 +1,263 @@
+//! An `egui` panel for inspecting events and listeners while the app is running.
+//!
+//! The [`EventInspectorPlugin`] shows an "Event Inspector" window listing every inspected event
+//! type, along with the number of events dispatched in the last frame, and the propagation paths
+//! of the most recent events, recorded with an [`EventHistory`]. Clicking an entity in a path
+//! selects it, and lists the listeners attached to it, with a toggle to enable or disable each of
+//! them. This makes it possible to debug how events are wired visually, without adding logging.
+//!
+//! ```no_run
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::EntityEvent,
+//! #     inspector::EventInspectorPlugin,
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_egui::EguiPlugin;
+//! # #[derive(Clone, Event)]
+//! # struct Click {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! App::new().add_plugins((
+//!     EguiPlugin,
+//!     EventListenerPlugin::<Click>::default(),
+//!     EventInspectorPlugin::default().inspect::<Click>(),
+//! ));
+//! ```
+//!
+//! The window is drawn on the primary window, and needs the `EguiPlugin` of `bevy_egui`.
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_egui::{egui, EguiContext};
+use bevy_utils::get_short_name;
+use bevy_window::PrimaryWindow;
+
+use crate::{
+    event_dispatcher::EventDispatcher,
+    event_listener::{EntityEvent, Listeners, On},
+    history::EventHistory,
+};
+
+/// The number of events kept in the [`EventHistory`] added for each inspected event type, if it
+/// does not already have one.
+const HISTORY_CAPACITY: usize = 16;
+
+/// Adds the [`EventInspector`] resource and the system drawing its window, see the
+/// [module docs](self).
+#[derive(Default)]
+pub struct EventInspectorPlugin {
+    registrations: Vec<fn(&mut App)>,
+}
+
+impl EventInspectorPlugin {
+    /// Show the events of type `E` in the inspector. The [`EventListenerPlugin<E>`] must also be
+    /// added. An [`EventHistory<E>`] is added, unless one was already added with
+    /// [`EventListenerPlugin::with_history`].
+    ///
+    /// [`EventListenerPlugin<E>`]: crate::EventListenerPlugin
+    /// [`EventListenerPlugin::with_history`]: crate::EventListenerPlugin::with_history
+    pub fn inspect<E: EntityEvent>(mut self) -> Self {
+        self.registrations.push(register::<E>);
+        self
+    }
+}
+
+impl Plugin for EventInspectorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EventInspector>()
+            .add_systems(Update, inspector_ui);
+        for register in &self.registrations {
+            register(app);
+        }
+    }
+}
+
+/// The state of the inspector window, see the [module docs](self).
+#[derive(Resource)]
+pub struct EventInspector {
+    /// Is the window open? The window can be closed from its title bar.
+    pub open: bool,
+    /// The entity whose listeners are shown.
+    pub selected: Option<Entity>,
+    types: Vec<InspectedType>,
+}
+
+impl Default for EventInspector {
+    fn default() -> Self {
+        Self {
+            open: true,
+            selected: None,
+            types: Vec::new(),
+        }
+    }
+}
+
+impl EventInspector {
+    /// The names of the inspected event types, in the order they were added.
+    pub fn event_types(&self) -> impl Iterator<Item = &str> + '_ {
+        self.types.iter().map(|inspected| inspected.name.as_str())
+    }
+}
+
+/// An event type shown in the [`EventInspector`], with its type erased.
+struct InspectedType {
+    name: String,
+    total_events: fn(&World) -> usize,
+    history_ui: fn(&World, &mut egui::Ui, &mut Option<Entity>),
+    listeners_ui: fn(&mut World, &mut egui::Ui, Entity),
+    /// The value of `total_events` when the inspector last ran.
+    last_total: usize,
+    /// The number of events dispatched between the last two times the inspector ran.
+    per_frame: usize,
+}
+
+fn register<E: EntityEvent>(app: &mut App) {
+    if !app.world.contains_resource::<EventHistory<E>>() {
+        app.insert_resource(EventHistory::<E>::new(HISTORY_CAPACITY));
+    }
+    app.world
+        .resource_mut::<EventInspector>()
+        .types
+        .push(InspectedType {
+            name: get_short_name(std::any::type_name::<E>()),
+            total_events: total_events::<E>,
+            history_ui: history_ui::<E>,
+            listeners_ui: listeners_ui::<E>,
+            last_total: 0,
+            per_frame: 0,
+        });
+}
+
+fn total_events<E: EntityEvent>(world: &World) -> usize {
+    world
+        .get_resource::<EventDispatcher<E>>()
+        .map_or(0, EventDispatcher::total_events)
+}
+
+/// Show the propagation paths of the recent events of type `E`, newest first.
+fn history_ui<E: EntityEvent>(world: &World, ui: &mut egui::Ui, selected: &mut Option<Entity>) {
+    let Some(history) = world.get_resource::<EventHistory<E>>() else {
+        ui.label("No history is recorded.");
+        return;
+    };
+    for entry in history.iter().rev() {
+        ui.horizontal_wrapped(|ui| {
+            let path = match entry.path.as_slice() {
+                [] => std::slice::from_ref(&entry.target),
+                path => path,
+            };
+            for (i, &entity) in path.iter().enumerate() {
+                if i > 0 {
+                    ui.label("→");
+                }
+                if ui
+                    .selectable_label(*selected == Some(entity), format!("{entity:?}"))
+                    .clicked()
+                {
+                    *selected = Some(entity);
+                }
+            }
+            ui.label(format!("({} listeners)", entry.listeners.len()));
+            if entry.propagation_stopped {
+                ui.label("stopped");
+            }
+        });
+    }
+}
+
+/// Show a toggle for each listener of events of type `E` on `entity`.
+fn listeners_ui<E: EntityEvent>(world: &mut World, ui: &mut egui::Ui, entity: Entity) {
+    let Some(mut entity) = world.get_entity_mut(entity) else {
+        return;
+    };
+    let name = get_short_name(std::any::type_name::<E>());
+    if let Some(mut on) = entity.get_mut::<On<E>>() {
+        let mut enabled = on.is_enabled();
+        let label = format!("On<{name}>{}", describe(&on));
+        if ui.checkbox(&mut enabled, label).changed() {
+            on.set_enabled(enabled);
+        }
+    }
+    if let Some(mut listeners) = entity.get_mut::<Listeners<E>>() {
+        let ids: Vec<_> = listeners.ids().collect();
+        for id in ids {
+            let Some(on) = listeners.get_mut(id) else {
+                continue;
+            };
+            let mut enabled = on.is_enabled();
+            let label = format!("Listeners<{name}> {id:?}{}", describe(on));
+            if ui.checkbox(&mut enabled, label).changed() {
+                on.set_enabled(enabled);
+            }
+        }
+    }
+}
+
+/// The registered name and statistics of a listener, to show next to its toggle.
+fn describe<E: EntityEvent>(on: &On<E>) -> String {
+    let name = on
+        .registered_name()
+        .map_or_else(String::new, |name| format!(" \"{name}\""));
+    match on.last_fired_frame() {
+        Some(frame) => format!(
+            "{name}, fired {} times, last in frame {frame}",
+            on.times_fired()
+        ),
+        None => format!("{name}, fired {} times", on.times_fired()),
+    }
+}
+
+/// Draw the inspector window on the primary window.
+fn inspector_ui(world: &mut World) {
+    let Ok(mut context) = world
+        .query_filtered::<&mut EguiContext, With<PrimaryWindow>>()
+        .get_single_mut(world)
+    else {
+        return;
+    };
+    let context = context.get_mut().clone();
+    world.resource_scope(|world, mut inspector: Mut<EventInspector>| {
+        let inspector = &mut *inspector;
+        for inspected in &mut inspector.types {
+            let total = (inspected.total_events)(world);
+            inspected.per_frame = total - inspected.last_total;
+            inspected.last_total = total;
+        }
+        let mut open = inspector.open;
+        egui::Window::new("Event Inspector")
+            .open(&mut open)
+            .show(&context, |ui| {
+                for inspected in &inspector.types {
+                    ui.collapsing(
+                        format!(
+                            "{} ({} in the last frame)",
+                            inspected.name, inspected.per_frame
+                        ),
+                        |ui| (inspected.history_ui)(world, ui, &mut inspector.selected),
+                    );
+                }
+                ui.separator();
+                let Some(selected) = inspector.selected else {
+                    ui.label("Click an entity to show its listeners.");
+                    return;
+                };
+                ui.horizontal(|ui| {
+                    ui.label(format!("Listeners on {selected:?}"));
+                    if ui.button("Clear").clicked() {
+                        inspector.selected = None;
+                    }
+                });
+                for inspected in &inspector.types {
+                    (inspected.listeners_ui)(world, ui, selected);
+                }
+            });
+        inspector.open = open;
+    });
+}
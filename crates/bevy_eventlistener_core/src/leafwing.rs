@@ -0,0 +1,134 @@
+//! Bubbling action events, converted from the [`ActionState`] of `leafwing-input-manager`.
+//!
+//! Add the [`ActionEventsPlugin<A>`] to send an [`ActionPressed<A>`] event whenever an action of
+//! type `A` is just pressed, and an [`ActionReleased<A>`] event whenever one is just released. These
+//! events target the entity with the [`ActionState<A>`] component, and bubble up the hierarchy like
+//! any other [`EntityEvent`], so a vehicle can handle the actions of the character seated in it.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::Listener,
+//! #     event_listener::On,
+//! #     leafwing::{ActionEventsPlugin, ActionPressed},
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_reflect::Reflect;
+//! # use leafwing_input_manager::prelude::*;
+//! #[derive(Actionlike, Clone, Copy, PartialEq, Eq, Debug, Reflect)]
+//! enum Action {
+//!     Jump,
+//!     Honk,
+//! }
+//!
+//! fn spawn_car(mut commands: Commands) {
+//!     commands.spawn(On::<ActionPressed<Action>>::run(
+//!         |pressed: Listener<ActionPressed<Action>>| {
+//!             if pressed.action == Action::Honk {
+//!                 println!("honk!");
+//!             }
+//!         },
+//!     ));
+//! }
+//!
+//! App::new()
+//!     .add_plugins((
+//!         InputManagerPlugin::<Action>::default(),
+//!         ActionEventsPlugin::<Action>::default(),
+//!     ))
+//!     .add_systems(Startup, spawn_car);
+//! ```
+//!
+//! Only [`ActionState<A>`] components send events. The [`ActionState<A>`] resource is not the
+//! state of any entity, so it is ignored.
+
+use std::marker::PhantomData;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use leafwing_input_manager::{action_state::ActionState, plugin::InputManagerSystem, Actionlike};
+
+use crate::{event_listener::EntityEvent, EventListenerPlugin, EventListenerSet};
+
+macro_rules! action_event {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, PartialEq, Eq, Debug, Event)]
+        pub struct $name<A> {
+            /// The entity with the [`ActionState<A>`].
+            pub target: Entity,
+            /// The action.
+            pub action: A,
+        }
+
+        impl<A: Actionlike> EntityEvent for $name<A> {
+            fn target(&self) -> Entity {
+                self.target
+            }
+        }
+    };
+}
+
+action_event!(
+    /// Sent when an action is just pressed, see the [module docs](self).
+    ActionPressed
+);
+action_event!(
+    /// Sent when an action is just released, see the [module docs](self).
+    ActionReleased
+);
+
+/// Adds an [`EventListenerPlugin`] for [`ActionPressed<A>`] and [`ActionReleased<A>`], and sends
+/// them when the [`ActionState<A>`] of an entity changes, see the [module docs](self).
+///
+/// The events are sent in the [`PreUpdate`] schedule, after the [`InputManagerSystem`]s and before
+/// the [`EventListenerSet`], so they are dispatched in the same frame as the input.
+pub struct ActionEventsPlugin<A> {
+    phantom: PhantomData<fn() -> A>,
+}
+
+impl<A> Default for ActionEventsPlugin<A> {
+    fn default() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<A: Actionlike> Plugin for ActionEventsPlugin<A> {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            EventListenerPlugin::<ActionPressed<A>>::default(),
+            EventListenerPlugin::<ActionReleased<A>>::default(),
+        ))
+        .add_systems(
+            PreUpdate,
+            send_action_events::<A>
+                .after(InputManagerSystem::ManualControl)
+                .after(InputManagerSystem::ReleaseOnDisable)
+                .before(EventListenerSet),
+        );
+    }
+}
+
+/// Send an event for every action of type `A` that was just pressed or released.
+fn send_action_events<A: Actionlike>(
+    action_states: Query<(Entity, &ActionState<A>)>,
+    mut pressed: EventWriter<ActionPressed<A>>,
+    mut released: EventWriter<ActionReleased<A>>,
+) {
+    for (target, action_state) in &action_states {
+        pressed.send_batch(
+            action_state
+                .get_just_pressed()
+                .into_iter()
+                .map(|action| ActionPressed { target, action }),
+        );
+        released.send_batch(
+            action_state
+                .get_just_released()
+                .into_iter()
+                .map(|action| ActionReleased { target, action }),
+        );
+    }
+}
@@ -1,40 +1,1254 @@
 //! Core implementation of event listening, bubbling, and callbacks.
 
 use bevy_app::prelude::*;
-use bevy_ecs::prelude::*;
+use bevy_ecs::{
+    prelude::*,
+    schedule::{ScheduleLabel, SystemConfigs},
+    system::{Command, EntityCommands},
+    world::EntityMut,
+};
+use bevy_hierarchy::Parent;
+use bevy_reflect::TypePath;
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::{error, warn};
 
-use event_dispatcher::EventDispatcher;
-use event_listener::EntityEvent;
+use async_callbacks::AsyncCallbackTasks;
+use callback_errors::{CallbackErrorHandler, OnPanic, PanicIsolation};
+use callback_registry::{CallbackRegistry, ListenerParams, NamedListener};
+use callbacks::{IntoCallback, SharedCallback, SharedCallbacks};
+use checksum::StableHasher;
+use delayed::DelayedEvents;
+use event_dispatcher::{
+    Coalesce, CooldownQueue, DispatchNow, DispatchedEvent, EventDispatchControl, EventDispatcher,
+    EventOrder, ForwardEvents, NestedDispatch, QueueOverflow,
+};
+use event_listener::{
+    AddListener, DefaultAction, EntityEvent, ExpiringListeners, GlobalListeners, ListenerGroups,
+    ListenerHandle, Listeners, On, RemoveListener,
+};
+use history::EventHistory;
+use hooks::EventHooks;
+use notifications::{ListenerAdded, ListenerRemoved};
+use source::{EventSource, EventSources};
+use traversal::Traversal;
 
+#[cfg(feature = "animation")]
+pub mod animation;
+#[cfg(feature = "asset")]
+pub mod asset;
+pub mod async_callbacks;
+pub mod callback_errors;
+pub mod callback_registry;
 pub mod callbacks;
+pub mod checksum;
+pub mod delayed;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
+pub mod drag;
+pub mod dynamic;
 pub mod event_dispatcher;
 pub mod event_listener;
+pub mod family;
+#[cfg(feature = "focus")]
+pub mod focus;
+pub mod gesture;
+pub mod hierarchy;
+pub mod history;
+pub mod hooks;
+pub mod hover;
+#[cfg(feature = "egui")]
+pub mod inspector;
+#[cfg(feature = "leafwing")]
+pub mod leafwing;
+pub mod lifecycle;
+pub mod listener_info;
+pub mod listener_stats;
+pub mod notifications;
+pub mod on_any;
+#[cfg(any(
+    feature = "rapier2d",
+    feature = "rapier3d",
+    feature = "xpbd_2d",
+    feature = "xpbd_3d"
+))]
+pub mod physics;
+#[cfg(feature = "recording")]
+pub mod recording;
+#[cfg(feature = "replication")]
+pub mod replication;
+#[cfg(feature = "snapshot")]
+pub mod snapshot;
+pub mod source;
+pub mod targeted;
+pub mod testing;
+pub mod traversal;
+#[cfg(feature = "bevy_ui")]
+pub mod ui;
 
+pub use smallvec;
+
+/// The system set containing the event dispatch systems of every event type, along with the
+/// system that applies the results of async callbacks.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub struct EventListenerSet;
 
-/// Adds event listening and bubbling support for event `E`.
-pub struct EventListenerPlugin<E>(std::marker::PhantomData<E>);
+/// The system set containing the dispatch systems for events of type `E`. Use this to order
+/// systems relative to the dispatch of a single event type. These systems are also part of the
+/// [`EventListenerSet`].
+#[derive(SystemSet)]
+pub struct EventDispatchSet<E: EntityEvent>(std::marker::PhantomData<fn() -> E>);
 
-impl<E> Default for EventListenerPlugin<E> {
+impl<E: EntityEvent> Default for EventDispatchSet<E> {
     fn default() -> Self {
         Self(std::marker::PhantomData)
     }
 }
 
-impl<E: EntityEvent> Plugin for EventListenerPlugin<E> {
+impl<E: EntityEvent> Clone for EventDispatchSet<E> {
+    fn clone(&self) -> Self {
+        Self::default()
+    }
+}
+
+impl<E: EntityEvent> PartialEq for EventDispatchSet<E> {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl<E: EntityEvent> Eq for EventDispatchSet<E> {}
+
+impl<E: EntityEvent> std::hash::Hash for EventDispatchSet<E> {
+    fn hash<H: std::hash::Hasher>(&self, _state: &mut H) {}
+}
+
+impl<E: EntityEvent> std::fmt::Debug for EventDispatchSet<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("EventDispatchSet")
+            .field(&std::any::type_name::<E>())
+            .finish()
+    }
+}
+
+/// Adds the dispatch systems to the schedule chosen with [`EventListenerPlugin::in_schedule`].
+type AddSystems = Box<dyn Fn(&mut World, SystemConfigs) + Send + Sync>;
+/// Adds the dispatch systems to a set chosen with [`EventListenerPlugin::in_set`].
+type ConfigureSystems = Box<dyn Fn(SystemConfigs) -> SystemConfigs + Send + Sync>;
+
+/// Adds event listening and bubbling support for event `E`.
+///
+/// Events bubble up the hierarchy through the [`Traversal`] component `T`, which defaults to
+/// [`Parent`]. Only one plugin should be added for each event type.
+///
+/// By default, events are dispatched in the [`PreUpdate`] schedule. Use
+/// [`EventListenerPlugin::in_schedule`] and [`EventListenerPlugin::in_set`] to change this:
+///
+/// ```
+/// # use bevy_eventlistener_core::{event_listener::EntityEvent, EventListenerPlugin};
+/// # use bevy_app::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// # #[derive(Clone, Event)]
+/// # struct Click {
+/// #     target: Entity,
+/// # }
+/// # impl EntityEvent for Click {
+/// #     fn target(&self) -> Entity {
+/// #         self.target
+/// #     }
+/// # }
+/// #[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+/// struct InteractionSet;
+///
+/// App::new().add_plugins(
+///     EventListenerPlugin::<Click>::new()
+///         .in_schedule(Update)
+///         .in_set(InteractionSet),
+/// );
+/// ```
+pub struct EventListenerPlugin<E, T = Parent> {
+    schedule: AddSystems,
+    fixed_timestep: bool,
+    sets: Vec<ConfigureSystems>,
+    order: EventOrder,
+    forward: Option<ForwardEvents>,
+    coalesce: Option<Coalesce<E>>,
+    cache_graph: bool,
+    target_only: bool,
+    frame_budget: Option<std::time::Duration>,
+    queue_limit: Option<(usize, QueueOverflow)>,
+    history: Option<usize>,
+    notify_changes: bool,
+    reflect_info: bool,
+    listener_stats: bool,
+    nested: Option<usize>,
+    hash_event: Option<fn(&E, &mut StableHasher)>,
+    callback_errors: CallbackErrorHandler<E>,
+    on_panic: Option<OnPanic>,
+    #[cfg(feature = "trace")]
+    debug_logging: bool,
+    #[cfg(feature = "trace")]
+    despawn_warnings: bool,
+    phantom: std::marker::PhantomData<fn() -> (E, T)>,
+}
+
+impl<E, T> Default for EventListenerPlugin<E, T> {
+    fn default() -> Self {
+        Self {
+            schedule: Box::new(|world, systems| add_systems(world, PreUpdate, systems)),
+            fixed_timestep: false,
+            sets: Vec::new(),
+            order: EventOrder::default(),
+            forward: None,
+            coalesce: None,
+            cache_graph: false,
+            target_only: false,
+            frame_budget: None,
+            queue_limit: None,
+            history: None,
+            notify_changes: false,
+            reflect_info: false,
+            listener_stats: false,
+            nested: None,
+            hash_event: None,
+            callback_errors: CallbackErrorHandler::Log,
+            on_panic: None,
+            #[cfg(feature = "trace")]
+            debug_logging: false,
+            #[cfg(feature = "trace")]
+            despawn_warnings: false,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E, T> EventListenerPlugin<E, T> {
+    /// Create a plugin that dispatches events in the [`PreUpdate`] schedule.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Dispatch events in the given `schedule`, instead of [`PreUpdate`].
+    pub fn in_schedule(mut self, schedule: impl ScheduleLabel + Clone) -> Self {
+        self.schedule = Box::new(move |world, systems| {
+            add_systems(world, schedule.clone(), systems);
+        });
+        self
+    }
+
+    /// Dispatch events in a fixed timestep `schedule`, like [`FixedUpdate`], so they stay in step
+    /// with other systems in that schedule, like physics.
+    ///
+    /// Bevy normally updates the event buffer once per frame, so events are dropped after two
+    /// frames. A fixed timestep schedule can run less than once per frame, so this plugin instead
+    /// updates the buffer of `E` in the `schedule`, after dispatching. Events are kept until they
+    /// have been dispatched, no matter how often the schedule runs. Other systems that read events
+    /// of type `E` should also run in the `schedule`. `E` must not be added with
+    /// [`App::add_event`] elsewhere, or the buffer would also be updated every frame.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{event_listener::EntityEvent, EventListenerPlugin};
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Collision {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Collision {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// App::new().add_plugins(
+    ///     EventListenerPlugin::<Collision>::new().in_fixed_schedule(FixedUpdate),
+    /// );
+    /// ```
+    pub fn in_fixed_schedule(mut self, schedule: impl ScheduleLabel + Clone) -> Self {
+        self.fixed_timestep = true;
+        self.in_schedule(schedule)
+    }
+
+    /// Add the dispatch systems to the system `set`, in addition to the [`EventListenerSet`] and
+    /// [`EventDispatchSet<E>`]. This can be called more than once to add the systems to multiple
+    /// sets.
+    pub fn in_set(mut self, set: impl SystemSet + Clone) -> Self {
+        self.sets
+            .push(Box::new(move |systems| systems.in_set(set.clone())));
+        self
+    }
+
+    /// Set the order in which events sent in the same frame are propagated. Defaults to
+    /// [`EventOrder::SendOrder`].
+    pub fn with_order(mut self, order: EventOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Keep the graph of entities with listeners between frames, instead of traversing the
+    /// hierarchy again every frame, see [`EventDispatcher::cache_listener_graph`].
+    pub fn cache_listener_graph(mut self) -> Self {
+        self.cache_graph = true;
+        self
+    }
+
+    /// Only trigger the listeners on the target of each event, skipping the traversal of its
+    /// ancestors, see [`EventDispatcher::target_only`]. Use this for events that never need to
+    /// bubble.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{event_listener::EntityEvent, EventListenerPlugin};
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Damage {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Damage {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// App::new().add_plugins(EventListenerPlugin::<Damage>::new().target_only());
+    /// ```
+    pub fn target_only(mut self) -> Self {
+        self.target_only = true;
+        self
+    }
+
+    /// Spend at most `budget` propagating events each frame, carrying the remaining events over to
+    /// the next frame, see [`EventDispatcher::with_frame_budget`].
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{event_listener::EntityEvent, EventListenerPlugin};
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # use std::time::Duration;
+    /// # #[derive(Clone, Event)]
+    /// # struct Select {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Select {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// App::new().add_plugins(
+    ///     EventListenerPlugin::<Select>::new().with_frame_budget(Duration::from_micros(500)),
+    /// );
+    /// ```
+    pub fn with_frame_budget(mut self, budget: std::time::Duration) -> Self {
+        self.frame_budget = Some(budget);
+        self
+    }
+
+    /// Limit the number of events of type `E` waiting to be propagated to `limit`, and handle the
+    /// events over the limit with the `overflow` policy. By default, there is no limit.
+    pub fn with_queue_limit(mut self, limit: usize, overflow: QueueOverflow) -> Self {
+        self.queue_limit = Some((limit, overflow));
+        self
+    }
+
+    /// Combine the events sent to the same target in the same frame into a single event before
+    /// they are propagated, using the `coalesce` policy. By default, every event is propagated.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     event_dispatcher::Coalesce, event_listener::EntityEvent, EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_math::Vec2;
+    /// #[derive(Clone, Event)]
+    /// struct Scroll {
+    ///     target: Entity,
+    ///     delta: Vec2,
+    /// }
+    /// # impl EntityEvent for Scroll {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    ///
+    /// App::new().add_plugins(EventListenerPlugin::<Scroll>::new().coalesce_events(
+    ///     Coalesce::Merge(|scroll, next| scroll.delta += next.delta),
+    /// ));
+    /// ```
+    pub fn coalesce_events(mut self, coalesce: Coalesce<E>) -> Self {
+        self.coalesce = Some(coalesce);
+        self
+    }
+
+    /// Once events have finished propagating, send the events selected by `forward` as a
+    /// [`DispatchedEvent<E>`], so they can be read by normal systems. This can be used to react to
+    /// events that no listener handled.
+    pub fn forward_events(mut self, forward: ForwardEvents) -> Self {
+        self.forward = Some(forward);
+        self
+    }
+
+    /// Handle the errors returned by fallible callbacks with `handler`, instead of logging them,
+    /// see the [`callback_errors`] module.
+    pub fn on_callback_error(mut self, handler: CallbackErrorHandler<E>) -> Self {
+        self.callback_errors = handler;
+        self
+    }
+
+    /// Catch the panics of callbacks, instead of aborting dispatch, and pass them to the
+    /// [`CallbackErrorHandler<E>`]. The listener of a callback that panicked is handled with the
+    /// `on_panic` policy, see [`PanicIsolation`].
+    pub fn isolate_panics(mut self, on_panic: OnPanic) -> Self {
+        self.on_panic = Some(on_panic);
+        self
+    }
+
+    /// Keep a history of the last `capacity` events in the [`EventHistory<E>`] resource, including
+    /// the path of each event and the listeners it triggered.
+    pub fn with_history(mut self, capacity: usize) -> Self {
+        self.history = Some(capacity);
+        self
+    }
+
+    /// Send a [`ListenerAdded`](notifications::ListenerAdded) or
+    /// [`ListenerRemoved`](notifications::ListenerRemoved) event whenever an [`On<E>`] or
+    /// [`Listeners<E>`] component is added to or removed from an entity, see the
+    /// [`notifications`] module.
+    pub fn notify_listener_changes(mut self) -> Self {
+        self.notify_changes = true;
+        self
+    }
+
+    /// Keep a reflectable [`ListenerInfo`](listener_info::ListenerInfo) component on every entity
+    /// with listeners for events of type `E`, so inspectors can show their state, see the
+    /// [`listener_info`] module.
+    pub fn reflect_listener_info(mut self) -> Self {
+        self.reflect_info = true;
+        self
+    }
+
+    /// Dispatch the events of type `E` sent by callbacks immediately, instead of the next time the
+    /// dispatch systems run. This removes a frame of latency from every step of a chain of events,
+    /// like a click that opens a menu, which focuses its first button.
+    ///
+    /// Once an event of any type has finished propagating, the events of type `E` that were sent
+    /// while it was propagating, for example with an [`EventWriter`], are propagated right away,
+    /// before the next event. Nested events can send more events in turn, up to `max_depth` levels
+    /// deep. Events that would go deeper, or that have the same type and target as an event that
+    /// is still propagating, which would be a cycle, are dispatched the next time the dispatch
+    /// systems run instead. Other systems reading events of type `E` still see every event.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{event_listener::{EntityEvent, On}, EventListenerPlugin};
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # #[derive(Clone, Event)]
+    /// # struct OpenMenu {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for OpenMenu {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # #[derive(Resource, Default)]
+    /// # struct MenuOpen(bool);
+    /// let mut app = App::new();
+    /// app.add_plugins((
+    ///     EventListenerPlugin::<Click>::default(),
+    ///     EventListenerPlugin::<OpenMenu>::default().dispatch_nested(8),
+    /// ))
+    /// .init_resource::<MenuOpen>();
+    ///
+    /// let menu = app
+    ///     .world
+    ///     .spawn(On::<OpenMenu>::run(|mut open: ResMut<MenuOpen>| open.0 = true))
+    ///     .id();
+    /// let button = app
+    ///     .world
+    ///     .spawn(On::<Click>::send_event_with(move |_| OpenMenu { target: menu }))
+    ///     .id();
+    ///
+    /// app.world.send_event(Click { target: button });
+    /// app.update();
+    /// // The menu opened in the same frame as the click.
+    /// assert!(app.world.resource::<MenuOpen>().0);
+    /// ```
+    pub fn dispatch_nested(mut self, max_depth: usize) -> Self {
+        self.nested = Some(max_depth);
+        self
+    }
+
+    /// Keep the [`ListenerStats<E>`](listener_stats::ListenerStats) resource up to date, with the
+    /// number of enabled listeners, and the events dispatched and callbacks run in the last frame,
+    /// see the [`listener_stats`] module.
+    pub fn with_listener_stats(mut self) -> Self {
+        self.listener_stats = true;
+        self
+    }
+
+    /// Log every event once it has finished propagating, to help find out why a listener was or
+    /// wasn't triggered. Each message includes the event's target, the entities with listeners on
+    /// its path, which listeners were triggered and in which phase, and which listener stopped
+    /// propagation. Entities are described with their [`Name`](bevy_core::Name), if they have one.
+    ///
+    /// Messages are logged at the `info` level. This is only available with the `trace` feature.
+    #[cfg(feature = "trace")]
+    pub fn with_debug_logging(mut self) -> Self {
+        self.debug_logging = true;
+        self
+    }
+
+    /// Log a warning when an event reaches an entity that was despawned by a callback while the
+    /// event was propagating. The entity is always skipped, and the event continues to propagate
+    /// to the rest of its path, the warning only helps to find out why its listeners were not
+    /// triggered.
+    ///
+    /// Messages are logged at the `warn` level. This is only available with the `trace` feature.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     event_listener::{EntityEvent, On},
+    /// #     EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # use bevy_hierarchy::BuildWorldChildren;
+    /// # #[derive(Clone, Event)]
+    /// # struct Click {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for Click {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// # #[derive(Resource, Default)]
+    /// # struct Clicks(usize);
+    /// let mut app = App::new();
+    /// app.add_plugins(EventListenerPlugin::<Click>::default().with_despawn_warnings())
+    ///     .init_resource::<Clicks>();
+    ///
+    /// let parent = app
+    ///     .world
+    ///     .spawn(On::<Click>::run(|mut clicks: ResMut<Clicks>| clicks.0 += 1))
+    ///     .id();
+    /// let target = app
+    ///     .world
+    ///     .spawn(On::<Click>::run(move |mut commands: Commands| {
+    ///         commands.entity(parent).despawn();
+    ///     }))
+    ///     .set_parent(parent)
+    ///     .id();
+    ///
+    /// // Logs a warning that the parent was skipped, because it was despawned.
+    /// app.world.send_event(Click { target });
+    /// app.update();
+    /// assert_eq!(app.world.resource::<Clicks>().0, 0);
+    /// ```
+    #[cfg(feature = "trace")]
+    pub fn with_despawn_warnings(mut self) -> Self {
+        self.despawn_warnings = true;
+        self
+    }
+}
+
+impl<E: std::hash::Hash, T> EventListenerPlugin<E, T> {
+    /// Make the dispatch of events of type `E` reproducible bit for bit on every machine, for
+    /// lockstep multiplayer, and keep a checksum of the events that were dispatched, see the
+    /// [`checksum`] module.
+    ///
+    /// Events and listeners are always visited in a stable order: events in their
+    /// [`EventOrder`], and the listeners on each entity in order of priority, then in the order
+    /// they were added. Dispatch never iterates over a hash map. The only part of dispatch that
+    /// depends on the machine is the [frame budget](EventListenerPlugin::with_frame_budget),
+    /// which is ignored in deterministic mode. The app must still send the same events in the same
+    /// order, spawn the same entities, and drive [`Time`](bevy_time::Time) the same way on every
+    /// machine, for delayed events and throttled, debounced or expiring listeners. Async callbacks
+    /// finish in an unpredictable order, so they should not be used.
+    ///
+    /// Peers can compare the [`EventDispatcher::frame_checksum`] every frame, to detect a desync.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     event_dispatcher::EventDispatcher,
+    /// #     event_listener::{EntityEvent, On},
+    /// #     EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// #[derive(Clone, Event, Hash)]
+    /// struct Attack {
+    ///     target: Entity,
+    ///     damage: u32,
+    /// }
+    /// # impl EntityEvent for Attack {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    ///
+    /// let mut app = App::new();
+    /// app.add_plugins(EventListenerPlugin::<Attack>::new().deterministic());
+    /// let target = app.world.spawn(On::<Attack>::run(|| {})).id();
+    /// app.world.send_event(Attack { target, damage: 3 });
+    /// app.update();
+    ///
+    /// let dispatcher = app.world.resource::<EventDispatcher<Attack>>();
+    /// // Send this to the other peers, to compare with their own checksum for the same frame.
+    /// let checksum = dispatcher.frame_checksum().unwrap();
+    /// # assert_eq!(checksum, dispatcher.checksum().unwrap());
+    /// ```
+    pub fn deterministic(mut self) -> Self {
+        self.hash_event = Some(checksum::hash_event::<E>);
+        self
+    }
+}
+
+impl<E: EntityEvent, T: Traversal> Plugin for EventListenerPlugin<E, T> {
     fn build(&self, app: &mut App) {
-        app.add_event::<E>()
-            .insert_resource(EventDispatcher::<E>::default())
-            .add_systems(
+        self.add_to_world(&mut app.world);
+    }
+}
+
+impl<E: EntityEvent, T: Traversal> EventListenerPlugin<E, T> {
+    /// Add the resources and dispatch systems of this plugin to the `world`, and to its
+    /// [`Schedules`].
+    fn add_to_world(&self, world: &mut World) {
+        let mut dispatcher = EventDispatcher::<E>::with_order(self.order).with_traversal::<T>();
+        if self.cache_graph {
+            dispatcher = dispatcher.cache_listener_graph();
+        }
+        if self.target_only {
+            dispatcher = dispatcher.target_only();
+        }
+        if let Some(budget) = self.frame_budget {
+            dispatcher = dispatcher.with_frame_budget(budget);
+        }
+        if let Some((limit, overflow)) = self.queue_limit {
+            dispatcher = dispatcher.with_queue_limit(limit, overflow);
+        }
+        if let Some(coalesce) = self.coalesce {
+            dispatcher = dispatcher.coalesce_events(coalesce);
+        }
+        if let Some(hash_event) = self.hash_event {
+            #[cfg(feature = "trace")]
+            if self.frame_budget.is_some() {
+                warn!(
+                    "The frame budget of {:?} is ignored, because its dispatcher is deterministic",
+                    std::any::type_name::<E>(),
+                );
+            }
+            dispatcher = dispatcher.with_checksum(hash_event);
+        }
+        if let Some(max_depth) = self.nested {
+            dispatcher = dispatcher.with_nested_dispatch(max_depth);
+            NestedDispatch::register::<E>(world);
+        }
+        if let Some(forward) = self.forward {
+            dispatcher = dispatcher.forward_events(forward);
+            add_event::<DispatchedEvent<E>>(world);
+        }
+        #[cfg(feature = "trace")]
+        if self.debug_logging {
+            dispatcher = dispatcher.with_debug_logging();
+        }
+        #[cfg(feature = "trace")]
+        if self.despawn_warnings {
+            dispatcher = dispatcher.with_despawn_warnings();
+        }
+        if let Some(capacity) = self.history {
+            world.insert_resource(EventHistory::<E>::new(capacity));
+        }
+        if self.fixed_timestep {
+            world.init_resource::<Events<E>>();
+        } else {
+            add_event::<E>(world);
+        }
+        world.insert_resource(dispatcher);
+        world.init_resource::<GlobalListeners<E>>();
+        world.init_resource::<EventHooks<E>>();
+        world.init_resource::<DelayedEvents<E>>();
+        world.init_resource::<SharedCallbacks<E>>();
+        world.init_resource::<ListenerGroups>();
+        world.init_resource::<EventDispatchControl>();
+        world.init_resource::<EventSources<E>>();
+        world.init_resource::<ExpiringListeners<E>>();
+        world.insert_resource(self.callback_errors);
+        if let Some(on_panic) = self.on_panic {
+            world.insert_resource(PanicIsolation::<E>::new(on_panic));
+        }
+
+        let systems = (
+            On::<E>::remove_expired.run_if(ExpiringListeners::<E>::has_expiring),
+            CooldownQueue::<E>::run.run_if(CooldownQueue::<E>::has_pending),
+            DelayedEvents::<E>::send_due.run_if(DelayedEvents::<E>::has_pending),
+            EventDispatcher::<E>::build::<T>.run_if(
+                on_event::<E>()
+                    .or_else(EventDispatcher::<E>::has_carried_events)
+                    .or_else(EventDispatcher::<E>::has_held_events),
+            ),
+            EventDispatcher::<E>::bubble_events.run_if(EventDispatcher::<E>::has_events),
+            EventDispatcher::<E>::cleanup.run_if(EventDispatcher::<E>::has_events),
+        )
+            .chain()
+            .in_set(EventListenerSet)
+            .in_set(EventDispatchSet::<E>::default());
+        let systems = if self.fixed_timestep {
+            (systems, Events::<E>::update_system).chain()
+        } else {
+            systems
+        };
+        let systems = self.sets.iter().fold(systems, |systems, set| set(systems));
+        (self.schedule)(world, systems);
+
+        if self.hash_event.is_some() {
+            let reset = EventDispatcher::<E>::reset_frame_checksum
+                .before(On::<E>::remove_expired)
+                .in_set(EventListenerSet)
+                .in_set(EventDispatchSet::<E>::default());
+            let reset = self.sets.iter().fold(reset, |systems, set| set(systems));
+            (self.schedule)(world, reset);
+        }
+
+        if self.notify_changes {
+            add_event::<ListenerAdded>(world);
+            add_event::<ListenerRemoved>(world);
+            let notify = notifications::notify_listener_changes::<E>
+                .in_set(EventListenerSet)
+                .in_set(EventDispatchSet::<E>::default());
+            let notify = self.sets.iter().fold(notify, |systems, set| set(systems));
+            (self.schedule)(world, notify);
+        }
+
+        if self.reflect_info {
+            if let Some(registry) = world.get_resource::<AppTypeRegistry>() {
+                registry.write().register::<listener_info::ListenerInfo>();
+            }
+            let sync = listener_info::sync_listener_info::<E>
+                .in_set(EventListenerSet)
+                .after(EventDispatchSet::<E>::default());
+            let sync = self.sets.iter().fold(sync, |systems, set| set(systems));
+            (self.schedule)(world, sync);
+        }
+
+        if self.listener_stats {
+            world.init_resource::<listener_stats::ListenerStats<E>>();
+            let update = listener_stats::ListenerStats::<E>::update
+                .in_set(EventListenerSet)
+                .after(EventDispatchSet::<E>::default());
+            let update = self.sets.iter().fold(update, |systems, set| set(systems));
+            (self.schedule)(world, update);
+        }
+
+        // Only one instance of this system is needed, no matter how many event types are added.
+        if !world.contains_resource::<AsyncCallbackTasks>() {
+            world.init_resource::<AsyncCallbackTasks>();
+            add_systems(
+                world,
                 PreUpdate,
-                (
-                    EventDispatcher::<E>::build.run_if(on_event::<E>()),
-                    EventDispatcher::<E>::bubble_events.run_if(on_event::<E>()),
-                    EventDispatcher::<E>::cleanup.run_if(on_event::<E>()),
-                )
-                    .chain()
-                    .in_set(EventListenerSet),
+                AsyncCallbackTasks::apply_finished.in_set(EventListenerSet),
             );
+        }
+    }
+}
+
+/// Add `systems` to the `schedule` in the world's [`Schedules`], creating the schedule if it
+/// doesn't exist, like [`App::add_systems`].
+fn add_systems<M>(
+    world: &mut World,
+    schedule: impl ScheduleLabel,
+    systems: impl IntoSystemConfigs<M>,
+) {
+    let mut schedules = world.resource_mut::<Schedules>();
+    if let Some(schedule) = schedules.get_mut(&schedule) {
+        schedule.add_systems(systems);
+    } else {
+        let mut new_schedule = Schedule::new();
+        new_schedule.add_systems(systems);
+        schedules.insert(schedule, new_schedule);
+    }
+}
+
+/// Add the [`Events<E>`] resource, and the system that updates it every frame, like
+/// [`App::add_event`].
+fn add_event<E: Event>(world: &mut World) {
+    if !world.contains_resource::<Events<E>>() {
+        world.init_resource::<Events<E>>();
+        add_systems(world, First, Events::<E>::update_system);
+    }
+}
+
+/// Adds an [`EventListenerPlugin`] with the default settings for every event type in the tuple
+/// `Events`, so a whole vocabulary of events can be registered at once. Tuples of up to 12 event
+/// types are supported, and tuples can be nested for more.
+///
+/// ```
+/// # use bevy_eventlistener_core::{event_listener::EntityEvent, EventListenerPlugins};
+/// # use bevy_app::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// # macro_rules! event {
+/// #     ($name:ident) => {
+/// #         #[derive(Clone, Event)]
+/// #         struct $name {
+/// #             target: Entity,
+/// #         }
+/// #         impl EntityEvent for $name {
+/// #             fn target(&self) -> Entity {
+/// #                 self.target
+/// #             }
+/// #         }
+/// #     };
+/// # }
+/// # event!(Click);
+/// # event!(Hover);
+/// # event!(Drop);
+/// App::new().add_plugins(EventListenerPlugins::<(Click, Hover, Drop)>::default());
+/// ```
+///
+/// Event types that need other settings, such as a different [`Traversal`], can be added with
+/// their own [`EventListenerPlugin`] alongside this one.
+pub struct EventListenerPlugins<Events> {
+    phantom: std::marker::PhantomData<fn() -> Events>,
+}
+
+impl<Events> Default for EventListenerPlugins<Events> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+/// A tuple of event types that can be added at once with [`EventListenerPlugins`].
+pub trait EntityEventSet: 'static {
+    /// Add an [`EventListenerPlugin`] for every event type in this set.
+    fn add_plugins(app: &mut App);
+}
+
+impl<E: EntityEvent> EntityEventSet for E {
+    fn add_plugins(app: &mut App) {
+        app.add_plugins(EventListenerPlugin::<E>::default());
+    }
+}
+
+macro_rules! impl_event_listener_plugins {
+    ($($event:ident),*) => {
+        impl<$($event: EntityEventSet),*> EntityEventSet for ($($event,)*) {
+            fn add_plugins(app: &mut App) {
+                $($event::add_plugins(app);)*
+            }
+        }
+    };
+}
+
+impl_event_listener_plugins!(E0);
+impl_event_listener_plugins!(E0, E1);
+impl_event_listener_plugins!(E0, E1, E2);
+impl_event_listener_plugins!(E0, E1, E2, E3);
+impl_event_listener_plugins!(E0, E1, E2, E3, E4);
+impl_event_listener_plugins!(E0, E1, E2, E3, E4, E5);
+impl_event_listener_plugins!(E0, E1, E2, E3, E4, E5, E6);
+impl_event_listener_plugins!(E0, E1, E2, E3, E4, E5, E6, E7);
+impl_event_listener_plugins!(E0, E1, E2, E3, E4, E5, E6, E7, E8);
+impl_event_listener_plugins!(E0, E1, E2, E3, E4, E5, E6, E7, E8, E9);
+impl_event_listener_plugins!(E0, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10);
+impl_event_listener_plugins!(E0, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11);
+
+impl<Events: EntityEventSet> Plugin for EventListenerPlugins<Events> {
+    fn build(&self, app: &mut App) {
+        Events::add_plugins(app);
+    }
+}
+
+/// Extension methods for adding event listeners to an [`App`].
+pub trait EventListenerAppExt {
+    /// Add an [`EventListenerPlugin`] with the default settings for events of type `E`. This is
+    /// a shorthand for `app.add_plugins(EventListenerPlugin::<E>::default())`, see also
+    /// [`EventListenerPlugins`] to add many event types at once.
+    fn add_event_listener<E: EntityEvent>(&mut self) -> &mut Self;
+
+    /// Add a listener that is triggered by every event of type `E`, regardless of its target. See
+    /// [`GlobalListeners`] for more details. Returns a [`ListenerHandle`] that can be used to
+    /// remove the new listener later, like
+    /// [`EventListenerWorldExt::add_global_listener`].
+    fn add_global_listener<E: EntityEvent>(&mut self, listener: On<E>) -> ListenerHandle<E>;
+
+    /// Set the [`DefaultAction`] for events of type `E`, replacing the existing one. The
+    /// `callback` system is run once an event has finished propagating, unless a listener called
+    /// [`ListenerInput::prevent_default`](callbacks::ListenerInput::prevent_default).
+    fn set_default_action<E: EntityEvent, Marker>(
+        &mut self,
+        callback: impl IntoSystem<(), (), Marker>,
+    ) -> &mut Self;
+
+    /// Add a callback system to the [`SharedCallbacks<E>`] resource, returning a handle that can be
+    /// used by many listeners with [`On::run_shared`].
+    fn add_shared_callback<E: EntityEvent, Marker>(
+        &mut self,
+        callback: impl IntoSystem<(), (), Marker>,
+    ) -> SharedCallback<E>;
+
+    /// Register a function that constructs an event listener under `name` in the
+    /// [`CallbackRegistry<E>`], so it can be referred to by a [`NamedListener<E>`].
+    fn register_callback<E: EntityEvent + TypePath>(
+        &mut self,
+        name: impl Into<String>,
+        listener: impl Fn() -> On<E> + Send + Sync + 'static,
+    ) -> &mut Self;
+
+    /// Register a function that constructs an event listener from a set of [`ListenerParams`]
+    /// under `name` in the [`CallbackRegistry<E>`], see [`CallbackRegistry::register_with_params`].
+    fn register_callback_with_params<E: EntityEvent + TypePath>(
+        &mut self,
+        name: impl Into<String>,
+        listener: impl Fn(&ListenerParams) -> On<E> + Send + Sync + 'static,
+    ) -> &mut Self;
+}
+
+impl EventListenerAppExt for App {
+    fn add_event_listener<E: EntityEvent>(&mut self) -> &mut Self {
+        self.add_plugins(EventListenerPlugin::<E>::default())
+    }
+
+    fn add_global_listener<E: EntityEvent>(&mut self, listener: On<E>) -> ListenerHandle<E> {
+        self.world.add_global_listener(listener)
+    }
+
+    fn set_default_action<E: EntityEvent, Marker>(
+        &mut self,
+        callback: impl IntoSystem<(), (), Marker>,
+    ) -> &mut Self {
+        self.insert_resource(DefaultAction::<E>::new(callback))
+    }
+
+    fn add_shared_callback<E: EntityEvent, Marker>(
+        &mut self,
+        callback: impl IntoSystem<(), (), Marker>,
+    ) -> SharedCallback<E> {
+        self.world
+            .get_resource_or_insert_with(SharedCallbacks::<E>::default)
+            .add(callback)
+    }
+
+    fn register_callback<E: EntityEvent + TypePath>(
+        &mut self,
+        name: impl Into<String>,
+        listener: impl Fn() -> On<E> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.register_callback_with_params(name, move |_| listener())
+    }
+
+    fn register_callback_with_params<E: EntityEvent + TypePath>(
+        &mut self,
+        name: impl Into<String>,
+        listener: impl Fn(&ListenerParams) -> On<E> + Send + Sync + 'static,
+    ) -> &mut Self {
+        // Named listeners are resolved before any events are dispatched, so they can be triggered
+        // in the same frame they were spawned.
+        if !self.world.contains_resource::<CallbackRegistry<E>>() {
+            self.init_resource::<CallbackRegistry<E>>()
+                .register_type::<NamedListener<E>>()
+                .add_systems(
+                    PreUpdate,
+                    (CallbackRegistry::<E>::resolve, apply_deferred)
+                        .chain()
+                        .before(EventListenerSet),
+                );
+        }
+        self.world
+            .resource_mut::<CallbackRegistry<E>>()
+            .register_with_params(name, listener);
+        self
+    }
+}
+
+/// Extension methods for sending events with [`Commands`].
+pub trait EventListenerCommandsExt {
+    /// Send the `event` when the commands are applied, so it propagates from its
+    /// [`targets`](EntityEvent::targets) the next time the dispatch systems run. Unlike an
+    /// [`EventWriter<E>`], this doesn't need to be added to the system's parameters, so it can be
+    /// used by any code that has access to [`Commands`].
+    fn trigger_targeted<E: EntityEvent>(&mut self, event: E);
+
+    /// Propagate the `event` as soon as the commands are applied, instead of the next time the
+    /// dispatch systems run, see [`DispatchNow`].
+    fn dispatch_now<E: EntityEvent>(&mut self, event: E);
+
+    /// Add the `plugin` to the running app when the commands are applied, see
+    /// [`EventListenerWorldExt::add_event_listener_plugin`].
+    fn add_event_listener_plugin<E: EntityEvent, T: Traversal>(
+        &mut self,
+        plugin: EventListenerPlugin<E, T>,
+    );
+
+    /// Add the `listener` to the [`Listeners<E>`] of the `entity` when the commands are applied,
+    /// returning a [`ListenerHandle`] that can be used to remove it later.
+    fn add_listener<E: EntityEvent>(
+        &mut self,
+        entity: Entity,
+        listener: On<E>,
+    ) -> ListenerHandle<E>;
+
+    /// Add the `listener` to the [`GlobalListeners<E>`] when the commands are applied, returning a
+    /// [`ListenerHandle`] that can be used to remove it later.
+    fn add_global_listener<E: EntityEvent>(&mut self, listener: On<E>) -> ListenerHandle<E>;
+
+    /// Remove the listener referred to by the `handle` when the commands are applied, leaving the
+    /// other listeners untouched.
+    fn remove_listener<E: EntityEvent>(&mut self, handle: ListenerHandle<E>);
+
+    /// Send the `event` when the commands are applied, recording its `source`, see
+    /// [`EventListenerWorldExt::send_event_from`].
+    fn send_event_from<E: EntityEvent>(&mut self, source: impl Into<EventSource>, event: E);
+}
+
+impl EventListenerCommandsExt for Commands<'_, '_> {
+    fn trigger_targeted<E: EntityEvent>(&mut self, event: E) {
+        self.add(move |world: &mut World| world.trigger_targeted(event));
+    }
+
+    fn dispatch_now<E: EntityEvent>(&mut self, event: E) {
+        self.add(DispatchNow::new(event));
+    }
+
+    fn add_event_listener_plugin<E: EntityEvent, T: Traversal>(
+        &mut self,
+        plugin: EventListenerPlugin<E, T>,
+    ) {
+        self.add(move |world: &mut World| world.add_event_listener_plugin(plugin));
+    }
+
+    fn add_listener<E: EntityEvent>(
+        &mut self,
+        entity: Entity,
+        listener: On<E>,
+    ) -> ListenerHandle<E> {
+        let add = AddListener::new(entity, listener);
+        let handle = add.handle();
+        self.add(add);
+        handle
+    }
+
+    fn add_global_listener<E: EntityEvent>(&mut self, listener: On<E>) -> ListenerHandle<E> {
+        let add = AddListener::global(listener);
+        let handle = add.handle();
+        self.add(add);
+        handle
+    }
+
+    fn remove_listener<E: EntityEvent>(&mut self, handle: ListenerHandle<E>) {
+        self.add(RemoveListener::from(handle));
+    }
+
+    fn send_event_from<E: EntityEvent>(&mut self, source: impl Into<EventSource>, event: E) {
+        let source = source.into();
+        self.add(move |world: &mut World| world.send_event_from(source, event));
+    }
+}
+
+/// Extension methods for sending events with exclusive access to the [`World`].
+pub trait EventListenerWorldExt {
+    /// Send the `event`, so it propagates from its [`targets`](EntityEvent::targets) the next
+    /// time the dispatch systems run, see [`EventListenerCommandsExt::trigger_targeted`].
+    fn trigger_targeted<E: EntityEvent>(&mut self, event: E);
+
+    /// Add the `plugin` to an app that is already running, so events of type `E` are dispatched
+    /// to their listeners from the next time its schedule runs. This is useful for event types
+    /// that are only known after startup, such as the events of a mod loaded at runtime. The
+    /// resources of the plugin are inserted, and its systems are added to the world's
+    /// [`Schedules`].
+    ///
+    /// The systems can't be added to a schedule while it is running, so this must not be called
+    /// from the schedule the plugin dispatches events in, [`PreUpdate`] by default. Commands
+    /// applied in [`Update`] are fine. If a plugin was already added for `E`, this does nothing.
+    ///
+    /// ```
+    /// # use bevy_eventlistener_core::{
+    /// #     event_listener::{EntityEvent, On},
+    /// #     EventListenerCommandsExt, EventListenerPlugin,
+    /// # };
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_ecs::prelude::*;
+    /// # #[derive(Clone, Event)]
+    /// # struct ModEvent {
+    /// #     target: Entity,
+    /// # }
+    /// # impl EntityEvent for ModEvent {
+    /// #     fn target(&self) -> Entity {
+    /// #         self.target
+    /// #     }
+    /// # }
+    /// fn load_mod(mut commands: Commands) {
+    ///     commands.add_event_listener_plugin(EventListenerPlugin::<ModEvent>::default());
+    /// }
+    ///
+    /// let mut app = App::new();
+    /// app.add_systems(Update, load_mod.run_if(run_once()));
+    /// app.update();
+    ///
+    /// let target = app.world.spawn(On::<ModEvent>::run(|| {})).id();
+    /// app.world.send_event(ModEvent { target });
+    /// app.update();
+    /// ```
+    fn add_event_listener_plugin<E: EntityEvent, T: Traversal>(
+        &mut self,
+        plugin: EventListenerPlugin<E, T>,
+    );
+
+    /// Add the `listener` to the [`Listeners<E>`] of the `entity`, returning a [`ListenerHandle`]
+    /// that can be used to remove it later.
+    fn add_listener<E: EntityEvent>(
+        &mut self,
+        entity: Entity,
+        listener: On<E>,
+    ) -> ListenerHandle<E>;
+
+    /// Add the `listener` to the [`GlobalListeners<E>`], returning a [`ListenerHandle`] that can be
+    /// used to remove it later.
+    fn add_global_listener<E: EntityEvent>(&mut self, listener: On<E>) -> ListenerHandle<E>;
+
+    /// Remove the listener referred to by the `handle`, leaving the other listeners untouched.
+    /// Returns the listener if it still existed.
+    fn remove_listener<E: EntityEvent>(&mut self, handle: ListenerHandle<E>) -> Option<On<E>>;
+
+    /// Send the `event`, recording its `source`, which is exposed to listeners with
+    /// [`ListenerInput::source`](callbacks::ListenerInput::source). Use
+    /// [`EventSource::caller`] to record the location this is called from.
+    fn send_event_from<E: EntityEvent>(&mut self, source: impl Into<EventSource>, event: E);
+}
+
+impl EventListenerWorldExt for World {
+    fn trigger_targeted<E: EntityEvent>(&mut self, event: E) {
+        self.send_event(event);
+    }
+
+    fn add_event_listener_plugin<E: EntityEvent, T: Traversal>(
+        &mut self,
+        plugin: EventListenerPlugin<E, T>,
+    ) {
+        if self.contains_resource::<EventDispatcher<E>>() {
+            #[cfg(feature = "trace")]
+            error!(
+                "An EventListenerPlugin was already added for {}",
+                std::any::type_name::<E>()
+            );
+            return;
+        }
+        plugin.add_to_world(self);
+    }
+
+    fn add_listener<E: EntityEvent>(
+        &mut self,
+        entity: Entity,
+        listener: On<E>,
+    ) -> ListenerHandle<E> {
+        let add = AddListener::new(entity, listener);
+        let handle = add.handle();
+        add.apply(self);
+        handle
+    }
+
+    fn add_global_listener<E: EntityEvent>(&mut self, listener: On<E>) -> ListenerHandle<E> {
+        let add = AddListener::global(listener);
+        let handle = add.handle();
+        add.apply(self);
+        handle
+    }
+
+    fn remove_listener<E: EntityEvent>(&mut self, handle: ListenerHandle<E>) -> Option<On<E>> {
+        handle.remove(self)
+    }
+
+    fn send_event_from<E: EntityEvent>(&mut self, source: impl Into<EventSource>, event: E) {
+        if !self.contains_resource::<Events<E>>() {
+            // Logs the same error as any other event sent before it was added.
+            self.send_event(event);
+            return;
+        }
+        self.resource_scope(|world, mut events: Mut<Events<E>>| {
+            world
+                .get_resource_or_insert_with(EventSources::<E>::default)
+                .send(&mut events, source.into(), event);
+        });
+    }
+}
+
+/// Extension methods for adding and removing the listeners of an entity, with
+/// [`EntityCommands`] or [`EntityMut`].
+///
+/// ```
+/// # use bevy_eventlistener_core::{event_listener::EntityEvent, EventListenerEntityExt};
+/// # use bevy_ecs::prelude::*;
+/// # #[derive(Clone, Event)]
+/// # struct Click {
+/// #     target: Entity,
+/// # }
+/// # impl EntityEvent for Click {
+/// #     fn target(&self) -> Entity {
+/// #         self.target
+/// #     }
+/// # }
+/// fn open_door() {}
+///
+/// fn spawn_door(mut commands: Commands) {
+///     let door = commands.spawn_empty().listen::<Click, _>(open_door).id();
+///     commands.entity(door).emit(Click { target: door });
+/// }
+/// ```
+pub trait EventListenerEntityExt {
+    /// Add a listener that runs the `callback` when it is triggered by an event of type `E`, like
+    /// [`On::run`]. The listener is added to the entity's [`Listeners<E>`], so it does not replace any
+    /// existing listeners.
+    fn listen<E: EntityEvent, Marker>(&mut self, callback: impl IntoCallback<Marker>) -> &mut Self;
+
+    /// Remove every listener for events of type `E` from the entity, both the [`On<E>`] and the
+    /// [`Listeners<E>`] components.
+    fn unlisten<E: EntityEvent>(&mut self) -> &mut Self;
+
+    /// Send the `event`. It propagates from its own [`targets`](EntityEvent::targets), which are
+    /// usually this entity, the next time the dispatch systems run.
+    fn emit<E: EntityEvent>(&mut self, event: E) -> &mut Self;
+}
+
+impl EventListenerEntityExt for EntityCommands<'_, '_, '_> {
+    fn listen<E: EntityEvent, Marker>(&mut self, callback: impl IntoCallback<Marker>) -> &mut Self {
+        let entity = self.id();
+        self.commands()
+            .add(AddListener::new(entity, On::<E>::run(callback)));
+        self
+    }
+
+    fn unlisten<E: EntityEvent>(&mut self) -> &mut Self {
+        self.remove::<(On<E>, Listeners<E>)>()
+    }
+
+    fn emit<E: EntityEvent>(&mut self, event: E) -> &mut Self {
+        self.commands().trigger_targeted(event);
+        self
+    }
+}
+
+impl EventListenerEntityExt for EntityMut<'_> {
+    fn listen<E: EntityEvent, Marker>(&mut self, callback: impl IntoCallback<Marker>) -> &mut Self {
+        let add = AddListener::new(self.id(), On::<E>::run(callback));
+        self.world_scope(|world| add.apply(world));
+        self
+    }
+
+    fn unlisten<E: EntityEvent>(&mut self) -> &mut Self {
+        self.remove::<(On<E>, Listeners<E>)>();
+        self
+    }
+
+    fn emit<E: EntityEvent>(&mut self, event: E) -> &mut Self {
+        self.world_scope(|world| world.trigger_targeted(event));
+        self
     }
 }
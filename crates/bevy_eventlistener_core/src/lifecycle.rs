@@ -0,0 +1,158 @@
+//! Events sent when a component is added to, changed on, or removed from an entity.
+//!
+//! The [`ComponentEventsPlugin<C>`] sends a [`ComponentAdded<C>`], [`ComponentChanged<C>`] or
+//! [`ComponentRemoved<C>`] event targeting an entity whenever its `C` component is added, changed
+//! or removed. These events bubble up the hierarchy like any other [`EntityEvent`], so a parent can
+//! react to the state of its children with a single listener, instead of polling a query.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::Listener,
+//! #     event_listener::On,
+//! #     lifecycle::{ComponentChanged, ComponentEventsPlugin},
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_hierarchy::BuildWorldChildren;
+//! #[derive(Component)]
+//! struct Health(u32);
+//!
+//! let mut app = App::new();
+//! app.add_plugins(ComponentEventsPlugin::<Health>::default());
+//!
+//! let party = app
+//!     .world
+//!     .spawn(On::<ComponentChanged<Health>>::run(
+//!         |changed: Listener<ComponentChanged<Health>>, health: Query<&Health>| {
+//!             let health = health.get(changed.target).unwrap();
+//!             println!("a party member now has {} health", health.0);
+//!         },
+//!     ))
+//!     .id();
+//! let member = app.world.spawn(Health(10)).set_parent(party).id();
+//! app.update();
+//!
+//! app.world.get_mut::<Health>(member).unwrap().0 = 5;
+//! app.update();
+//! ```
+//!
+//! Changes are detected once per frame, right before events are dispatched, so a component that is
+//! changed several times in a frame only sends one event. A component that is added is not also
+//! reported as changed in the same frame. When an entity is despawned, its [`ComponentRemoved<C>`]
+//! event can't bubble, because the entity no longer exists, so it only reaches
+//! [`GlobalListeners`](crate::event_listener::GlobalListeners).
+
+use std::marker::PhantomData;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+
+use crate::{event_listener::EntityEvent, EventListenerPlugin, EventListenerSet};
+
+macro_rules! component_event {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Event)]
+        pub struct $name<C> {
+            /// The entity the component is on.
+            pub target: Entity,
+            phantom: PhantomData<fn() -> C>,
+        }
+
+        impl<C> $name<C> {
+            /// Create an event targeting the entity with the component.
+            pub fn new(target: Entity) -> Self {
+                Self {
+                    target,
+                    phantom: PhantomData,
+                }
+            }
+        }
+
+        impl<C> Clone for $name<C> {
+            fn clone(&self) -> Self {
+                Self::new(self.target)
+            }
+        }
+
+        impl<C> std::fmt::Debug for $name<C> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("component", &std::any::type_name::<C>())
+                    .field("target", &self.target)
+                    .finish()
+            }
+        }
+
+        impl<C: Component> EntityEvent for $name<C> {
+            fn target(&self) -> Entity {
+                self.target
+            }
+        }
+    };
+}
+
+component_event!(
+    /// Sent when a `C` component is added to an entity, see the [module docs](self).
+    ComponentAdded
+);
+component_event!(
+    /// Sent when the `C` component of an entity is mutably accessed, or replaced by inserting a
+    /// new one, see the [module docs](self).
+    ComponentChanged
+);
+component_event!(
+    /// Sent when a `C` component is removed from an entity, or the entity is despawned, see the
+    /// [module docs](self).
+    ComponentRemoved
+);
+
+/// Sends the [`ComponentAdded<C>`], [`ComponentChanged<C>`] and [`ComponentRemoved<C>`] events,
+/// and adds an [`EventListenerPlugin`] for each of them, see the [module docs](self).
+///
+/// The events are sent in the [`PreUpdate`] schedule, right before the [`EventListenerSet`], so
+/// they are dispatched in the same frame.
+pub struct ComponentEventsPlugin<C> {
+    phantom: PhantomData<fn() -> C>,
+}
+
+impl<C> Default for ComponentEventsPlugin<C> {
+    fn default() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: Component> Plugin for ComponentEventsPlugin<C> {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            EventListenerPlugin::<ComponentAdded<C>>::default(),
+            EventListenerPlugin::<ComponentChanged<C>>::default(),
+            EventListenerPlugin::<ComponentRemoved<C>>::default(),
+        ))
+        .add_systems(
+            PreUpdate,
+            send_component_events::<C>.before(EventListenerSet),
+        );
+    }
+}
+
+/// Send an event for every `C` component that was added, changed or removed since this system last
+/// ran.
+fn send_component_events<C: Component>(
+    changed: Query<(Entity, Ref<C>), Changed<C>>,
+    mut removed: RemovedComponents<C>,
+    mut added_events: EventWriter<ComponentAdded<C>>,
+    mut changed_events: EventWriter<ComponentChanged<C>>,
+    mut removed_events: EventWriter<ComponentRemoved<C>>,
+) {
+    for (entity, component) in &changed {
+        if component.is_added() {
+            added_events.send(ComponentAdded::new(entity));
+        } else {
+            changed_events.send(ComponentChanged::new(entity));
+        }
+    }
+    removed_events.send_batch(removed.iter().map(ComponentRemoved::new));
+}
@@ -0,0 +1,151 @@
+//! A reflectable summary of the listeners on each entity, for inspectors.
+//!
+//! Callback systems cannot be reflected, so inspectors like `bevy-inspector-egui` can only show
+//! [`On<E>`] and [`Listeners<E>`] as opaque components. With
+//! [`EventListenerPlugin::reflect_listener_info`](crate::EventListenerPlugin::reflect_listener_info),
+//! every entity with listeners for events of type `E` also gets a [`ListenerInfo`] component,
+//! which is registered for reflection, and lists the event type, registered name, enabled state
+//! and statistics of each of its listeners. A [`ListenerSummary`] is also available for any
+//! listener with [`On::summary`], and is used by the `Debug` implementation of [`On<E>`].
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     listener_info::ListenerInfo,
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Clone, Event)]
+//! # struct Click {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! let mut app = App::new();
+//! app.add_plugins(EventListenerPlugin::<Click>::default().reflect_listener_info());
+//!
+//! let button = app.world.spawn(On::<Click>::run(|| {})).id();
+//! app.world.send_event(Click { target: button });
+//! app.update();
+//!
+//! let info = app.world.get::<ListenerInfo>(button).unwrap();
+//! assert_eq!(info.listeners[0].times_fired, 1);
+//! ```
+
+use bevy_ecs::prelude::*;
+use bevy_reflect::Reflect;
+use bevy_utils::HashSet;
+
+use crate::event_listener::{EntityEvent, Listeners, On};
+
+/// A reflectable summary of an [`On<E>`] listener, see the [module docs](self).
+#[derive(Reflect, Clone, PartialEq, Eq, Debug, Default)]
+pub struct ListenerSummary {
+    /// The name of the event type `E`.
+    pub event_type: String,
+    /// The name the listener was registered under in a
+    /// [`CallbackRegistry`](crate::callback_registry::CallbackRegistry), if it was created by one.
+    pub registered_name: Option<String>,
+    /// Can the listener be triggered? See [`On::set_enabled`].
+    pub enabled: bool,
+    /// The number of times the listener was triggered.
+    pub times_fired: u64,
+    /// The frame the listener was last triggered in.
+    pub last_fired_frame: Option<u32>,
+}
+
+impl<E: EntityEvent> On<E> {
+    /// A reflectable summary of this listener, see the [module docs](self).
+    pub fn summary(&self) -> ListenerSummary {
+        ListenerSummary {
+            event_type: std::any::type_name::<E>().to_owned(),
+            registered_name: self.registered_name().map(str::to_owned),
+            enabled: self.is_enabled(),
+            times_fired: self.times_fired(),
+            last_fired_frame: self.last_fired_frame(),
+        }
+    }
+}
+
+impl<E: EntityEvent> std::fmt::Debug for On<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let summary = self.summary();
+        f.debug_struct("On")
+            .field("event_type", &summary.event_type)
+            .field("registered_name", &summary.registered_name)
+            .field("enabled", &summary.enabled)
+            .field("times_fired", &summary.times_fired)
+            .field("last_fired_frame", &summary.last_fired_frame)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A reflectable component listing a [`ListenerSummary`] for each listener on its entity, see the
+/// [module docs](self).
+#[derive(Component, Reflect, Clone, PartialEq, Eq, Debug, Default)]
+#[reflect(Component)]
+pub struct ListenerInfo {
+    /// The listeners on the entity, grouped by event type. The listeners of each event type are in
+    /// the same order as in its [`Listeners<E>`], after its [`On<E>`].
+    pub listeners: Vec<ListenerSummary>,
+}
+
+/// Update the [`ListenerInfo`] of every entity whose listeners of events of type `E` were added,
+/// changed or removed. This is added by
+/// [`EventListenerPlugin::reflect_listener_info`](crate::EventListenerPlugin::reflect_listener_info).
+#[allow(clippy::type_complexity)]
+pub fn sync_listener_info<E: EntityEvent>(
+    mut commands: Commands,
+    changed: Query<Entity, Or<(Changed<On<E>>, Changed<Listeners<E>>)>>,
+    mut removed_on: RemovedComponents<On<E>>,
+    mut removed_listeners: RemovedComponents<Listeners<E>>,
+    mut entities: Query<(
+        Option<&On<E>>,
+        Option<&Listeners<E>>,
+        Option<&mut ListenerInfo>,
+    )>,
+) {
+    let event_type = std::any::type_name::<E>();
+    let updated: HashSet<Entity> = changed
+        .iter()
+        .chain(removed_on.iter())
+        .chain(removed_listeners.iter())
+        .collect();
+    for entity in updated {
+        let Ok((on, listeners, info)) = entities.get_mut(entity) else {
+            continue;
+        };
+        let summaries = on.into_iter().map(On::summary).chain(
+            listeners
+                .into_iter()
+                .flat_map(|listeners| listeners.ids().filter_map(|id| listeners.get(id)))
+                .map(On::summary),
+        );
+        if let Some(mut info) = info {
+            info.listeners
+                .retain(|summary| summary.event_type != event_type);
+            info.listeners.extend(summaries);
+            continue;
+        }
+        let listeners: Vec<_> = summaries.collect();
+        if listeners.is_empty() {
+            continue;
+        }
+        // The listeners of another event type may also be inserting a `ListenerInfo` this frame.
+        commands.add(move |world: &mut World| {
+            let Some(mut entity) = world.get_entity_mut(entity) else {
+                return;
+            };
+            match entity.get_mut::<ListenerInfo>() {
+                Some(mut info) => info.listeners.extend(listeners),
+                None => {
+                    entity.insert(ListenerInfo { listeners });
+                }
+            }
+        });
+    }
+}
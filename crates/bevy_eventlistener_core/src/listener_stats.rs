@@ -0,0 +1,149 @@
+//! Cheap statistics about the listeners of each event type, updated every frame.
+//!
+//! With [`EventListenerPlugin::with_listener_stats`](crate::EventListenerPlugin::with_listener_stats),
+//! the [`ListenerStats<E>`] resource counts the listeners for events of type `E` that are enabled,
+//! and the events dispatched and callbacks run in the last frame. Unlike the statistics of the
+//! `diagnostics` feature, time is not measured, and listeners are only counted again on the
+//! entities whose listeners changed, so this is cheap enough to leave on in release builds, to
+//! drive adaptive throttling or analytics.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     listener_stats::ListenerStats,
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Clone, Event)]
+//! # struct Click {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! let mut app = App::new();
+//! app.add_plugins(EventListenerPlugin::<Click>::default().with_listener_stats());
+//!
+//! let button = app.world.spawn(On::<Click>::run(|| {})).id();
+//! app.world.send_event(Click { target: button });
+//! app.world.send_event(Click { target: button });
+//! app.update();
+//!
+//! let stats = app.world.resource::<ListenerStats<Click>>();
+//! assert_eq!(stats.active_listeners(), 1);
+//! assert_eq!(stats.events_last_frame(), 2);
+//! assert_eq!(stats.callbacks_last_frame(), 2);
+//! ```
+
+use bevy_ecs::prelude::*;
+use bevy_utils::HashMap;
+
+use crate::{
+    event_dispatcher::EventDispatcher,
+    event_listener::{EntityEvent, GlobalListeners, Listeners, On},
+};
+
+/// A resource with statistics about the listeners for events of type `E`, see the
+/// [module docs](self).
+#[derive(Resource)]
+pub struct ListenerStats<E: EntityEvent> {
+    active_listeners: usize,
+    events_last_frame: usize,
+    callbacks_last_frame: usize,
+    /// The number of enabled listeners on each entity with listeners.
+    per_entity: HashMap<Entity, usize>,
+    /// The number of enabled [`GlobalListeners<E>`].
+    global: usize,
+    /// The totals of the [`EventDispatcher<E>`] when the statistics were last updated.
+    last_events: usize,
+    last_callbacks: usize,
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E: EntityEvent> Default for ListenerStats<E> {
+    fn default() -> Self {
+        Self {
+            active_listeners: 0,
+            events_last_frame: 0,
+            callbacks_last_frame: 0,
+            per_entity: HashMap::new(),
+            global: 0,
+            last_events: 0,
+            last_callbacks: 0,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent> ListenerStats<E> {
+    /// The number of enabled listeners, on entities and in the [`GlobalListeners<E>`]. Listeners
+    /// in disabled [`ListenerGroups`](crate::event_listener::ListenerGroups) are still counted.
+    pub fn active_listeners(&self) -> usize {
+        self.active_listeners
+    }
+
+    /// The number of events propagated in the last frame. Events with multiple
+    /// [`targets`](EntityEvent::targets) are counted once for each target.
+    pub fn events_last_frame(&self) -> usize {
+        self.events_last_frame
+    }
+
+    /// The number of callbacks run in the last frame.
+    pub fn callbacks_last_frame(&self) -> usize {
+        self.callbacks_last_frame
+    }
+
+    /// Update the statistics, once the events of the frame have been dispatched. This is added by
+    /// [`EventListenerPlugin::with_listener_stats`](crate::EventListenerPlugin::with_listener_stats).
+    #[allow(clippy::type_complexity)]
+    pub fn update(
+        mut stats: ResMut<ListenerStats<E>>,
+        dispatcher: Res<EventDispatcher<E>>,
+        global: Res<GlobalListeners<E>>,
+        changed: Query<Entity, Or<(Changed<On<E>>, Changed<Listeners<E>>)>>,
+        mut removed_on: RemovedComponents<On<E>>,
+        mut removed_listeners: RemovedComponents<Listeners<E>>,
+        entities: Query<(Option<&On<E>>, Option<&Listeners<E>>)>,
+    ) {
+        let stats = stats.as_mut();
+        let total_events = dispatcher.total_events();
+        let total_callbacks = dispatcher.total_listeners_triggered();
+        stats.events_last_frame = total_events - stats.last_events;
+        stats.callbacks_last_frame = total_callbacks - stats.last_callbacks;
+        stats.last_events = total_events;
+        stats.last_callbacks = total_callbacks;
+
+        for entity in changed
+            .iter()
+            .chain(removed_on.iter())
+            .chain(removed_listeners.iter())
+        {
+            let count = entities.get(entity).map_or(0, |(on, listeners)| {
+                enabled(on.into_iter().chain(listeners.into_iter().flat_map(listed)))
+            });
+            let old = match count {
+                0 => stats.per_entity.remove(&entity),
+                count => stats.per_entity.insert(entity, count),
+            };
+            stats.active_listeners = stats.active_listeners + count - old.unwrap_or(0);
+        }
+        if global.is_changed() {
+            let count = enabled(listed(&global.listeners));
+            stats.active_listeners = stats.active_listeners + count - stats.global;
+            stats.global = count;
+        }
+    }
+}
+
+/// The number of enabled listeners in `listeners`.
+fn enabled<'a, E: EntityEvent>(listeners: impl Iterator<Item = &'a On<E>>) -> usize {
+    listeners.filter(|listener| listener.is_enabled()).count()
+}
+
+/// The listeners in a [`Listeners<E>`].
+fn listed<E: EntityEvent>(listeners: &Listeners<E>) -> impl Iterator<Item = &On<E>> {
+    listeners.listeners.iter().map(|(_, listener)| listener)
+}
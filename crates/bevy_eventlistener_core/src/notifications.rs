@@ -0,0 +1,150 @@
+//! Events sent when listeners are added to or removed from entities.
+//!
+//! With [`EventListenerPlugin::notify_listener_changes`](crate::EventListenerPlugin::notify_listener_changes),
+//! a [`ListenerAdded`] event is sent whenever an [`On<E>`] or [`Listeners<E>`] component is added
+//! to an entity, and a [`ListenerRemoved`] event whenever one is removed, including when its entity
+//! is despawned. These events are not generic, so a single system can keep track of the listeners
+//! of every event type, to maintain an index, update a picking backend, or warn about listeners on
+//! entities that are not part of any hierarchy.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     notifications::{ListenerAdded, ListenerRemoved},
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Clone, Event)]
+//! # struct Click {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! fn log_listeners(
+//!     mut added: EventReader<ListenerAdded>,
+//!     mut removed: EventReader<ListenerRemoved>,
+//! ) {
+//!     for added in added.iter() {
+//!         println!("{:?} now listens to {}", added.entity, added.event_type_name);
+//!     }
+//!     for removed in removed.iter() {
+//!         println!("{:?} stopped listening to {}", removed.entity, removed.event_type_name);
+//!     }
+//! }
+//!
+//! let mut app = App::new();
+//! app.add_plugins(EventListenerPlugin::<Click>::default().notify_listener_changes())
+//!     .add_systems(Update, log_listeners);
+//! app.world.spawn(On::<Click>::run(|| {}));
+//! app.update();
+//!
+//! let added = app.world.resource::<Events<ListenerAdded>>();
+//! let added = added.iter_current_update_events().next().unwrap();
+//! assert!(added.is::<Click>());
+//! ```
+
+use std::any::TypeId;
+
+use bevy_ecs::prelude::*;
+
+use crate::event_listener::{EntityEvent, Listeners, On};
+
+/// Which listener component was added or removed.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ListenerComponent {
+    /// An [`On<E>`] component.
+    On,
+    /// A [`Listeners<E>`] component.
+    Listeners,
+}
+
+/// Sent when an [`On<E>`] or [`Listeners<E>`] component is added to an entity, see the
+/// [module docs](self).
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ListenerAdded {
+    /// The entity the component was added to.
+    pub entity: Entity,
+    /// The component that was added.
+    pub component: ListenerComponent,
+    /// The [`TypeId`] of the event type `E`.
+    pub event_type: TypeId,
+    /// The name of the event type `E`.
+    pub event_type_name: &'static str,
+}
+
+impl ListenerAdded {
+    /// Returns `true` if the listener is for events of type `E`.
+    pub fn is<E: EntityEvent>(&self) -> bool {
+        self.event_type == TypeId::of::<E>()
+    }
+}
+
+/// Sent when an [`On<E>`] or [`Listeners<E>`] component is removed from an entity, or when the
+/// entity is despawned, see the [module docs](self).
+#[derive(Event, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ListenerRemoved {
+    /// The entity the component was removed from.
+    pub entity: Entity,
+    /// The component that was removed.
+    pub component: ListenerComponent,
+    /// The [`TypeId`] of the event type `E`.
+    pub event_type: TypeId,
+    /// The name of the event type `E`.
+    pub event_type_name: &'static str,
+}
+
+impl ListenerRemoved {
+    /// Returns `true` if the listener was for events of type `E`.
+    pub fn is<E: EntityEvent>(&self) -> bool {
+        self.event_type == TypeId::of::<E>()
+    }
+}
+
+/// A system that sends a [`ListenerAdded`] or [`ListenerRemoved`] event for every listener
+/// component of events of type `E` that was added or removed since it last ran. This is added by
+/// [`EventListenerPlugin::notify_listener_changes`](crate::EventListenerPlugin::notify_listener_changes).
+pub fn notify_listener_changes<E: EntityEvent>(
+    added_on: Query<Entity, Added<On<E>>>,
+    added_listeners: Query<Entity, Added<Listeners<E>>>,
+    mut removed_on: RemovedComponents<On<E>>,
+    mut removed_listeners: RemovedComponents<Listeners<E>>,
+    mut added: EventWriter<ListenerAdded>,
+    mut removed: EventWriter<ListenerRemoved>,
+) {
+    let event_type = TypeId::of::<E>();
+    let event_type_name = std::any::type_name::<E>();
+    let added_components = added_on
+        .iter()
+        .map(|entity| (entity, ListenerComponent::On))
+        .chain(
+            added_listeners
+                .iter()
+                .map(|entity| (entity, ListenerComponent::Listeners)),
+        );
+    added.send_batch(added_components.map(|(entity, component)| ListenerAdded {
+        entity,
+        component,
+        event_type,
+        event_type_name,
+    }));
+    let removed_components = removed_on
+        .iter()
+        .map(|entity| (entity, ListenerComponent::On))
+        .chain(
+            removed_listeners
+                .iter()
+                .map(|entity| (entity, ListenerComponent::Listeners)),
+        );
+    removed.send_batch(
+        removed_components.map(|(entity, component)| ListenerRemoved {
+            entity,
+            component,
+            event_type,
+            event_type_name,
+        }),
+    );
+}
@@ -0,0 +1,168 @@
+//! A catch-all listener that is triggered by every event that reaches its entity, for debugging
+//! overlays, analytics, and other tooling that should not need to know every event type.
+//!
+//! An [`OnAny`] component runs its callback for every event reaching its entity, of any type
+//! registered with an [`OnAnyPlugin<E>`]. The event is exposed as a `&dyn Reflect` in an
+//! [`AnyEvent`], along with its type and how it reached the listener.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::EntityEvent,
+//! #     on_any::{AnyEvent, OnAny, OnAnyPlugin},
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_reflect::Reflect;
+//! #[derive(Clone, Event, Reflect)]
+//! struct Click {
+//!     target: Entity,
+//! }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! # #[derive(Resource, Default)]
+//! # struct Seen(Vec<String>);
+//!
+//! let mut app = App::new();
+//! app.add_plugins((
+//!     EventListenerPlugin::<Click>::default(),
+//!     OnAnyPlugin::<Click>::default(),
+//! ))
+//! .init_resource::<Seen>();
+//!
+//! let button = app
+//!     .world
+//!     .spawn(OnAny::run(|world: &mut World, event: &AnyEvent| {
+//!         let name = event.type_name.rsplit("::").next().unwrap_or_default();
+//!         world.resource_mut::<Seen>().0.push(name.to_string());
+//!     }))
+//!     .id();
+//!
+//! app.world.send_event(Click { target: button });
+//! app.update();
+//! assert_eq!(app.world.resource::<Seen>().0, ["Click"]);
+//! ```
+
+use std::any::TypeId;
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_reflect::Reflect;
+
+use crate::{
+    callbacks::{EventPhase, ListenerInput},
+    event_listener::{AddListener, EntityEvent, On},
+    EventListenerSet,
+};
+
+/// The callback of an [`OnAny`] listener.
+type AnyCallback = Box<dyn FnMut(&mut World, &AnyEvent) + Send + Sync>;
+
+/// Lets [`OnAny`] listeners be triggered by events of type `E`, see the [module docs](self).
+///
+/// Entities with an [`OnAny`] component are given a listener for `E` in the [`PreUpdate`]
+/// schedule, before the [`EventListenerSet`], so they are triggered by events in the same frame
+/// they were spawned.
+pub struct OnAnyPlugin<E> {
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E> Default for OnAnyPlugin<E> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent + Reflect> Plugin for OnAnyPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (OnAny::attach::<E>, apply_deferred)
+                .chain()
+                .before(EventListenerSet),
+        );
+    }
+}
+
+/// An event of any type that reached an [`OnAny`] listener.
+pub struct AnyEvent<'a> {
+    /// The event data.
+    pub event: &'a dyn Reflect,
+    /// The type of the event.
+    pub type_id: TypeId,
+    /// The name of the type of the event, as returned by [`std::any::type_name`].
+    pub type_name: &'static str,
+    /// The entity the event is propagating from.
+    pub target: Entity,
+    /// The entity the [`OnAny`] listener is on.
+    pub listener: Entity,
+    /// The phase of propagation the event reached the listener in.
+    pub phase: EventPhase,
+}
+
+/// A listener that is triggered by every event reaching its entity, of any type registered with an
+/// [`OnAnyPlugin`], see the [module docs](self).
+///
+/// The listener is triggered as if it was a bubbling listener for each event type, with the default
+/// priority. It cannot change the events, or stop their propagation.
+#[derive(Component)]
+pub struct OnAny {
+    /// The callback, which is taken out while it runs.
+    callback: Option<AnyCallback>,
+}
+
+impl OnAny {
+    /// Run the `callback` every time an event reaches this entity. The callback is an arbitrary
+    /// closure with exclusive access to the [`World`].
+    pub fn run(callback: impl FnMut(&mut World, &AnyEvent) + Send + Sync + 'static) -> Self {
+        Self {
+            callback: Some(Box::new(callback)),
+        }
+    }
+
+    /// Give each new [`OnAny`] a listener for events of type `E`, which is removed along with the
+    /// [`OnAny`] component. This is added by the [`OnAnyPlugin<E>`].
+    pub fn attach<E: EntityEvent + Reflect>(
+        mut commands: Commands,
+        added: Query<Entity, Added<OnAny>>,
+    ) {
+        for entity in &added {
+            let listener = On::<E>::run_with_input(forward::<E>).while_present::<OnAny>();
+            commands.add(AddListener::new(entity, listener));
+        }
+    }
+}
+
+/// Run the callback of the [`OnAny`] on the entity listening for the event.
+fn forward<E: EntityEvent + Reflect>(
+    In(input): In<ListenerInput<E>>,
+    world: &mut World,
+) -> ListenerInput<E> {
+    let listener = input.listener();
+    // The callback is missing if this event was sent and dispatched by the callback itself.
+    let Some(mut callback) = world
+        .get_mut::<OnAny>(listener)
+        .and_then(|mut on_any| on_any.callback.take())
+    else {
+        return input;
+    };
+    let event = AnyEvent {
+        event: input.as_reflect(),
+        type_id: TypeId::of::<E>(),
+        type_name: std::any::type_name::<E>(),
+        target: input.target(),
+        listener,
+        phase: input.phase(),
+    };
+    callback(world, &event);
+    if let Some(mut on_any) = world.get_mut::<OnAny>(listener) {
+        // Keep the callback that replaced this one while it ran, if any.
+        on_any.callback.get_or_insert(callback);
+    }
+    input
+}
@@ -0,0 +1,189 @@
+//! Bubbling collision events, converted from the events of a physics engine.
+//!
+//! Add the [`PhysicsEventsPlugin`] to send a [`CollisionStarted`] and [`CollisionEnded`] event
+//! targeting each of the two colliding entities, whenever the physics engine reports that they
+//! started or stopped touching. These events bubble up the hierarchy like any other
+//! [`EntityEvent`], so a listener on a vehicle or character is triggered by collisions with any of
+//! its child colliders.
+//!
+//! The collision events of each physics engine are converted when its feature is enabled:
+//!
+//! - `rapier2d` and `rapier3d` convert the `CollisionEvent`s of `bevy_rapier2d` and
+//!   `bevy_rapier3d`. Rapier only reports collisions for colliders with
+//!   `ActiveEvents::COLLISION_EVENTS`.
+//! - `xpbd_2d` and `xpbd_3d` convert the `CollisionStarted` and `CollisionEnded` events of
+//!   `bevy_xpbd_2d` and `bevy_xpbd_3d`.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::Listener,
+//! #     event_listener::On,
+//! #     physics::{CollisionStarted, PhysicsEventsPlugin},
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! fn spawn_car(mut commands: Commands) {
+//!     commands.spawn(On::<CollisionStarted>::run(
+//!         |collision: Listener<CollisionStarted>| {
+//!             println!("{:?} hit {:?}", collision.target, collision.other);
+//!         },
+//!     ));
+//! }
+//!
+//! App::new()
+//!     .add_plugins(PhysicsEventsPlugin)
+//!     .add_systems(Startup, spawn_car);
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+
+use crate::{event_listener::EntityEvent, EventListenerPlugin, EventListenerSet};
+
+macro_rules! collision_event {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+        pub struct $name {
+            /// The collider entity this event was sent to.
+            pub target: Entity,
+            /// The other collider entity involved in the collision.
+            pub other: Entity,
+        }
+
+        impl EntityEvent for $name {
+            fn target(&self) -> Entity {
+                self.target
+            }
+        }
+    };
+}
+
+collision_event!(
+    /// Sent to both colliders when they start touching, see the [module docs](self).
+    CollisionStarted
+);
+collision_event!(
+    /// Sent to both colliders when they stop touching, see the [module docs](self).
+    CollisionEnded
+);
+
+/// Adds an [`EventListenerPlugin`] for [`CollisionStarted`] and [`CollisionEnded`], and sends them
+/// when the physics engines enabled by the features of this crate report a collision, see the
+/// [module docs](self).
+///
+/// The events are sent in the [`PreUpdate`] schedule, before the [`EventListenerSet`]. Physics
+/// engines step in the [`PostUpdate`] schedule by default, so collisions are dispatched at the
+/// start of the next frame.
+pub struct PhysicsEventsPlugin;
+
+impl Plugin for PhysicsEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            EventListenerPlugin::<CollisionStarted>::default(),
+            EventListenerPlugin::<CollisionEnded>::default(),
+        ));
+        #[cfg(feature = "rapier2d")]
+        app.add_systems(
+            PreUpdate,
+            rapier2d::send_collision_events.before(EventListenerSet),
+        );
+        #[cfg(feature = "rapier3d")]
+        app.add_systems(
+            PreUpdate,
+            rapier3d::send_collision_events.before(EventListenerSet),
+        );
+        #[cfg(feature = "xpbd_2d")]
+        app.add_systems(
+            PreUpdate,
+            xpbd_2d::send_collision_events.before(EventListenerSet),
+        );
+        #[cfg(feature = "xpbd_3d")]
+        app.add_systems(
+            PreUpdate,
+            xpbd_3d::send_collision_events.before(EventListenerSet),
+        );
+    }
+}
+
+/// Send the events for a collision between `a` and `b` to both of them.
+fn send_pair<E: EntityEvent>(
+    writer: &mut EventWriter<E>,
+    a: Entity,
+    b: Entity,
+    event: fn(Entity, Entity) -> E,
+) {
+    writer.send(event(a, b));
+    writer.send(event(b, a));
+}
+
+fn started(target: Entity, other: Entity) -> CollisionStarted {
+    CollisionStarted { target, other }
+}
+
+fn ended(target: Entity, other: Entity) -> CollisionEnded {
+    CollisionEnded { target, other }
+}
+
+macro_rules! rapier_backend {
+    ($feature:literal, $backend:ident, $krate:ident) => {
+        #[cfg(feature = $feature)]
+        mod $backend {
+            use bevy_ecs::prelude::*;
+            use $krate::prelude::CollisionEvent;
+
+            use super::{send_pair, CollisionEnded, CollisionStarted};
+
+            /// Send the events for every rapier collision since this system last ran.
+            pub(super) fn send_collision_events(
+                mut collisions: EventReader<CollisionEvent>,
+                mut started: EventWriter<CollisionStarted>,
+                mut ended: EventWriter<CollisionEnded>,
+            ) {
+                for collision in collisions.iter() {
+                    match *collision {
+                        CollisionEvent::Started(a, b, _) => {
+                            send_pair(&mut started, a, b, super::started)
+                        }
+                        CollisionEvent::Stopped(a, b, _) => {
+                            send_pair(&mut ended, a, b, super::ended)
+                        }
+                    }
+                }
+            }
+        }
+    };
+}
+
+rapier_backend!("rapier2d", rapier2d, bevy_rapier2d);
+rapier_backend!("rapier3d", rapier3d, bevy_rapier3d);
+
+macro_rules! xpbd_backend {
+    ($feature:literal, $backend:ident, $krate:ident) => {
+        #[cfg(feature = $feature)]
+        mod $backend {
+            use bevy_ecs::prelude::*;
+            use $krate::prelude as xpbd;
+
+            use super::{send_pair, CollisionEnded, CollisionStarted};
+
+            /// Send the events for every xpbd collision since this system last ran.
+            pub(super) fn send_collision_events(
+                mut collisions_started: EventReader<xpbd::CollisionStarted>,
+                mut collisions_ended: EventReader<xpbd::CollisionEnded>,
+                mut started: EventWriter<CollisionStarted>,
+                mut ended: EventWriter<CollisionEnded>,
+            ) {
+                for xpbd::CollisionStarted(a, b) in collisions_started.iter() {
+                    send_pair(&mut started, *a, *b, super::started);
+                }
+                for xpbd::CollisionEnded(a, b) in collisions_ended.iter() {
+                    send_pair(&mut ended, *a, *b, super::ended);
+                }
+            }
+        }
+    };
+}
+
+xpbd_backend!("xpbd_2d", xpbd_2d, bevy_xpbd_2d);
+xpbd_backend!("xpbd_3d", xpbd_3d, bevy_xpbd_3d);
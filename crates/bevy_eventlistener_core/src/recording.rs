@@ -0,0 +1,239 @@
+//! Recording the events dispatched in one run of an app, and replaying them in a later run.
+//!
+//! The [`RecordingPlugin<E>`] adds an [`EventRecorder<E>`], which captures every event of type `E`
+//! along with its target and the frame it was dispatched in, counted from the start of the
+//! recording. The recording is an [`EventLog<E>`], which can be saved with any serde format. Later,
+//! the log can be handed to the [`EventReplayer<E>`], which sends each event again in the same
+//! frame, relative to the start of the replay. This makes it possible to reproduce a bug from a
+//! recording of the events that caused it.
+//!
+//! Replays are only deterministic if the app is. In particular, recorded events refer to entities
+//! by their [`Entity`] id, so the app must spawn the same entities in the same order when the log
+//! is replayed. Only events that drive the app, such as input events, should be recorded. Events
+//! sent by listeners will be sent again by the same listeners when the recorded events are
+//! replayed.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     recording::{EventRecorder, EventReplayer, RecordingPlugin},
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Clone, Event, Serialize, Deserialize)]
+//! struct Press {
+//!     target: Entity,
+//! }
+//! # impl EntityEvent for Press {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! # #[derive(Resource, Default)]
+//! # struct Presses(usize);
+//!
+//! let mut app = App::new();
+//! app.add_plugins((
+//!     EventListenerPlugin::<Press>::default(),
+//!     RecordingPlugin::<Press>::default(),
+//! ));
+//! app.init_resource::<Presses>();
+//! let button = app
+//!     .world
+//!     .spawn(On::<Press>::run(|mut presses: ResMut<Presses>| presses.0 += 1))
+//!     .id();
+//!
+//! app.world.resource_mut::<EventRecorder<Press>>().start();
+//! app.update();
+//! app.world.send_event(Press { target: button });
+//! app.update();
+//! let log = app.world.resource_mut::<EventRecorder<Press>>().stop();
+//! assert_eq!(log.events[0].frame, 1);
+//!
+//! // Later, usually in another run of the app, after loading the log:
+//! app.world.resource_mut::<EventReplayer<Press>>().play(log);
+//! app.update();
+//! app.update();
+//! assert_eq!(app.world.resource::<Presses>().0, 2);
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::{event_listener::EntityEvent, EventDispatchSet};
+
+/// Adds the [`EventRecorder<E>`] and [`EventReplayer<E>`] resources, see the
+/// [module docs](self).
+///
+/// Events are recorded and replayed in the [`PreUpdate`] schedule, right before the
+/// [`EventDispatchSet<E>`]. Replayed events are dispatched in the frame they are sent.
+pub struct RecordingPlugin<E> {
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E> Default for RecordingPlugin<E> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent> Plugin for RecordingPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EventRecorder<E>>()
+            .init_resource::<EventReplayer<E>>()
+            .add_systems(
+                PreUpdate,
+                (EventReplayer::<E>::replay, EventRecorder::<E>::record)
+                    .chain()
+                    .before(EventDispatchSet::<E>::default()),
+            );
+    }
+}
+
+/// An event captured by an [`EventRecorder`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordedEvent<E> {
+    /// The frame the event was dispatched in, counted from the start of the recording.
+    pub frame: u64,
+    /// The target of the event.
+    pub target: Entity,
+    /// The event.
+    pub event: E,
+}
+
+/// The events captured by an [`EventRecorder`], in the order they were sent.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventLog<E> {
+    /// The recorded events.
+    pub events: Vec<RecordedEvent<E>>,
+}
+
+impl<E> Default for EventLog<E> {
+    fn default() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+/// A resource that captures the events of type `E` while it is recording.
+#[derive(Resource)]
+pub struct EventRecorder<E: EntityEvent> {
+    recording: bool,
+    frame: u64,
+    log: EventLog<E>,
+}
+
+impl<E: EntityEvent> Default for EventRecorder<E> {
+    fn default() -> Self {
+        Self {
+            recording: false,
+            frame: 0,
+            log: EventLog::default(),
+        }
+    }
+}
+
+impl<E: EntityEvent> EventRecorder<E> {
+    /// Start a new recording, discarding any events recorded so far. The frame this is called in,
+    /// or the next frame if it is called after the events were recorded, is frame 0.
+    pub fn start(&mut self) {
+        self.recording = true;
+        self.frame = 0;
+        self.log.events.clear();
+    }
+
+    /// Stop recording, and return the events recorded since the recording was started.
+    pub fn stop(&mut self) -> EventLog<E> {
+        self.recording = false;
+        std::mem::take(&mut self.log)
+    }
+
+    /// Returns `true` if events are being recorded.
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// The events recorded so far.
+    pub fn log(&self) -> &EventLog<E> {
+        &self.log
+    }
+
+    /// Capture the events sent this frame, if recording.
+    pub fn record(mut recorder: ResMut<EventRecorder<E>>, mut events: EventReader<E>) {
+        if !recorder.recording {
+            events.clear();
+            return;
+        }
+        let frame = recorder.frame;
+        for event in events.iter() {
+            recorder.log.events.push(RecordedEvent {
+                frame,
+                target: event.target(),
+                event: event.clone(),
+            });
+        }
+        recorder.frame += 1;
+    }
+}
+
+/// A resource that sends the events of an [`EventLog<E>`] again, in the same frames they were
+/// recorded in.
+#[derive(Resource)]
+pub struct EventReplayer<E: EntityEvent> {
+    frame: u64,
+    next: usize,
+    log: Option<EventLog<E>>,
+}
+
+impl<E: EntityEvent> Default for EventReplayer<E> {
+    fn default() -> Self {
+        Self {
+            frame: 0,
+            next: 0,
+            log: None,
+        }
+    }
+}
+
+impl<E: EntityEvent> EventReplayer<E> {
+    /// Start replaying `log`, replacing any replay in progress. The first frame the replayer runs
+    /// in is frame 0.
+    pub fn play(&mut self, log: EventLog<E>) {
+        self.frame = 0;
+        self.next = 0;
+        self.log = Some(log);
+    }
+
+    /// Stop the replay in progress, if any.
+    pub fn stop(&mut self) {
+        self.log = None;
+    }
+
+    /// Returns `true` if a log is being replayed. The replay ends once every event has been sent.
+    pub fn is_playing(&self) -> bool {
+        self.log.is_some()
+    }
+
+    /// Send the events recorded in the current frame of the replay.
+    pub fn replay(mut replayer: ResMut<EventReplayer<E>>, mut events: EventWriter<E>) {
+        let EventReplayer { frame, next, log } = replayer.as_mut();
+        let Some(replay) = log else {
+            return;
+        };
+        while let Some(recorded) = replay.events.get(*next) {
+            if recorded.frame > *frame {
+                break;
+            }
+            events.send(recorded.event.clone());
+            *next += 1;
+        }
+        *frame += 1;
+        if *next == replay.events.len() {
+            *log = None;
+        }
+    }
+}
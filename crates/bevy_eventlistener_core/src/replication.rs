@@ -0,0 +1,241 @@
+//! Forwarding targeted events from a server to its clients.
+//!
+//! The [`ReplicationPlugin<E>`] does not send anything over the network itself. On the server,
+//! every event of type `E` is copied into the [`OutgoingEvents<E>`] resource, which the app drains
+//! and sends to its clients with any serde format and transport. On the client, received events
+//! are pushed into the [`IncomingEvents<E>`] resource. Their entities are then mapped from server
+//! entities to local entities with the [`NetworkEntityMap`], and the events are dispatched
+//! through the client's own listeners, exactly as if they had been sent locally.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::EntityEvent,
+//! #     replication::{IncomingEvents, NetworkEntityMap, ReplicatedEvent, ReplicationPlugin},
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Clone, Event, Serialize, Deserialize)]
+//! struct Press {
+//!     target: Entity,
+//! }
+//! # impl EntityEvent for Press {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//!
+//! impl ReplicatedEvent for Press {
+//!     fn map_entities(&mut self, map: &mut dyn FnMut(Entity) -> Option<Entity>) -> Option<()> {
+//!         self.target = map(self.target)?;
+//!         Some(())
+//!     }
+//! }
+//!
+//! let mut client = App::new();
+//! client.add_plugins((
+//!     EventListenerPlugin::<Press>::default(),
+//!     ReplicationPlugin::<Press>::client(),
+//! ));
+//! # let server_button = Entity::from_raw(7);
+//!
+//! // When the server's button is replicated to the client:
+//! let button = client.world.spawn_empty().id();
+//! client
+//!     .world
+//!     .resource_mut::<NetworkEntityMap>()
+//!     .insert(server_button, button);
+//!
+//! // When a message is received from the server, and deserialized:
+//! let press = Press {
+//!     target: server_button,
+//! };
+//! client.world.resource_mut::<IncomingEvents<Press>>().push(press);
+//! client.update();
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::error;
+use bevy_utils::HashMap;
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::{event_listener::EntityEvent, EventListenerSet};
+
+/// An [`EntityEvent`] that can be sent from a server to its clients.
+pub trait ReplicatedEvent: EntityEvent + Serialize + DeserializeOwned {
+    /// Replace every entity stored in this event, including its target, with the entity returned
+    /// by `map`. If `map` returns `None` for any entity, this should also return `None`, and the
+    /// event is discarded.
+    fn map_entities(&mut self, map: &mut dyn FnMut(Entity) -> Option<Entity>) -> Option<()>;
+}
+
+/// Which side of the connection a [`ReplicationPlugin`] is added to.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ReplicationRole {
+    /// Events sent in this app are copied into the [`OutgoingEvents`].
+    Server,
+    /// Events pushed into the [`IncomingEvents`] are mapped and sent in this app.
+    Client,
+}
+
+/// Forwards events of type `E` from a server to its clients, see the [module docs](self).
+pub struct ReplicationPlugin<E> {
+    role: ReplicationRole,
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E> ReplicationPlugin<E> {
+    /// Copy every event of type `E` into the [`OutgoingEvents<E>`], so they can be sent to
+    /// clients.
+    pub fn server() -> Self {
+        Self {
+            role: ReplicationRole::Server,
+            phantom: std::marker::PhantomData,
+        }
+    }
+
+    /// Send the events pushed into the [`IncomingEvents<E>`] in this app, after mapping their
+    /// entities with the [`NetworkEntityMap`].
+    pub fn client() -> Self {
+        Self {
+            role: ReplicationRole::Client,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: ReplicatedEvent> Plugin for ReplicationPlugin<E> {
+    fn build(&self, app: &mut App) {
+        match self.role {
+            ReplicationRole::Server => {
+                app.init_resource::<OutgoingEvents<E>>().add_systems(
+                    PreUpdate,
+                    OutgoingEvents::<E>::collect.after(EventListenerSet),
+                );
+            }
+            ReplicationRole::Client => {
+                app.init_resource::<IncomingEvents<E>>()
+                    .init_resource::<NetworkEntityMap>()
+                    .add_systems(
+                        PreUpdate,
+                        IncomingEvents::<E>::send.before(EventListenerSet),
+                    );
+            }
+        }
+    }
+}
+
+/// A resource storing the events of type `E` sent on the server, waiting to be sent to clients.
+/// The app is responsible for draining and sending them.
+#[derive(Resource)]
+pub struct OutgoingEvents<E: ReplicatedEvent> {
+    events: Vec<E>,
+}
+
+impl<E: ReplicatedEvent> Default for OutgoingEvents<E> {
+    fn default() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+impl<E: ReplicatedEvent> OutgoingEvents<E> {
+    /// Remove and return the events waiting to be sent, in the order they were sent on the server.
+    pub fn drain(&mut self) -> impl Iterator<Item = E> + '_ {
+        self.events.drain(..)
+    }
+
+    /// Copy the events sent this frame, once they have been dispatched on the server.
+    pub fn collect(mut outgoing: ResMut<OutgoingEvents<E>>, mut events: EventReader<E>) {
+        outgoing.events.extend(events.iter().cloned());
+    }
+}
+
+/// A resource storing the events of type `E` received from the server, waiting to be dispatched.
+/// The app is responsible for receiving and pushing them.
+#[derive(Resource)]
+pub struct IncomingEvents<E: ReplicatedEvent> {
+    events: Vec<E>,
+}
+
+impl<E: ReplicatedEvent> Default for IncomingEvents<E> {
+    fn default() -> Self {
+        Self { events: Vec::new() }
+    }
+}
+
+impl<E: ReplicatedEvent> IncomingEvents<E> {
+    /// Queue an event received from the server, to be sent in the next frame.
+    pub fn push(&mut self, event: E) {
+        self.events.push(event);
+    }
+
+    /// Map the entities of each received event, and send it so it is dispatched by the client's
+    /// listeners. Events referring to an entity that is not in the [`NetworkEntityMap`] are
+    /// discarded.
+    pub fn send(
+        mut incoming: ResMut<IncomingEvents<E>>,
+        map: Res<NetworkEntityMap>,
+        mut events: EventWriter<E>,
+    ) {
+        for mut event in incoming.events.drain(..) {
+            if event
+                .map_entities(&mut |entity| map.local(entity))
+                .is_none()
+            {
+                #[cfg(feature = "trace")]
+                error!(
+                    "Discarding replicated event {:?} targeting {:?}, which refers to an entity that \
+                    is not in the NetworkEntityMap",
+                    std::any::type_name::<E>(),
+                    event.target(),
+                );
+                continue;
+            }
+            events.send(event);
+        }
+    }
+}
+
+/// A resource mapping the entities of the server to the entities of the client, used to map the
+/// entities in replicated events. The app is responsible for keeping it up to date as entities are
+/// replicated.
+#[derive(Resource, Default, Debug)]
+pub struct NetworkEntityMap {
+    to_local: HashMap<Entity, Entity>,
+    to_remote: HashMap<Entity, Entity>,
+}
+
+impl NetworkEntityMap {
+    /// Map the server entity `remote` to the client entity `local`.
+    pub fn insert(&mut self, remote: Entity, local: Entity) {
+        if let Some(old_local) = self.to_local.insert(remote, local) {
+            self.to_remote.remove(&old_local);
+        }
+        if let Some(old_remote) = self.to_remote.insert(local, remote) {
+            if old_remote != remote {
+                self.to_local.remove(&old_remote);
+            }
+        }
+    }
+
+    /// Remove the mapping of the server entity `remote`, returning the client entity it was mapped
+    /// to.
+    pub fn remove(&mut self, remote: Entity) -> Option<Entity> {
+        let local = self.to_local.remove(&remote)?;
+        self.to_remote.remove(&local);
+        Some(local)
+    }
+
+    /// The client entity mapped to the server entity `remote`.
+    pub fn local(&self, remote: Entity) -> Option<Entity> {
+        self.to_local.get(&remote).copied()
+    }
+
+    /// The server entity mapped to the client entity `local`.
+    pub fn remote(&self, local: Entity) -> Option<Entity> {
+        self.to_remote.get(&local).copied()
+    }
+}
@@ -0,0 +1,266 @@
+//! Saving the events that have not been dispatched yet, and restoring them when a game is loaded.
+//!
+//! Events sent after the events of type `E` were dispatched this frame, events carried over to the
+//! next frame by a [frame budget](crate::EventListenerPlugin::with_frame_budget), events held while
+//! [paused](crate::event_dispatcher::EventDispatchControl), and the [`DelayedEvents<E>`] are all
+//! waiting to be dispatched. If a game is saved at this point,
+//! [`EventSnapshot::take`] removes these events from the world, and returns them as an
+//! [`EventSnapshot<E>`], which can be saved alongside the rest of the game with any serde format.
+//! When the game is loaded, [`EventSnapshot::restore`] sends them again, so they are dispatched as
+//! if the game had never been interrupted.
+//!
+//! The entities of a loaded game are usually not the same as the entities of the game that was
+//! saved, so every entity in the restored events is mapped with a function, such as the
+//! `EntityMap` filled in when a `DynamicScene` is written to the world. Events referring to an
+//! entity that cannot be mapped are discarded.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     event_listener::{EntityEvent, On},
+//! #     snapshot::{EventSnapshot, SnapshotEvent, SnapshotPlugin},
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Clone, Event, Serialize, Deserialize)]
+//! struct Explode {
+//!     target: Entity,
+//! }
+//! # impl EntityEvent for Explode {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//!
+//! impl SnapshotEvent for Explode {
+//!     fn map_entities(&mut self, map: &mut dyn FnMut(Entity) -> Option<Entity>) -> Option<()> {
+//!         self.target = map(self.target)?;
+//!         Some(())
+//!     }
+//! }
+//! # #[derive(Resource, Default)]
+//! # struct Explosions(usize);
+//!
+//! let mut app = App::new();
+//! app.add_plugins((
+//!     EventListenerPlugin::<Explode>::default(),
+//!     SnapshotPlugin::<Explode>::default(),
+//! ));
+//! # app.init_resource::<Explosions>();
+//! let bomb = app.world.spawn_empty().id();
+//! app.world.send_event(Explode { target: bomb });
+//!
+//! // When the game is saved:
+//! let snapshot = EventSnapshot::<Explode>::take(&mut app.world);
+//! assert_eq!(snapshot.events.len(), 1);
+//!
+//! // When the game is loaded, and the bomb was spawned again:
+//! let loaded_bomb = app
+//!     .world
+//!     .spawn(On::<Explode>::run(|mut explosions: ResMut<Explosions>| {
+//!         explosions.0 += 1
+//!     }))
+//!     .id();
+//! snapshot.restore(&mut app.world, &mut |entity| {
+//!     (entity == bomb).then_some(loaded_bomb)
+//! });
+//! app.update();
+//! assert_eq!(app.world.resource::<Explosions>().0, 1);
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::{event::ManualEventReader, prelude::*};
+#[cfg(feature = "trace")]
+use bevy_utils::tracing::error;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    delayed::{Delay, DelayedEvents},
+    event_dispatcher::EventDispatcher,
+    event_listener::EntityEvent,
+    EventDispatchSet, EventListenerSet,
+};
+
+/// An [`EntityEvent`] that can be saved in an [`EventSnapshot`].
+pub trait SnapshotEvent: EntityEvent + Serialize + DeserializeOwned {
+    /// Replace every entity stored in this event, including its target, with the entity returned
+    /// by `map`. If `map` returns `None` for any entity, this should also return `None`, and the
+    /// event is discarded.
+    fn map_entities(&mut self, map: &mut dyn FnMut(Entity) -> Option<Entity>) -> Option<()>;
+}
+
+/// Keeps track of which events of type `E` were already dispatched, so an [`EventSnapshot<E>`]
+/// only contains the events that are still pending, see the [module docs](self).
+///
+/// Without this plugin, every event of type `E` that has not been dropped by the [`Events<E>`]
+/// resource yet is considered pending, including events that were already dispatched.
+pub struct SnapshotPlugin<E> {
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E> Default for SnapshotPlugin<E> {
+    fn default() -> Self {
+        Self {
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: SnapshotEvent> Plugin for SnapshotPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DispatchedEvents<E>>().add_systems(
+            PreUpdate,
+            DispatchedEvents::<E>::mark
+                .in_set(EventListenerSet)
+                .after(EventDispatchSet::<E>::default()),
+        );
+    }
+}
+
+/// A reader that has read every event of type `E` that was dispatched.
+#[derive(Resource)]
+struct DispatchedEvents<E: Event> {
+    reader: ManualEventReader<E>,
+}
+
+impl<E: Event> Default for DispatchedEvents<E> {
+    fn default() -> Self {
+        Self {
+            reader: ManualEventReader::default(),
+        }
+    }
+}
+
+impl<E: Event> DispatchedEvents<E> {
+    /// Read the events dispatched this frame.
+    fn mark(mut dispatched: ResMut<DispatchedEvents<E>>, events: Res<Events<E>>) {
+        dispatched.reader.clear(&events);
+    }
+}
+
+/// The events of type `E` that were waiting to be dispatched when the snapshot was taken, see the
+/// [module docs](self).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EventSnapshot<E> {
+    /// The events that were sent, in the order they were sent, including the events held while
+    /// events of type `E` were paused.
+    pub events: Vec<E>,
+    /// The events that were carried over from the last frame by a frame budget, along with the
+    /// target they were propagating from. These are dispatched before any other events.
+    pub carried: Vec<(E, Entity)>,
+    /// The [`DelayedEvents<E>`], along with the delay left for each of them.
+    pub delayed: Vec<(E, Delay)>,
+}
+
+impl<E> Default for EventSnapshot<E> {
+    fn default() -> Self {
+        Self {
+            events: Vec::new(),
+            carried: Vec::new(),
+            delayed: Vec::new(),
+        }
+    }
+}
+
+impl<E> EventSnapshot<E> {
+    /// The number of events in the snapshot.
+    pub fn len(&self) -> usize {
+        self.events.len() + self.carried.len() + self.delayed.len()
+    }
+
+    /// Returns `true` if no events were waiting to be dispatched.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<E: SnapshotEvent> EventSnapshot<E> {
+    /// Remove the events of type `E` that are waiting to be dispatched from the `world`, and
+    /// return them.
+    ///
+    /// Every event is removed from the [`Events<E>`] resource, so systems that read events of type
+    /// `E` will not see the events that were already dispatched either, if they have not run yet.
+    pub fn take(world: &mut World) -> Self {
+        let mut snapshot = Self::default();
+        let pending = match (
+            world.get_resource::<Events<E>>(),
+            world.get_resource::<DispatchedEvents<E>>(),
+        ) {
+            (Some(events), Some(dispatched)) => dispatched.reader.len(events),
+            (Some(events), None) => events.len(),
+            (None, _) => 0,
+        };
+        if let Some(mut dispatcher) = world.get_resource_mut::<EventDispatcher<E>>() {
+            // Events held while paused were sent before the pending events. Event sources point
+            // into the running program, so they are not kept.
+            let held = dispatcher.held.drain(..).map(|(event, _)| event);
+            snapshot.events.extend(held);
+            let carried = dispatcher.carried.drain(..);
+            snapshot
+                .carried
+                .extend(carried.map(|(event, target, _)| (event, target)));
+        }
+        if let Some(mut events) = world.get_resource_mut::<Events<E>>() {
+            let skip = events.len() - pending;
+            snapshot.events.extend(events.drain().skip(skip));
+        }
+        if let Some(mut delayed) = world.get_resource_mut::<DelayedEvents<E>>() {
+            snapshot.delayed.extend(delayed.drain());
+        }
+        snapshot
+    }
+
+    /// Map the entities of every event in the snapshot with `map`, and add them back to the
+    /// `world`, to be dispatched in the next frame. Events referring to an entity that `map`
+    /// returns `None` for are discarded.
+    pub fn restore(self, world: &mut World, map: &mut dyn FnMut(Entity) -> Option<Entity>) {
+        let mut carried = self
+            .carried
+            .into_iter()
+            .filter_map(|(mut event, target)| {
+                Some((
+                    map_event(&mut event, map).then_some(event)?,
+                    map(target)?,
+                    None,
+                ))
+            })
+            .collect();
+        match world.get_resource_mut::<EventDispatcher<E>>() {
+            Some(mut dispatcher) => dispatcher.carried.append(&mut carried),
+            // Without a dispatcher, there is no frame budget to carry the events over.
+            None => world.send_event_batch(carried.into_iter().map(|(event, ..)| event)),
+        }
+        let events = self
+            .events
+            .into_iter()
+            .filter_map(|mut event| map_event(&mut event, map).then_some(event));
+        world.send_event_batch(events);
+        let Some(mut delayed) = world.get_resource_mut::<DelayedEvents<E>>() else {
+            return;
+        };
+        for (mut event, delay) in self.delayed {
+            if map_event(&mut event, map) {
+                delayed.push(event, delay);
+            }
+        }
+    }
+}
+
+/// Map the entities of the `event`, returning `false` if it must be discarded.
+fn map_event<E: SnapshotEvent>(
+    event: &mut E,
+    map: &mut dyn FnMut(Entity) -> Option<Entity>,
+) -> bool {
+    if event.map_entities(map).is_some() {
+        return true;
+    }
+    #[cfg(feature = "trace")]
+    error!(
+        "Discarding restored event {:?} targeting {:?}, which refers to an entity that could not \
+        be mapped",
+        std::any::type_name::<E>(),
+        event.target(),
+    );
+    false
+}
@@ -0,0 +1,165 @@
+//! Records where events were sent from, so callbacks and debugging tools can tell apart the events
+//! of the same type sent by different systems.
+//!
+//! Events sent with [`SourcedEvents<E>`], or
+//! [`EventListenerWorldExt::send_event_from`](crate::EventListenerWorldExt::send_event_from), are
+//! tagged with an [`EventSource`]: either the location in the code the event was sent from, or a
+//! tag chosen by the sender. The source is exposed to listeners with
+//! [`ListenerInput::source`](crate::callbacks::ListenerInput::source). Events sent any other way
+//! have no source.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::ListenerInput,
+//! #     event_listener::{EntityEvent, On},
+//! #     source::SourcedEvents,
+//! #     EventListenerPlugin,
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # #[derive(Clone, Event)]
+//! # struct Damage {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Damage {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! # #[derive(Resource, Default)]
+//! # struct Sources(Vec<String>);
+//! # #[derive(Resource)]
+//! # struct Player(Entity);
+//! fn poison(player: Res<Player>, mut damage: SourcedEvents<Damage>) {
+//!     damage.send_from("poison", Damage { target: player.0 });
+//! }
+//!
+//! let mut app = App::new();
+//! app.add_plugins(EventListenerPlugin::<Damage>::default())
+//!     .init_resource::<Sources>()
+//!     .add_systems(Update, poison);
+//!
+//! let player = app
+//!     .world
+//!     .spawn(On::<Damage>::run(
+//!         |event: Res<ListenerInput<Damage>>, mut sources: ResMut<Sources>| {
+//!             sources.0.extend(event.source().map(|source| source.to_string()));
+//!         },
+//!     ))
+//!     .id();
+//! app.insert_resource(Player(player));
+//!
+//! app.update();
+//! app.update();
+//! assert_eq!(app.world.resource::<Sources>().0, ["poison"]);
+//! ```
+
+use std::{borrow::Cow, fmt, panic::Location};
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_utils::HashMap;
+
+use crate::event_listener::EntityEvent;
+
+/// Where an event was sent from, see the [module docs](self).
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum EventSource {
+    /// The location in the code the event was sent from.
+    Location(&'static Location<'static>),
+    /// A tag chosen by the sender, such as the name of the system or the subsystem.
+    Tag(Cow<'static, str>),
+}
+
+impl EventSource {
+    /// The source of an event sent from the location this is called from.
+    #[track_caller]
+    pub fn caller() -> Self {
+        Self::Location(Location::caller())
+    }
+}
+
+impl From<&'static str> for EventSource {
+    fn from(tag: &'static str) -> Self {
+        Self::Tag(tag.into())
+    }
+}
+
+impl From<String> for EventSource {
+    fn from(tag: String) -> Self {
+        Self::Tag(tag.into())
+    }
+}
+
+impl From<Cow<'static, str>> for EventSource {
+    fn from(tag: Cow<'static, str>) -> Self {
+        Self::Tag(tag)
+    }
+}
+
+impl fmt::Display for EventSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Location(location) => location.fmt(f),
+            Self::Tag(tag) => f.write_str(tag),
+        }
+    }
+}
+
+/// A resource storing the [`EventSource`] of the events of type `E` that were sent with a source
+/// and have not been dispatched yet. This is added by the
+/// [`EventListenerPlugin`](crate::EventListenerPlugin).
+#[derive(Resource)]
+pub struct EventSources<E> {
+    /// The source of each event, by event id.
+    sources: HashMap<usize, EventSource>,
+    phantom: std::marker::PhantomData<fn() -> E>,
+}
+
+impl<E> Default for EventSources<E> {
+    fn default() -> Self {
+        Self {
+            sources: HashMap::default(),
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<E: EntityEvent> EventSources<E> {
+    /// Send the `event` to the `events`, recording its `source`.
+    pub fn send(&mut self, events: &mut Events<E>, source: EventSource, event: E) {
+        self.sources
+            .insert(events.oldest_id() + events.len(), source);
+        events.send(event);
+    }
+
+    /// Take the source of the event with the id `id`, if it has one.
+    pub(crate) fn take(&mut self, id: usize) -> Option<EventSource> {
+        self.sources.remove(&id)
+    }
+
+    /// Forget the sources of the events that have already been read.
+    pub(crate) fn clear(&mut self) {
+        self.sources.clear();
+    }
+}
+
+/// A [`SystemParam`] that sends events of type `E` like an [`EventWriter<E>`], recording where
+/// they were sent from, see the [module docs](self).
+#[derive(SystemParam)]
+pub struct SourcedEvents<'w, E: EntityEvent> {
+    events: ResMut<'w, Events<E>>,
+    sources: ResMut<'w, EventSources<E>>,
+}
+
+impl<E: EntityEvent> SourcedEvents<'_, E> {
+    /// Send the `event`, with the location it was sent from as its source.
+    #[track_caller]
+    pub fn send(&mut self, event: E) {
+        self.send_from(EventSource::caller(), event);
+    }
+
+    /// Send the `event`, with the given `source`.
+    pub fn send_from(&mut self, source: impl Into<EventSource>, event: E) {
+        self.sources.send(&mut self.events, source.into(), event);
+    }
+}
@@ -0,0 +1,132 @@
+//! Targeting the events of other crates at entities.
+//!
+//! Events defined by bevy or by third-party crates, such as asset or window events, can't
+//! implement [`EntityEvent`]. The [`Targeted<E>`] wrapper pairs any event with a target entity, and
+//! the [`TargetedEventsPlugin<E>`] converts every event of type `E` with a target into a
+//! [`Targeted<E>`], which propagates through the listeners like any other event, without a newtype
+//! for each event type.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{
+//! #     callbacks::Listener,
+//! #     event_listener::On,
+//! #     targeted::{Targeted, TargetedEventsPlugin},
+//! # };
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! // An event from another crate, that is not an `EntityEvent`.
+//! #[derive(Clone, Event)]
+//! struct WindowResized {
+//!     window: Entity,
+//!     width: f32,
+//! }
+//!
+//! let mut app = App::new();
+//! app.add_event::<WindowResized>()
+//!     .add_plugins(TargetedEventsPlugin::new(|resized: &WindowResized| {
+//!         Some(resized.window)
+//!     }));
+//!
+//! let window = app
+//!     .world
+//!     .spawn(On::<Targeted<WindowResized>>::run(
+//!         |resized: Listener<Targeted<WindowResized>>| {
+//!             println!("resized to {}", resized.width);
+//!         },
+//!     ))
+//!     .id();
+//! app.world.send_event(WindowResized {
+//!     window,
+//!     width: 800.0,
+//! });
+//! app.update();
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+
+use crate::{event_listener::EntityEvent, EventDispatchSet, EventListenerPlugin};
+
+/// An event of type `E` that targets an entity, so it can propagate through listeners even though
+/// `E` is not an [`EntityEvent`]. The event can be accessed with the `event` field, or through
+/// [`Deref`](std::ops::Deref).
+#[derive(Event, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct Targeted<E> {
+    /// The entity targeted by the event.
+    pub target: Entity,
+    /// The wrapped event.
+    pub event: E,
+}
+
+impl<E> Targeted<E> {
+    /// Target the `event` at `target`.
+    pub fn new(target: Entity, event: E) -> Self {
+        Self { target, event }
+    }
+}
+
+impl<E: Event + Clone> EntityEvent for Targeted<E> {
+    fn target(&self) -> Entity {
+        self.target
+    }
+}
+
+impl<E> std::ops::Deref for Targeted<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.event
+    }
+}
+
+impl<E> std::ops::DerefMut for Targeted<E> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.event
+    }
+}
+
+/// Sends a [`Targeted<E>`] for every event of type `E` that has a target, and adds the
+/// [`EventListenerPlugin`] for [`Targeted<E>`], see the [module docs](self).
+///
+/// Events are converted in the [`PreUpdate`] schedule, right before the [`EventDispatchSet`] of
+/// [`Targeted<E>`], so events sent before then are dispatched in the same frame. The event `E`
+/// must be added to the app separately, usually by the crate that defines it.
+pub struct TargetedEventsPlugin<E> {
+    extract: fn(&E) -> Option<Entity>,
+}
+
+impl<E> TargetedEventsPlugin<E> {
+    /// Convert the events of type `E` into [`Targeted<E>`] events, targeting the entity returned by
+    /// `extract`. Events for which `extract` returns `None` are not converted.
+    pub fn new(extract: fn(&E) -> Option<Entity>) -> Self {
+        Self { extract }
+    }
+}
+
+impl<E: Event + Clone> Plugin for TargetedEventsPlugin<E> {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(TargetExtractor(self.extract))
+            .add_plugins(EventListenerPlugin::<Targeted<E>>::default())
+            .add_systems(
+                PreUpdate,
+                send_targeted::<E>.before(EventDispatchSet::<Targeted<E>>::default()),
+            );
+    }
+}
+
+/// The function used by a [`TargetedEventsPlugin`] to find the target of each event.
+#[derive(Resource)]
+struct TargetExtractor<E>(fn(&E) -> Option<Entity>);
+
+/// Send a [`Targeted<E>`] for every event of type `E` that has a target.
+fn send_targeted<E: Event + Clone>(
+    extract: Res<TargetExtractor<E>>,
+    mut events: EventReader<E>,
+    mut targeted: EventWriter<Targeted<E>>,
+) {
+    for event in events.iter() {
+        if let Some(target) = (extract.0)(event) {
+            targeted.send(Targeted::new(target, event.clone()));
+        }
+    }
+}
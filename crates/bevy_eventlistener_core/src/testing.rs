@@ -0,0 +1,170 @@
+//! Utilities for testing how events propagate to listeners.
+//!
+//! The [`TestApp`] trait adds helpers to [`App`] that send events, record the listeners they
+//! trigger, and make assertions on the recorded listeners:
+//!
+//! ```
+//! # use bevy_eventlistener_core::{event_listener::EntityEvent, testing::TestApp, EventListenerPlugin};
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_hierarchy::BuildWorldChildren;
+//! # #[derive(Clone, Event)]
+//! # struct Click {
+//! #     target: Entity,
+//! # }
+//! # impl EntityEvent for Click {
+//! #     fn target(&self) -> Entity {
+//! #         self.target
+//! #     }
+//! # }
+//! let mut app = App::new();
+//! app.add_plugins(EventListenerPlugin::<Click>::default());
+//!
+//! let parent = app.world.spawn_empty().id();
+//! let child = app.world.spawn_empty().set_parent(parent).id();
+//! app.record_listeners::<Click>(parent)
+//!     .record_listeners::<Click>(child)
+//!     .send_targeted(Click { target: child });
+//!
+//! app.assert_callback_fired::<Click>(child)
+//!     .assert_callback_fired::<Click>(parent);
+//! assert_eq!(app.recorded::<Click>()[0].listener, child);
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::{prelude::*, system::Command};
+
+use crate::{
+    callbacks::{EventPhase, Listener},
+    event_listener::{AddListener, EntityEvent, On},
+};
+
+/// A listener that was triggered while a [`ListenerRecorder`] was recording.
+#[derive(Clone, Debug)]
+pub struct ListenerRecord<E: EntityEvent> {
+    /// The entity the listener was on.
+    pub listener: Entity,
+    /// The entity the event was propagating from.
+    pub target: Entity,
+    /// The phase of propagation the listener was triggered in.
+    pub phase: EventPhase,
+    /// The event data, as it was when the listener was triggered.
+    pub event: E,
+}
+
+/// A resource storing every time a recording listener was triggered, in the order they were
+/// triggered. Recording listeners are created with [`ListenerRecorder::listener`].
+#[derive(Resource)]
+pub struct ListenerRecorder<E: EntityEvent> {
+    records: Vec<ListenerRecord<E>>,
+}
+
+impl<E: EntityEvent> Default for ListenerRecorder<E> {
+    fn default() -> Self {
+        Self {
+            records: Vec::new(),
+        }
+    }
+}
+
+impl<E: EntityEvent> ListenerRecorder<E> {
+    /// A listener that records every event it is triggered by in the [`ListenerRecorder<E>`]
+    /// resource, which must exist in the world.
+    pub fn listener() -> On<E> {
+        On::<E>::run(
+            |input: Listener<E>, mut recorder: ResMut<ListenerRecorder<E>>| {
+                recorder.records.push(ListenerRecord {
+                    listener: input.listener(),
+                    target: input.target(),
+                    phase: input.phase(),
+                    event: input.event_data.clone(),
+                });
+            },
+        )
+    }
+
+    /// The recorded listeners, in the order they were triggered.
+    pub fn records(&self) -> &[ListenerRecord<E>] {
+        &self.records
+    }
+
+    /// Remove all recorded listeners.
+    pub fn clear(&mut self) {
+        self.records.clear();
+    }
+}
+
+/// Extension trait for [`App`] with helpers for testing event listeners.
+pub trait TestApp {
+    /// Send an event, and update the app so it is dispatched.
+    fn send_targeted<E: EntityEvent>(&mut self, event: E) -> &mut Self;
+
+    /// Add a recording listener to `entity`, in addition to any listeners it already has. See
+    /// [`ListenerRecorder`].
+    fn record_listeners<E: EntityEvent>(&mut self, entity: Entity) -> &mut Self;
+
+    /// The listeners recorded so far, in the order they were triggered.
+    fn recorded<E: EntityEvent>(&self) -> &[ListenerRecord<E>];
+
+    /// Remove all recorded listeners for events of type `E`.
+    fn clear_recorded<E: EntityEvent>(&mut self) -> &mut Self;
+
+    /// Panics if the recording listener on `entity` has not been triggered by an event of type
+    /// `E`.
+    fn assert_callback_fired<E: EntityEvent>(&mut self, entity: Entity) -> &mut Self;
+
+    /// Panics if the recording listener on `entity` has been triggered by an event of type `E`.
+    fn assert_callback_not_fired<E: EntityEvent>(&mut self, entity: Entity) -> &mut Self;
+}
+
+impl TestApp for App {
+    fn send_targeted<E: EntityEvent>(&mut self, event: E) -> &mut Self {
+        self.world.send_event(event);
+        self.update();
+        self
+    }
+
+    fn record_listeners<E: EntityEvent>(&mut self, entity: Entity) -> &mut Self {
+        self.world.init_resource::<ListenerRecorder<E>>();
+        AddListener::new(entity, ListenerRecorder::<E>::listener()).apply(&mut self.world);
+        self
+    }
+
+    fn recorded<E: EntityEvent>(&self) -> &[ListenerRecord<E>] {
+        self.world
+            .get_resource::<ListenerRecorder<E>>()
+            .map_or(&[], ListenerRecorder::records)
+    }
+
+    fn clear_recorded<E: EntityEvent>(&mut self) -> &mut Self {
+        if let Some(mut recorder) = self.world.get_resource_mut::<ListenerRecorder<E>>() {
+            recorder.clear();
+        }
+        self
+    }
+
+    fn assert_callback_fired<E: EntityEvent>(&mut self, entity: Entity) -> &mut Self {
+        let records = self.recorded::<E>();
+        assert!(
+            records.iter().any(|record| record.listener == entity),
+            "expected a listener on {entity:?} to be triggered by {}, but it was not. Triggered \
+            listeners: {:?}",
+            std::any::type_name::<E>(),
+            records
+                .iter()
+                .map(|record| record.listener)
+                .collect::<Vec<_>>(),
+        );
+        self
+    }
+
+    fn assert_callback_not_fired<E: EntityEvent>(&mut self, entity: Entity) -> &mut Self {
+        let records = self.recorded::<E>();
+        assert!(
+            !records.iter().any(|record| record.listener == entity),
+            "expected no listener on {entity:?} to be triggered by {}, but one was",
+            std::any::type_name::<E>(),
+        );
+        self
+    }
+}
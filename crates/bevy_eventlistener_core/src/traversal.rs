@@ -0,0 +1,40 @@
+//! Relationships that events can bubble through.
+
+use bevy_ecs::prelude::*;
+use bevy_hierarchy::Parent;
+
+/// A component that describes the relationship events bubble through when they propagate up the
+/// hierarchy.
+///
+/// By default, events bubble up the [`Parent`] hierarchy. Implementing this trait for your own
+/// component allows events to bubble through any other relationship, like ownership, attachment,
+/// or a chain of command, using
+/// [`EventListenerPlugin::<E, T>`](crate::EventListenerPlugin). The event will be propagated from
+/// the target to the entity returned by [`Traversal::next`], and so on, until an entity without the
+/// component, or that returns `None`, is reached.
+///
+/// ```
+/// # use bevy_eventlistener_core::traversal::Traversal;
+/// # use bevy_ecs::prelude::*;
+/// /// The entity that owns this one.
+/// #[derive(Component)]
+/// struct Owner(Entity);
+///
+/// impl Traversal for Owner {
+///     fn next(&self) -> Option<Entity> {
+///         Some(self.0)
+///     }
+/// }
+/// ```
+///
+/// Relationships must not contain cycles, otherwise bubbling will never terminate.
+pub trait Traversal: Component {
+    /// The next entity the event should bubble to, if any.
+    fn next(&self) -> Option<Entity>;
+}
+
+impl Traversal for Parent {
+    fn next(&self) -> Option<Entity> {
+        Some(self.get())
+    }
+}
@@ -0,0 +1,139 @@
+//! Bubbling events for `bevy_ui` nodes, generated from changes to their [`Interaction`].
+//!
+//! Add the [`UiEventsPlugin`] to send a [`PressStart`], [`PressEnd`], [`Click`], [`HoverStart`]
+//! and [`HoverEnd`] event whenever the [`Interaction`] of a node changes. These events bubble up
+//! the UI hierarchy like any other [`EntityEvent`], so a listener on a parent node is triggered by
+//! interactions with any of its children that have an [`Interaction`] component.
+//!
+//! ```
+//! # use bevy_eventlistener_core::{event_listener::On, ui::{Click, UiEventsPlugin}};
+//! # use bevy_app::prelude::*;
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_ui::prelude::*;
+//! fn setup(mut commands: Commands) {
+//!     commands.spawn((
+//!         ButtonBundle::default(),
+//!         On::<Click>::run(|| println!("clicked!")),
+//!     ));
+//! }
+//!
+//! App::new()
+//!     .add_plugins(UiEventsPlugin)
+//!     .add_systems(Startup, setup);
+//! ```
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_ui::{Interaction, UiSystem};
+use bevy_utils::HashMap;
+
+use crate::{event_listener::EntityEvent, EventListenerPlugin, EventListenerSet};
+
+/// Adds an [`EventListenerPlugin`] for each of the events in this module, and sends them when the
+/// [`Interaction`] of a UI node changes.
+///
+/// The events are sent in the [`PreUpdate`] schedule, after [`UiSystem::Focus`], and dispatched in
+/// the same frame.
+pub struct UiEventsPlugin;
+
+impl Plugin for UiEventsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
+            EventListenerPlugin::<PressStart>::default(),
+            EventListenerPlugin::<PressEnd>::default(),
+            EventListenerPlugin::<Click>::default(),
+            EventListenerPlugin::<HoverStart>::default(),
+            EventListenerPlugin::<HoverEnd>::default(),
+        ))
+        .add_systems(
+            PreUpdate,
+            send_interaction_events
+                .after(UiSystem::Focus)
+                .before(EventListenerSet),
+        );
+    }
+}
+
+macro_rules! ui_event {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Clone, Copy, PartialEq, Eq, Debug, Event)]
+        pub struct $name {
+            /// The UI node whose [`Interaction`] changed.
+            pub target: Entity,
+        }
+
+        impl EntityEvent for $name {
+            fn target(&self) -> Entity {
+                self.target
+            }
+        }
+    };
+}
+
+ui_event!(
+    /// Sent when a UI node starts being pressed.
+    PressStart
+);
+ui_event!(
+    /// Sent when a UI node stops being pressed, whether or not the pointer is still over it.
+    PressEnd
+);
+ui_event!(
+    /// Sent when a UI node stops being pressed while the pointer is still over it, after its
+    /// [`PressEnd`].
+    Click
+);
+ui_event!(
+    /// Sent when the pointer starts hovering a UI node.
+    HoverStart
+);
+ui_event!(
+    /// Sent when the pointer stops hovering a UI node, after its [`PressEnd`] if it was pressed.
+    HoverEnd
+);
+
+/// Send the events for every UI node whose [`Interaction`] changed since the last time this system
+/// ran.
+///
+/// `bevy_ui` keeps a node [`Interaction::Pressed`] until the mouse button is released, even if the
+/// pointer leaves it. The node then becomes [`Interaction::Hovered`] if the pointer is still over
+/// it, which is sent as a [`Click`], or [`Interaction::None`] otherwise.
+#[allow(clippy::too_many_arguments)]
+pub fn send_interaction_events(
+    interactions: Query<(Entity, &Interaction), Changed<Interaction>>,
+    mut removed: RemovedComponents<Interaction>,
+    mut previous: Local<HashMap<Entity, Interaction>>,
+    mut press_start: EventWriter<PressStart>,
+    mut press_end: EventWriter<PressEnd>,
+    mut click: EventWriter<Click>,
+    mut hover_start: EventWriter<HoverStart>,
+    mut hover_end: EventWriter<HoverEnd>,
+) {
+    for entity in removed.iter() {
+        previous.remove(&entity);
+    }
+    for (target, interaction) in &interactions {
+        let old = previous
+            .insert(target, *interaction)
+            .unwrap_or(Interaction::None);
+        match (old, *interaction) {
+            (Interaction::None, Interaction::Hovered) => hover_start.send(HoverStart { target }),
+            (Interaction::None, Interaction::Pressed) => {
+                hover_start.send(HoverStart { target });
+                press_start.send(PressStart { target });
+            }
+            (Interaction::Hovered, Interaction::Pressed) => press_start.send(PressStart { target }),
+            (Interaction::Pressed, Interaction::Hovered) => {
+                press_end.send(PressEnd { target });
+                click.send(Click { target });
+            }
+            (Interaction::Pressed, Interaction::None) => {
+                press_end.send(PressEnd { target });
+                hover_end.send(HoverEnd { target });
+            }
+            (Interaction::Hovered, Interaction::None) => hover_end.send(HoverEnd { target }),
+            _ => {}
+        }
+    }
+}
@@ -1,48 +1,255 @@
 use proc_macro::TokenStream;
-use quote::quote;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
 
-#[proc_macro_derive(EntityEvent, attributes(target))]
+/// Derives `EntityEvent` for a struct or enum.
+///
+/// The field containing the target entity must be marked with `#[target]`. For enums, every
+/// variant must mark its own target field. The target field can also be an `Option<Entity>`, in
+/// which case events without a target are only delivered to the global listeners, see
+/// `EntityEvent::target`.
+///
+/// The `#[entity_event(..)]` attribute can be used on the type to configure how the event
+/// propagates:
+/// - `auto_propagate = false`: the event does not bubble up the hierarchy, see
+///   `EntityEvent::can_bubble`.
+#[proc_macro_derive(EntityEvent, attributes(target, entity_event))]
 pub fn derive(input: TokenStream) -> TokenStream {
-    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+    let ast: syn::DeriveInput = syn::parse_macro_input!(input);
+    match expand(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+fn expand(ast: &syn::DeriveInput) -> syn::Result<TokenStream2> {
     let name = &ast.ident;
     let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
-    let mut target = None;
-
-    match ast.data {
-        // Only process structs
+    let target = match ast.data {
         syn::Data::Struct(ref data_struct) => {
-            // Check the kind of fields the struct contains
-            // Structs with named fields
-            if let syn::Fields::Named(ref fields_named) = data_struct.fields {
-                // Iterate over the fields
-                for field in fields_named.named.iter() {
-                    // Get attributes #[..] on each field
-                    for attr in field.attrs.iter() {
-                        // Parse the attribute
-                        match attr.meta {
-                            // Find the duplicated idents
-                            syn::Meta::Path(ref path) if path.get_ident().unwrap() == "target" => {
-                                target = Some(field.ident.clone());
-                            }
-                            _ => (),
-                        }
-                    }
+            let (pattern, target) = target_field(&data_struct.fields, ast.ident.span())?;
+            quote! {
+                let Self #pattern = self;
+                #target
+            }
+        }
+        syn::Data::Enum(ref data_enum) => {
+            let arms = data_enum
+                .variants
+                .iter()
+                .map(|variant| {
+                    let variant_name = &variant.ident;
+                    let (pattern, target) = target_field(&variant.fields, variant.span())?;
+                    Ok(quote! { Self::#variant_name #pattern => #target, })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                match self {
+                    #(#arms)*
                 }
             }
         }
-        // Panic when we don't have a struct
-        _ => panic!("Must be a struct"),
+        syn::Data::Union(ref data_union) => {
+            return Err(syn::Error::new(
+                data_union.union_token.span(),
+                "EntityEvent cannot be derived for unions",
+            ))
+        }
+    };
+
+    let can_bubble = match auto_propagate(&ast.attrs)? {
+        Some(auto_propagate) => quote! {
+            fn can_bubble(&self) -> bool {
+                #auto_propagate
+            }
+        },
+        None => quote!(),
+    };
+
+    Ok(quote! {
+        impl #impl_generics EntityEvent for #name #ty_generics #where_clause {
+            fn target(&self) -> Entity {
+                #target
+            }
+
+            #can_bubble
+        }
+    })
+}
+
+/// Derives `EventFamily` and `EntityEvent` for an enum wrapping several events.
+///
+/// Every variant must have a single unnamed field, holding an event that implements
+/// `EntityEvent`. The enum targets and propagates like the event in each variant, and also
+/// implements `From` for each of them. See the `family` module of `bevy_eventlistener_core`.
+///
+/// The generated code expects the contents of the `bevy_eventlistener` prelude to be in scope.
+#[proc_macro_derive(EventFamily)]
+pub fn derive_family(input: TokenStream) -> TokenStream {
+    let ast: syn::DeriveInput = syn::parse_macro_input!(input);
+    match expand_family(&ast) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
     }
+}
+
+fn expand_family(ast: &syn::DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &ast.ident;
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
 
-    let target = target.unwrap();
+    let syn::Data::Enum(ref data_enum) = ast.data else {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "EventFamily can only be derived for enums",
+        ));
+    };
+    if data_enum.variants.is_empty() {
+        return Err(syn::Error::new(
+            ast.ident.span(),
+            "EventFamily requires at least one variant",
+        ));
+    }
+    let members = data_enum
+        .variants
+        .iter()
+        .map(|variant| match variant.fields {
+            syn::Fields::Unnamed(ref fields) if fields.unnamed.len() == 1 => {
+                Ok((&variant.ident, &fields.unnamed[0].ty))
+            }
+            _ => Err(syn::Error::new(
+                variant.span(),
+                "each variant of an EventFamily must wrap a single event, like `Click(Click)`",
+            )),
+        })
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let variants: Vec<_> = members.iter().map(|(variant, _)| *variant).collect();
+    let types: Vec<_> = members.iter().map(|(_, ty)| *ty).collect();
+    let delegate = |method: TokenStream2| {
+        quote! {
+            match self {
+                #(Self::#variants(__event) => EntityEvent::#method(__event),)*
+            }
+        }
+    };
+    let target = delegate(quote!(target));
+    let targets = delegate(quote!(targets));
+    let can_bubble = delegate(quote!(can_bubble));
+    let propagation = delegate(quote!(propagation));
 
-    let gen = quote! {
+    Ok(quote! {
         impl #impl_generics EntityEvent for #name #ty_generics #where_clause {
             fn target(&self) -> Entity {
-                self.#target
+                #target
+            }
+
+            fn targets(&self) -> EventTargets {
+                #targets
+            }
+
+            fn can_bubble(&self) -> bool {
+                #can_bubble
+            }
+
+            fn propagation(&self) -> Propagation {
+                #propagation
             }
         }
+
+        impl #impl_generics EventFamily for #name #ty_generics #where_clause {
+            fn add_members(members: &mut FamilyMembers) {
+                #(members.add::<#types>();)*
+            }
+
+            fn send_member(&self, sender: &mut MemberSender) {
+                match self {
+                    #(Self::#variants(__event) => sender.send(__event),)*
+                }
+            }
+        }
+
+        #(
+            impl #impl_generics From<#types> for #name #ty_generics #where_clause {
+                fn from(event: #types) -> Self {
+                    Self::#variants(event)
+                }
+            }
+        )*
+    })
+}
+
+/// Find the field marked with `#[target]`, returning a pattern that binds it, and an expression
+/// for the target entity, using the binding.
+fn target_field(
+    fields: &syn::Fields,
+    span: proc_macro2::Span,
+) -> syn::Result<(TokenStream2, TokenStream2)> {
+    let mut targets = fields.iter().enumerate().filter(|(_, field)| {
+        field
+            .attrs
+            .iter()
+            .any(|attr| attr.path().is_ident("target"))
+    });
+    let Some((index, field)) = targets.next() else {
+        return Err(syn::Error::new(
+            span,
+            "EntityEvent requires a field marked with #[target]",
+        ));
+    };
+    if let Some((_, duplicate)) = targets.next() {
+        return Err(syn::Error::new(
+            duplicate.span(),
+            "only one field can be marked with #[target]",
+        ));
+    }
+
+    let binding = format_ident!("__target");
+    let pattern = match field.ident {
+        Some(ref ident) => quote! { { #ident: #binding, .. } },
+        None => {
+            let skipped = (0..index).map(|_| quote!(_));
+            quote! { ( #(#skipped,)* #binding, .. ) }
+        }
+    };
+    let target = if is_option(&field.ty) {
+        quote! { (*#binding).unwrap_or(Entity::PLACEHOLDER) }
+    } else {
+        quote! { *#binding }
     };
-    gen.into()
+    Ok((pattern, target))
+}
+
+/// Is the type of the target field an `Option`? Only the last segment of its path is checked, so
+/// this matches both `Option<Entity>` and `std::option::Option<Entity>`.
+fn is_option(ty: &syn::Type) -> bool {
+    let syn::Type::Path(path) = ty else {
+        return false;
+    };
+    path.qself.is_none()
+        && path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "Option")
+}
+
+/// Parse the `auto_propagate` option of the `#[entity_event(..)]` attribute, if it is present.
+fn auto_propagate(attrs: &[syn::Attribute]) -> syn::Result<Option<syn::LitBool>> {
+    let mut auto_propagate = None;
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("entity_event"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("auto_propagate") {
+                auto_propagate = Some(meta.value()?.parse()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported entity_event attribute, expected `auto_propagate`"))
+            }
+        })?;
+    }
+    Ok(auto_propagate)
 }
@@ -50,6 +50,82 @@
 //! # }
 //! ```
 //!
+//! ## Deriving events
+//!
+//! [`EntityEvent`](prelude::EntityEvent) can be derived for structs and enums, by marking the field
+//! that holds the target entity with `#[target]`. Each variant of an enum marks its own target.
+//! The `#[entity_event(auto_propagate = false)]` attribute prevents the event from bubbling up the
+//! hierarchy.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! use bevy_eventlistener::prelude::*;
+//!
+//! #[derive(Clone, Event, EntityEvent)]
+//! struct Hit {
+//!     #[target]
+//!     victim: Entity,
+//!     damage: f32,
+//! }
+//!
+//! #[derive(Clone, Event, EntityEvent)]
+//! #[entity_event(auto_propagate = false)]
+//! enum Interaction {
+//!     Hover(#[target] Entity),
+//!     Press {
+//!         #[target]
+//!         button: Entity,
+//!         pressure: f32,
+//!     },
+//! }
+//!
+//! let entity = Entity::from_raw(7);
+//! let hit = Hit { victim: entity, damage: 1.0 };
+//! assert_eq!(hit.target(), entity);
+//! assert!(hit.can_bubble());
+//! let press = Interaction::Press { button: entity, pressure: 0.5 };
+//! assert_eq!(press.target(), entity);
+//! assert!(!press.can_bubble());
+//! ```
+//!
+//! A family of related events can be wrapped in an enum deriving `EventFamily`, with a variant for
+//! each event. An [`EventFamilyPlugin`](prelude::EventFamilyPlugin) dispatches each family event to
+//! the listeners of the enum, and then to the listeners of the event it wraps.
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! use bevy_eventlistener::prelude::*;
+//!
+//! # #[derive(Clone, Event, EntityEvent)]
+//! # struct Click {
+//! #     #[target]
+//! #     target: Entity,
+//! # }
+//! # #[derive(Clone, Event, EntityEvent)]
+//! # struct Hover {
+//! #     #[target]
+//! #     target: Entity,
+//! # }
+//! #[derive(Clone, Event, EventFamily)]
+//! enum UiEvent {
+//!     Click(Click),
+//!     Hover(Hover),
+//! }
+//!
+//! # #[derive(Resource, Default)]
+//! # struct Clicks(usize);
+//! let mut app = App::new();
+//! app.add_plugins(EventFamilyPlugin::<UiEvent>::default());
+//! # app.init_resource::<Clicks>();
+//! let button = app
+//!     .world
+//!     .spawn(On::<Click>::run(|mut clicks: ResMut<Clicks>| clicks.0 += 1))
+//!     .id();
+//! app.world.send_event(UiEvent::from(Click { target: button }));
+//! app.update();
+//! assert_eq!(app.world.resource::<Clicks>().0, 1);
+//! ```
+//!
 //! ## UI
 //!
 //! This library is intended to be upstreamed to bevy for use in making interactive UI. However, as
@@ -68,6 +144,7 @@
 //! - Depth: 64 (how many levels of children for an entity at the root)
 //! - Total nodes: 12,800 (total number of entities spawned)
 //! - Listener density: 20% (what percent of entities have event listeners?)
+//!
 //! ![image](https://github.com/aevyrie/bevy_eventlistener/assets/2632925/72f75640-8b44-4ace-af67-9898c4c78321)
 //!
 //! The blue line can be read as "how long does it take all of these events to bubble up a hierarchy
@@ -81,14 +158,35 @@
 //! could find.
 
 pub use bevy_eventlistener_core::*;
-pub use bevy_eventlistener_derive::EntityEvent;
+pub use bevy_eventlistener_derive::{EntityEvent, EventFamily};
 
 /// Common exports
 pub mod prelude {
     pub use bevy_eventlistener_core::{
-        callbacks::{Listener, ListenerInput, ListenerMut},
-        event_listener::{EntityEvent, On},
-        EventListenerPlugin,
+        callback_errors::{CallbackError, CallbackErrorHandler, OnPanic, PanicIsolation},
+        callback_registry::{CallbackRegistry, NamedListener},
+        callbacks::{
+            EventPhase, Listener, ListenerBatch, ListenerInput, ListenerInputs, ListenerMut,
+            ListenerResponses, SharedCallback, SharedCallbacks,
+        },
+        delayed::DelayedEvents,
+        event_dispatcher::{
+            Coalesce, DispatchNow, DispatchedEvent, EventDispatchControl, EventOrder,
+            ForwardEvents, QueueOverflow,
+        },
+        event_listener::{
+            AddListener, DefaultAction, EntityEvent, EventTargets, GlobalListeners, ListenerGroups,
+            ListenerHandle, Listeners, On, Propagation, PropagationBarrier, RemoveListener,
+        },
+        family::{EventFamily, EventFamilyPlugin, FamilyMembers, MemberSender},
+        hierarchy::{ChildAdded, ChildRemoved, HierarchyEventsPlugin, ParentChanged},
+        hooks::EventHooks,
+        notifications::{ListenerAdded, ListenerRemoved},
+        source::{EventSource, SourcedEvents},
+        targeted::{Targeted, TargetedEventsPlugin},
+        traversal::Traversal,
+        EventDispatchSet, EventListenerAppExt, EventListenerCommandsExt, EventListenerEntityExt,
+        EventListenerPlugin, EventListenerPlugins, EventListenerSet, EventListenerWorldExt,
     };
-    pub use bevy_eventlistener_derive::EntityEvent;
+    pub use bevy_eventlistener_derive::{EntityEvent, EventFamily};
 }